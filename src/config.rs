@@ -4,6 +4,8 @@ use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
+use crate::managers::equalizer::EQ_BANDS;
+
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
     pub static ref DATA_FOLDER: Option<PathBuf> =
@@ -26,47 +28,252 @@ pub fn get_data_dir() -> PathBuf {
     }
 }
 
+pub fn get_config_dir() -> PathBuf {
+    if let Some(s) = CONFIG_FOLDER.clone() {
+        s
+    } else if let Some(proj_dirs) = project_directory() {
+        proj_dirs.config_local_dir().to_path_buf()
+    } else {
+        PathBuf::from(".").join(".config")
+    }
+}
+
 fn project_directory() -> Option<ProjectDirs> {
     ProjectDirs::from("com", "malgra", env!("CARGO_PKG_NAME"))
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum SeekbarType {
+    #[default]
     Waveform,
     Slider,
+    Spectrum,
 }
 
-impl Default for SeekbarType {
-    fn default() -> Self {
-        Self::Waveform
+impl SeekbarType {
+    pub const ALL: [SeekbarType; 3] = [
+        SeekbarType::Waveform,
+        SeekbarType::Slider,
+        SeekbarType::Spectrum,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SeekbarType::Waveform => "Waveform",
+            SeekbarType::Slider => "Slider",
+            SeekbarType::Spectrum => "Spectrum visualizer",
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// How much one mouse-wheel notch over the artwork changes the volume
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VolumeStep {
+    Fine,
+    #[default]
+    Normal,
+    Coarse,
+}
+
+impl VolumeStep {
+    pub const ALL: [VolumeStep; 3] = [VolumeStep::Fine, VolumeStep::Normal, VolumeStep::Coarse];
+
+    /// The step as a fraction of full volume
+    pub fn amount(&self) -> f32 {
+        match self {
+            VolumeStep::Fine => 0.02,
+            VolumeStep::Normal => 0.05,
+            VolumeStep::Coarse => 0.10,
+        }
+    }
+}
+
+impl std::fmt::Display for VolumeStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", (self.amount() * 100.0).round())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub enum RepeatMode {
+    #[default]
     All,
     One,
+    Off,
 }
 
-impl Default for RepeatMode {
-    fn default() -> Self {
-        Self::All
+impl RepeatMode {
+    pub fn toggle(&self) -> Self {
+        match self {
+            RepeatMode::All => RepeatMode::One,
+            RepeatMode::One => RepeatMode::Off,
+            RepeatMode::Off => RepeatMode::All,
+        }
     }
 }
 
-impl RepeatMode {
+/// Colour scheme, flipped with the day/night button in the playback bar
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AppTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl AppTheme {
     pub fn toggle(&self) -> Self {
         match self {
-            RepeatMode::All => RepeatMode::One,
-            RepeatMode::One => RepeatMode::All,
+            AppTheme::Dark => AppTheme::Light,
+            AppTheme::Light => AppTheme::Dark,
         }
     }
+
+    pub fn iced_theme(&self) -> iced::Theme {
+        match self {
+            AppTheme::Dark => iced::Theme::CatppuccinMocha,
+            AppTheme::Light => iced::Theme::CatppuccinLatte,
+        }
+    }
+}
+
+/// Page shown after signing in
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StartPage {
+    #[default]
+    Likes,
+    Feed,
+    Search,
+}
+
+impl StartPage {
+    pub const ALL: [StartPage; 3] = [StartPage::Likes, StartPage::Feed, StartPage::Search];
 }
 
+impl std::fmt::Display for StartPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StartPage::Likes => "Likes",
+            StartPage::Feed => "Feed",
+            StartPage::Search => "Search",
+        })
+    }
+}
+
+/// Client-side order for the likes and feed lists
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TrackSort {
+    #[default]
+    Unsorted, // As the API returned them, e.g. most recently liked first
+    Newest,
+    Oldest,
+    TitleAsc,
+}
+
+impl TrackSort {
+    pub const ALL: [TrackSort; 4] = [
+        TrackSort::Unsorted,
+        TrackSort::Newest,
+        TrackSort::Oldest,
+        TrackSort::TitleAsc,
+    ];
+}
+
+impl std::fmt::Display for TrackSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TrackSort::Unsorted => "Default",
+            TrackSort::Newest => "Newest",
+            TrackSort::Oldest => "Oldest",
+            TrackSort::TitleAsc => "Title A–Z",
+        })
+    }
+}
+
+/// UI tick interval while playing when none is set, and the allowed range
+pub const DEFAULT_UI_TICK_MS: u64 = 100;
+pub const UI_TICK_MS_RANGE: std::ops::RangeInclusive<u64> = 50..=1000;
+
+/// Zoom range for the whole UI, and the change per zoom in/out
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.0;
+pub const UI_SCALE_STEP: f32 = 0.1;
+
+/// Longest track (in seconds) whose decoded samples repeat one keeps when none
+/// is set, and the most that can be set. Only recorded while repeat one is
+/// on; at about 21 MB per minute of decoded audio the default costs up to
+/// ~85 MB for the track playing.
+pub const DEFAULT_REPEAT_CACHE_SECS: u32 = 240;
+pub const MAX_REPEAT_CACHE_SECS: u32 = 600;
+
+// Missing keys fall back to their defaults, so settings files written by
+// older versions keep loading as new options are added
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct AppSettings {
     pub seekbar_type: SeekbarType,
     pub repeat_mode: RepeatMode,
+    pub show_remaining: bool, // Show -MM:SS remaining instead of the total duration
+    pub autoplay: bool,       // With repeat off, keep playing related tracks after the queue
+    pub eq_gains: [f32; EQ_BANDS], // Equalizer band gains in dB, lowest band first
+    pub normalize_volume: bool, // Even out loudness differences between tracks
+    pub wrap_queue: bool,     // Next/previous loop around the ends of the queue
+    pub output_device: Option<String>, // Audio output device name, None for the system default
+    pub track_sort: TrackSort, // Order of the likes and feed lists
+    pub discord_presence: bool, // Show the current track as Discord rich presence
+    pub volume_step: VolumeStep, // Volume change per wheel notch over the artwork
+    pub show_rate_limit: bool, // Warn when few SoundCloud API requests are left
+    pub feed_autorefresh_mins: Option<u32>, // Reload the feed this often while it's open; None is off
+    pub ui_tick_ms: Option<u64>, // UI update interval while playing; None for DEFAULT_UI_TICK_MS
+    pub default_page: StartPage, // Page shown after signing in
+    pub remember_last_page: bool, // Open on last_page instead of default_page
+    pub last_page: Option<StartPage>, // Likes, feed or search page last visited
+    pub repeat_cache_secs: Option<u32>, // Longest track repeat one restarts from decoded audio; 0 is off
+    pub hide_blocked: bool,             // Leave tracks blocked in the user's region out of lists
+    pub theme: AppTheme,
+    pub ui_scale: Option<f32>, // Zoom of the whole UI; None for 100%
+}
+
+impl AppSettings {
+    /// How often the UI updates while playing, clamped to `UI_TICK_MS_RANGE`
+    pub fn ui_tick_interval(&self) -> std::time::Duration {
+        let ms = self
+            .ui_tick_ms
+            .unwrap_or(DEFAULT_UI_TICK_MS)
+            .clamp(*UI_TICK_MS_RANGE.start(), *UI_TICK_MS_RANGE.end());
+        std::time::Duration::from_millis(ms)
+    }
+
+    /// UI zoom factor, clamped to `UI_SCALE_RANGE`
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale
+            .unwrap_or(1.0)
+            .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end())
+    }
+
+    /// Zoom the UI `steps` steps of `UI_SCALE_STEP` in (or out when negative)
+    pub fn zoom(&mut self, steps: i32) {
+        let scale = self.ui_scale() + steps as f32 * UI_SCALE_STEP;
+        // Round away float drift so repeated steps land on whole percents
+        let scale = (scale * 100.0).round() / 100.0;
+        self.ui_scale = Some(scale.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end()));
+    }
+
+    /// Page to open after signing in
+    pub fn landing_page(&self) -> StartPage {
+        match self.last_page {
+            Some(page) if self.remember_last_page => page,
+            _ => self.default_page,
+        }
+    }
+
+    /// Longest track whose decoded samples are kept for an instant repeat, or
+    /// None when that's turned off
+    pub fn repeat_cache_limit(&self) -> Option<std::time::Duration> {
+        let secs = self
+            .repeat_cache_secs
+            .unwrap_or(DEFAULT_REPEAT_CACHE_SECS)
+            .min(MAX_REPEAT_CACHE_SECS);
+        (secs > 0).then(|| std::time::Duration::from_secs(secs.into()))
+    }
 }
 
 pub fn get_settings_path() -> PathBuf {
@@ -110,3 +317,180 @@ pub fn save_settings(settings: &AppSettings) -> Result<(), Box<dyn std::error::E
 
     Ok(())
 }
+
+/// Smallest restored window size; anything smaller was saved by mistake
+const MIN_WINDOW_WIDTH: f32 = 400.0;
+const MIN_WINDOW_HEIGHT: f32 = 300.0;
+/// Largest plausible window extent or coordinate on any desktop
+const MAX_WINDOW_EXTENT: f32 = 16384.0;
+/// How much of the window must stay reachable when restoring its position
+const MIN_VISIBLE_WIDTH: f32 = 100.0;
+
+/// Last window size and position, restored on the next launch. Logical
+/// pixels, as iced reports them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    pub x: Option<f32>, // None on platforms that don't report positions (Wayland)
+    pub y: Option<f32>,
+}
+
+impl WindowGeometry {
+    /// Sanity-check saved geometry: None if the size is unusable, and the
+    /// position dropped (so the OS places the window) if it would leave the
+    /// title bar on none of `monitors`, e.g. on one that's been unplugged.
+    /// An empty `monitors` means their bounds aren't known, so any position
+    /// is kept.
+    pub fn validated(self, monitors: &[iced::Rectangle]) -> Option<Self> {
+        let size_ok =
+            |value: f32, min: f32| value.is_finite() && (min..=MAX_WINDOW_EXTENT).contains(&value);
+        if !size_ok(self.width, MIN_WINDOW_WIDTH) || !size_ok(self.height, MIN_WINDOW_HEIGHT) {
+            return None;
+        }
+
+        let position_ok = match (self.x, self.y) {
+            (Some(x), Some(y)) => {
+                x.is_finite()
+                    && y.is_finite()
+                    && (monitors.is_empty()
+                        || monitors.iter().any(|monitor| {
+                            let visible =
+                                (x + self.width).min(monitor.x + monitor.width) - x.max(monitor.x);
+                            visible >= MIN_VISIBLE_WIDTH
+                                && (monitor.y..=monitor.y + monitor.height - MIN_VISIBLE_WIDTH)
+                                    .contains(&y)
+                        }))
+            }
+            _ => false,
+        };
+        Some(if position_ok {
+            self
+        } else {
+            Self {
+                x: None,
+                y: None,
+                ..self
+            }
+        })
+    }
+}
+
+fn get_window_geometry_path() -> PathBuf {
+    get_config_dir().join("window.toml")
+}
+
+/// The saved window geometry, if there is a usable one
+pub fn load_window_geometry() -> Option<WindowGeometry> {
+    let contents = fs::read_to_string(get_window_geometry_path()).ok()?;
+    toml::from_str::<WindowGeometry>(&contents)
+        .inspect_err(|e| eprintln!("Failed to parse window geometry: {}. Using defaults.", e))
+        .ok()?
+        .validated(&crate::utilities::monitor_bounds())
+}
+
+pub fn save_window_geometry(geometry: &WindowGeometry) -> Result<(), Box<dyn std::error::Error>> {
+    let path = get_window_geometry_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, toml::to_string_pretty(geometry)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(width: f32, height: f32, x: f32, y: f32) -> WindowGeometry {
+        WindowGeometry {
+            width,
+            height,
+            x: Some(x),
+            y: Some(y),
+        }
+    }
+
+    #[test]
+    fn ui_tick_interval_defaults_and_clamps() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.ui_tick_interval().as_millis(), 100);
+        settings.ui_tick_ms = Some(5);
+        assert_eq!(settings.ui_tick_interval().as_millis(), 50);
+        settings.ui_tick_ms = Some(60_000);
+        assert_eq!(settings.ui_tick_interval().as_millis(), 1000);
+    }
+
+    #[test]
+    fn zoom_steps_within_range() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.ui_scale(), 1.0);
+        settings.zoom(3);
+        assert_eq!(settings.ui_scale(), 1.3);
+        settings.zoom(-20);
+        assert_eq!(settings.ui_scale(), 0.75);
+        settings.ui_scale = Some(10.0);
+        assert_eq!(settings.ui_scale(), 2.0);
+    }
+
+    #[test]
+    fn landing_page_prefers_last_page_when_remembered() {
+        let mut settings = AppSettings {
+            default_page: StartPage::Feed,
+            ..AppSettings::default()
+        };
+        assert_eq!(settings.landing_page(), StartPage::Feed);
+        settings.remember_last_page = true;
+        assert_eq!(settings.landing_page(), StartPage::Feed);
+        settings.last_page = Some(StartPage::Search);
+        assert_eq!(settings.landing_page(), StartPage::Search);
+        settings.remember_last_page = false;
+        assert_eq!(settings.landing_page(), StartPage::Feed);
+    }
+
+    #[test]
+    fn repeat_cache_limit_defaults_and_turns_off() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.repeat_cache_limit().unwrap().as_secs(), 240);
+        settings.repeat_cache_secs = Some(0);
+        assert!(settings.repeat_cache_limit().is_none());
+        settings.repeat_cache_secs = Some(3600);
+        assert_eq!(settings.repeat_cache_limit().unwrap().as_secs(), 600);
+    }
+
+    #[test]
+    fn validated_rejects_bad_sizes_and_drops_offscreen_positions() {
+        // A primary monitor with a second one to its left
+        let monitors = [
+            iced::Rectangle::new(iced::Point::ORIGIN, iced::Size::new(1920.0, 1080.0)),
+            iced::Rectangle::new(
+                iced::Point::new(-1280.0, 0.0),
+                iced::Size::new(1280.0, 1024.0),
+            ),
+        ];
+        let good = geometry(1024.0, 768.0, 200.0, 100.0);
+        assert_eq!(good.validated(&monitors), Some(good));
+        let on_left_monitor = geometry(1024.0, 768.0, -1200.0, 100.0);
+        assert_eq!(on_left_monitor.validated(&monitors), Some(on_left_monitor));
+
+        assert_eq!(geometry(10.0, 768.0, 0.0, 0.0).validated(&monitors), None);
+        assert_eq!(
+            geometry(f32::NAN, 768.0, 0.0, 0.0).validated(&monitors),
+            None
+        );
+
+        // Title bar above the desktop, or the window pushed off the left edge
+        let above = geometry(1024.0, 768.0, 200.0, -50.0)
+            .validated(&monitors)
+            .unwrap();
+        assert_eq!((above.x, above.y), (None, None));
+        let left = geometry(1024.0, 768.0, -3000.0, 100.0)
+            .validated(&monitors)
+            .unwrap();
+        assert_eq!((left.x, left.y), (None, None));
+
+        // Without monitor bounds the window system decides
+        let unknown = geometry(1024.0, 768.0, -3000.0, 100.0);
+        assert_eq!(unknown.validated(&[]), Some(unknown));
+    }
+}
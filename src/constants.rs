@@ -1,6 +1,12 @@
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::fs;
 use std::path::PathBuf;
+use std::sync::RwLock;
+use url::Url;
+
+use crate::config;
 
 fn load_dotenv() {
     // Try to load .env from current directory first
@@ -45,20 +51,110 @@ fn load_dotenv() {
     }
 }
 
+pub const DEFAULT_REDIRECT_URL: &str = "http://localhost:32857/";
+
+/// The registered SoundCloud app Rustwave signs in as
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Credentials {
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "default_redirect_url")]
+    pub redirect_url: String,
+}
+
+fn default_redirect_url() -> String {
+    DEFAULT_REDIRECT_URL.to_string()
+}
+
+impl Credentials {
+    /// Build credentials from user input, trimming whitespace and rejecting
+    /// empty fields or a redirect URL that won't parse
+    pub fn new(client_id: &str, client_secret: &str, redirect_url: &str) -> Result<Self, String> {
+        let client_id = client_id.trim();
+        let client_secret = client_secret.trim();
+        let redirect_url = match redirect_url.trim() {
+            "" => DEFAULT_REDIRECT_URL,
+            url => url,
+        };
+
+        if client_id.is_empty() || client_secret.is_empty() {
+            return Err("Both the client ID and client secret are required".to_string());
+        }
+        if Url::parse(redirect_url).is_err() {
+            return Err(format!("\"{}\" isn't a valid redirect URL", redirect_url));
+        }
+
+        Ok(Self {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_url: redirect_url.to_string(),
+        })
+    }
+}
+
 lazy_static! {
-    pub static ref CLIENT_ID: String = {
+    /// Discord application Rich Presence is published under; presence is
+    /// unavailable without one
+    pub static ref DISCORD_APP_ID: Option<String> = {
         load_dotenv();
-        env::var("CLIENT_ID").expect("CLIENT_ID must be set in .env file")
-    };
-    pub static ref CLIENT_SECRET: String = {
-        load_dotenv();
-        env::var("CLIENT_SECRET").expect("CLIENT_SECRET must be set in .env file")
-    };
-    pub static ref REDIRECT_URL: String = {
-        load_dotenv();
-        env::var("REDIRECT_URL").unwrap_or_else(|_| "http://localhost:32857/".to_string())
+        env::var("DISCORD_APP_ID").ok().filter(|id| !id.trim().is_empty())
     };
+    static ref CREDENTIALS: RwLock<Option<Credentials>> = RwLock::new(load_credentials());
+}
+
+fn credentials_path() -> PathBuf {
+    config::get_config_dir().join("credentials.toml")
+}
+
+/// `.env`/environment variables win; otherwise fall back to the credentials
+/// saved during onboarding
+fn load_credentials() -> Option<Credentials> {
+    load_dotenv();
+    if let (Ok(client_id), Ok(client_secret)) = (env::var("CLIENT_ID"), env::var("CLIENT_SECRET")) {
+        let redirect_url = env::var("REDIRECT_URL").unwrap_or_default();
+        if let Ok(credentials) = Credentials::new(&client_id, &client_secret, &redirect_url) {
+            return Some(credentials);
+        }
+    }
+
+    let contents = fs::read_to_string(credentials_path()).ok()?;
+    toml::from_str(&contents).ok()
+}
+
+/// The app credentials, or None on a fresh install that hasn't been set up yet
+pub fn credentials() -> Option<Credentials> {
+    CREDENTIALS.read().ok()?.clone()
+}
+
+/// Persist credentials entered during onboarding and use them from now on
+pub fn save_credentials(credentials: Credentials) -> Result<(), Box<dyn std::error::Error>> {
+    let path = credentials_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, toml::to_string_pretty(&credentials)?)?;
+
+    if let Ok(mut cached) = CREDENTIALS.write() {
+        *cached = Some(credentials);
+    }
+    Ok(())
 }
 
 pub const SOUNDCLOUD_AUTH_URL: &str = "https://secure.soundcloud.com/authorize";
 pub const SOUNDCLOUD_TOKEN_URL: &str = "https://secure.soundcloud.com/oauth/token";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_credentials_are_trimmed_and_validated() {
+        let credentials = Credentials::new(" id ", "secret\n", "").unwrap();
+        assert_eq!(credentials.client_id, "id");
+        assert_eq!(credentials.client_secret, "secret");
+        assert_eq!(credentials.redirect_url, DEFAULT_REDIRECT_URL);
+
+        assert!(Credentials::new("id", "  ", "").is_err());
+        assert!(Credentials::new("id", "secret", "not a url").is_err());
+    }
+}
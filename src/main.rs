@@ -1,6 +1,8 @@
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use crate::managers::{AudioManager, QueueManager};
+use crate::managers::discord::{DiscordPresence, NowPlaying};
+use crate::managers::{AudioManager, PlayerState, QueueManager};
 use crate::pages::AuthPage;
 use iced::animation::Animation;
 use iced::widget::image::Handle;
@@ -9,10 +11,60 @@ use iced::{
     event::{self, Status},
     keyboard::{Event::KeyPressed, Key, key::Named},
     time,
-    widget::{column, container},
+    widget::{column, container, stack, text},
     window,
 };
 
+/// Unplayable tracks skipped in a row before playback gives up
+const MAX_CONSECUTIVE_LOAD_FAILURES: u32 = 3;
+
+/// UI tick rate while idle or paused; the playing rate is a setting
+const IDLE_TICK_INTERVAL: Duration = Duration::from_millis(400);
+// How often an overflowing now-playing title scrolls by one character
+const MARQUEE_STEP_INTERVAL: Duration = Duration::from_millis(250);
+// Quiet time after the last resize or move before the geometry is saved
+const WINDOW_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Quiet time after the last next/previous press of a burst before the
+/// track it landed on starts downloading
+const SKIP_SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Past this far into a track, "previous" restarts it instead of going back
+const PREVIOUS_RESTART_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// Win32 handle of an iced window, if it's a Win32 window
+#[cfg(windows)]
+fn win32_hwnd(window: &dyn window::Window) -> Option<isize> {
+    use window::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    match window.window_handle().ok()?.as_raw() {
+        RawWindowHandle::Win32(handle) => Some(handle.hwnd.get()),
+        _ => None,
+    }
+}
+
+/// How long a track can be for its decoded samples to be kept for an instant
+/// restart; only worth the memory while repeat one is on
+fn replay_cache_limit(settings: &config::AppSettings) -> Option<Duration> {
+    match settings.repeat_mode {
+        config::RepeatMode::One => settings.repeat_cache_limit(),
+        _ => None,
+    }
+}
+
+/// Discord presence if the setting is on and an app id is configured
+fn discord_presence(settings: &config::AppSettings) -> Option<DiscordPresence> {
+    if !settings.discord_presence {
+        return None;
+    }
+    constants::DISCORD_APP_ID
+        .as_deref()
+        .map(DiscordPresence::new)
+}
+
 fn main() -> iced::Result {
     // Only initialize tracing in debug builds, filtered to only rustwave logs
     #[cfg(debug_assertions)]
@@ -23,13 +75,25 @@ fn main() -> iced::Result {
     // Load the application icon
     let icon = window::icon::from_file_data(include_bytes!("../assets/icon.png"), None).ok();
 
+    // Reopen where the window was last left
+    let defaults = window::Settings::default();
+    let geometry = config::load_window_geometry();
+    let size = geometry.map_or(defaults.size, |g| iced::Size::new(g.width, g.height));
+    let position = match geometry.and_then(|g| g.x.zip(g.y)) {
+        Some((x, y)) => window::Position::Specific(iced::Point::new(x, y)),
+        None => defaults.position,
+    };
+
     iced::application(MyApp::new, MyApp::update, MyApp::view)
         .title("Rustwave")
-        .theme(|_: &MyApp| iced::Theme::CatppuccinMocha)
+        .theme(|app: &MyApp| app.settings.theme.iced_theme())
+        .scale_factor(|app: &MyApp| app.settings.ui_scale())
         .subscription(MyApp::subscription)
         .window(window::Settings {
             icon,
-            ..Default::default()
+            size,
+            position,
+            ..defaults
         })
         .run()
 }
@@ -48,31 +112,60 @@ enum Message {
     LikesPage(pages::LikesPageMessage),
     AuthPage(pages::AuthPageMessage),
     SearchPage(pages::SearchPageMessage),
+    GenrePage(pages::GenrePageMessage),
     FeedPage(pages::FeedPageMessage),
+    FollowsPage(pages::FollowsPageMessage),
     UserPage(pages::UserPageMessage),
     PlaylistPage(pages::PlaylistPageMessage),
+    TrackPage(pages::TrackPageMessage),
     PlayPausePlayback,
     SeekForwards,
     SeekBackwards,
+    // Keyboard cursor through the current page's track list; pages handle
+    // MoveSelection, which the arrow keys are turned into
+    SelectNextTrack,
+    SelectPreviousTrack,
+    MoveSelection(isize, bool), // Rows to step, and whether blocked tracks are hidden
+    PlaySelectedTrack,
     UiTick,
     SeekToPosition(f32),
+    Scrub(f32), // Seekbar dragged to a percentage; seeks on ScrubRelease
+    ScrubRelease,
     MediaControlEvent(souvlaki::MediaControlEvent),
+    WindowOpened(window::Id),
+    WindowResized(iced::Size),
+    WindowMoved(iced::Point),
+    MonitorSizeLoaded(window::Id, Option<iced::Size>),
+    #[cfg(windows)]
+    WindowHandle(isize), // Win32 HWND of the app window, for media controls
     NextTrack,
     PreviousTrack,
+    #[allow(dead_code)] // For the queue view to play a queued track directly
+    JumpToQueueIndex(usize),
+    #[cfg(any(windows, target_os = "macos"))]
+    Quit, // Tray menu's Quit item
     ToggleRepeatMode,
+    ToggleTimeDisplay,
+    RelatedTracksLoaded(
+        Vec<crate::models::SoundCloudTrack>,
+        crate::soundcloud::TokenManager,
+    ),
+    RelatedTracksFailed(String, crate::soundcloud::TokenManager),
     TrackEnded,
     StartQueue(
         crate::models::SoundCloudTrack,
-        Vec<crate::models::SoundCloudTrack>,
+        std::sync::Arc<Vec<crate::models::SoundCloudTrack>>, // The list to queue from, shared
         crate::soundcloud::TokenManager,
     ),
     QueueStreamDownloaded(
+        u64, // generation of the download, see MyApp::stream_generation
         std::sync::Arc<crate::managers::audio_buffer::SharedAudioBuffer>,
         Option<Handle>,
         Option<Vec<f32>>,
         crate::soundcloud::TokenManager,
     ),
-    QueueStreamFailed(String, crate::soundcloud::TokenManager),
+    QueueStreamFailed(u64, String, crate::soundcloud::TokenManager),
+    SkipSettled(u64), // generation of the skip; starts the download if no newer one came
     NextTrackPrefetched(
         u64, // track id the prefetch was for
         std::sync::Arc<crate::managers::audio_buffer::SharedAudioBuffer>,
@@ -81,14 +174,89 @@ enum Message {
         crate::soundcloud::TokenManager,
     ),
     NextTrackPrefetchFailed(String, crate::soundcloud::TokenManager),
+    TrackAnalyzed(u64, Option<crate::managers::decoder::TrackAnalysis>), // Local decode of a played track
     NavigateToSearch,
     NavigateToLikes,
     NavigateToFeed,
+    NavigateToSettings,
+    NavigateToMe, // Profile page of the logged-in user
+    ShowTrackDetails(crate::models::SoundCloudTrack),
+    EnqueueTracks(Vec<crate::models::SoundCloudTrack>), // Add to the end of the queue, e.g. a whole playlist
+    NavigateToCurrentArtist, // Profile of the current queue track's uploader
+    MeLoaded(
+        crate::models::SoundCloudUser,
+        crate::soundcloud::TokenManager,
+    ),
+    MeLoadFailed(String, crate::soundcloud::TokenManager),
+    MeAvatarLoaded(Handle),
+    RestoreQueue,
+    DismissSavedQueue,
+    RestoredArtworkLoaded(u64, Handle), // Track id and artwork of a restored queue's current track
+    ToggleAutoplay,
+    ToggleWrapQueue,
+    ToggleDiscordPresence,
+    ToggleRateLimitIndicator,
+    ToggleHideBlocked,
+    ToggleTheme, // Between the light and dark themes
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    SetVolume(f32),
+    VolumeUp,   // One volume step, from the keyboard
+    VolumeDown, // One volume step, from the keyboard
+    ToggleMute,
+    MarqueeHovered(bool), // Pointer over the now-playing title, which pauses its scrolling
+    SetVolumeStep(config::VolumeStep),
+    SetTrackSort(config::TrackSort),
+    CopyLink(String), // Put a soundcloud.com link on the clipboard
+    ExportLikes(crate::managers::export::ExportFormat), // Save the loaded likes to a file
+    LibraryExported(Result<Option<std::path::PathBuf>, String>), // Saved path; None if cancelled
+    SetFeedAutorefresh(Option<u32>), // Minutes between feed reloads, None for off
+    SetUiTickMs(u64),
+    SetDefaultPage(config::StartPage),
+    ToggleRememberLastPage,
+    SetRepeatCacheSecs(u32),
+    FeedAutorefresh, // Periodic tick for the feed page to reload itself
+    SetSeekbarType(config::SeekbarType),
+    ToggleNormalizeVolume,
+    SetOutputDevice(Option<String>), // None for the system default
+    SetEqBand {
+        band: usize,
+        gain: f32,
+    },
+    ApplyEqPreset(crate::managers::equalizer::EqPreset),
+    SaveSettings, // Persist settings after a live-adjusted control (e.g. slider) is released
+    PromptSaveQueue,
+    PlaylistTitleChanged(String),
+    CancelSaveQueue,
+    SaveQueueAsPlaylist(String), // Playlist title
+    PlaylistCreated(
+        crate::models::SoundCloudPlaylist,
+        crate::soundcloud::TokenManager,
+    ),
+    PlaylistCreateFailed(String, crate::soundcloud::TokenManager),
+    AddToPlaylist(crate::models::SoundCloudTrack), // Open the playlist picker for a track
+    AddCurrentToPlaylist,
+    MyPlaylistsLoaded(
+        Vec<crate::models::SoundCloudPlaylist>,
+        crate::soundcloud::TokenManager,
+    ),
+    MyPlaylistsFailed(String, crate::soundcloud::TokenManager),
+    AddTrackToPlaylist(crate::models::SoundCloudPlaylist), // Picked in the playlist picker
+    TrackAddedToPlaylist(String, crate::soundcloud::TokenManager), // Playlist title
+    AddToPlaylistFailed(String, crate::soundcloud::TokenManager),
+    ClosePlaylistPicker,
+    LikeCurrentTrack, // From the playback bar; unlikes an already liked track
+    CurrentTrackLikeChanged(u64, bool, crate::soundcloud::TokenManager), // Track id, now liked
+    LikeCurrentTrackFailed(String, crate::soundcloud::TokenManager),
 }
 
 trait Page {
     fn update(&mut self, message: Message) -> (Option<Box<dyn Page>>, Task<Message>);
-    fn view(&self) -> iced::Element<'_, Message>;
+    /// `now_playing` is the id of the track the player has loaded, so lists
+    /// can highlight it wherever it appears; `hide_blocked` is the setting
+    /// for leaving region-blocked tracks out of them
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message>;
     /// Whether the page has an active animation that needs frame-by-frame redraws.
     fn is_animating(&self) -> bool {
         false
@@ -110,38 +278,143 @@ struct MyApp {
     artwork: Option<Handle>,
     artwork_anim: Animation<bool>, // Drives the fade/pop-in when artwork changes
     waveform_peaks: Option<Vec<f32>>, // Peak data for canvas rendering
+    scrub_position: Option<f32>,   // Seekbar percentage while a drag is in progress
     audio_manager: AudioManager,
     queue_manager: QueueManager,
     pending_stream_download: bool, // Flag to track if we're downloading the next track
+    ended_handled: bool, // TrackEnded was sent for the loaded track; cleared when another loads
+    stream_generation: u64, // Bumped per download so late results for skipped tracks are dropped
+    stream_download: Option<iced::task::Handle>, // Aborts the current download when superseded
     token_manager: Option<crate::soundcloud::TokenManager>, // Store token manager for queue operations
     settings: config::AppSettings,
     prefetched_track: Option<PrefetchedTrack>, // Buffered stream for the next queue track
     prefetch_in_flight: Option<u64>,           // Track id of a prefetch currently downloading
+    track_gains: HashMap<u64, f32>,            // Loudness normalization gain per track id
+    toast: Option<(String, Instant)>,          // Transient notice and when it was shown
+    load_failures: u32,                        // Tracks in a row that failed to load
+    playlist_title: Option<String>, // Title being typed to save the queue; None while closed
+    me: Option<crate::models::SoundCloudUser>, // Logged-in user, fetched once per session
+    me_avatar: Option<Handle>,
+    open_me_when_loaded: bool, // NavigateToMe arrived before `me` was fetched
+    playlist_picker: Option<crate::models::SoundCloudTrack>, // Track being added to a playlist
+    saved_queue: Option<crate::managers::queue::SavedQueue>, // Last run's queue, offered until answered or replaced
+    now_playing: Option<u64>, // Track loaded in the player, highlighted in every page's lists
+    liked_changes: HashMap<u64, bool>, // Likes changed from the playback bar, newer than the tracks' user_favorite
+    my_playlists: Option<Vec<crate::models::SoundCloudPlaylist>>, // Picker options; None until fetched
+    window_geometry: Option<config::WindowGeometry>, // Current size and position, once known
+    window_geometry_changed: Option<Instant>,        // Last unsaved resize or move
+    marquee_offset: usize, // Characters the overflowing title and user have scrolled
+    marquee_stepped: Instant,
+    marquee_hovered: bool,
+    unmuted_volume: Option<f32>,      // Volume to go back to while muted
+    discord: Option<DiscordPresence>, // Set while the setting is on and an app id is configured
+    #[cfg(any(windows, target_os = "macos"))]
+    tray: Option<crate::managers::tray::TrayManager>, // None if the OS refused the tray icon
 }
 
 impl MyApp {
-    // Helper method to start downloading and playing a track
-    fn start_track_download(
-        &mut self,
-        track: &crate::models::SoundCloudTrack,
-        token_manager: crate::soundcloud::TokenManager,
-    ) -> Task<Message> {
-        if track.stream_url.is_none() {
+    /// Whether the user likes `track`, counting changes made from the bar
+    fn is_liked(&self, track: &crate::models::SoundCloudTrack) -> bool {
+        self.liked_changes
+            .get(&track.id)
+            .copied()
+            .unwrap_or(track.user_favorite == Some(true))
+    }
+
+    /// Float a transient notice over the page, replacing any current one
+    fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some((message.into(), Instant::now()));
+    }
+
+    /// Report a track that couldn't be played and move on to the next one,
+    /// giving up after a few failures in a row so a broken stream or missing
+    /// output device doesn't skip through the whole queue
+    fn skip_unplayable_track(&mut self, error: String) -> Task<Message> {
+        eprintln!("Failed to load track: {}", error);
+        self.pending_stream_download = false;
+        self.audio_manager.stream_loading = false;
+        self.load_failures += 1;
+        self.show_toast(format!("Couldn't play \"{}\": {}", self.title, error));
+
+        if !self.audio_manager.has_output() || self.load_failures >= MAX_CONSECUTIVE_LOAD_FAILURES {
             return Task::none();
         }
+        match (
+            self.queue_manager.next_track().cloned(),
+            self.token_manager.clone(),
+        ) {
+            (Some(next), Some(token_manager)) => self.start_track_download(&next, token_manager),
+            _ => Task::none(),
+        }
+    }
 
+    /// Show `track` as loading in the playback bar and supersede any download
+    /// still running for a previous track. Returns the new download generation.
+    fn begin_track_load(&mut self, track: &crate::models::SoundCloudTrack) -> u64 {
         self.title = track.title.clone();
         self.user = track.user.username.clone();
+        self.marquee_offset = 0;
         self.audio_manager.track_duration = Duration::from_millis(track.duration);
         self.audio_manager.stream_loading = true;
         self.audio_manager.sink.clear();
         self.pending_stream_download = true;
 
+        self.stream_generation += 1;
+        if let Some(handle) = self.stream_download.take() {
+            handle.abort();
+        }
+
+        // Playing something supersedes last run's queue
+        self.saved_queue = None;
+        self.save_queue();
+        self.stream_generation
+    }
+
+    /// Write the queue to disk so the next launch can offer it back
+    fn save_queue(&self) {
+        if let Err(e) = crate::managers::queue::save_queue(&self.queue_manager.snapshot()) {
+            eprintln!("Failed to save queue: {}", e);
+        }
+    }
+
+    /// Play a track the user skipped to. A skip while another is still
+    /// loading is part of a burst, so the download waits until the presses
+    /// stop and only the track they land on is fetched.
+    fn skip_to_track(
+        &mut self,
+        track: &crate::models::SoundCloudTrack,
+        token_manager: crate::soundcloud::TokenManager,
+    ) -> Task<Message> {
+        if !self.pending_stream_download || track.stream_url.is_none() {
+            return self.start_track_download(track, token_manager);
+        }
+
+        let generation = self.begin_track_load(track);
+        Task::perform(tokio::time::sleep(SKIP_SETTLE_DELAY), move |_| {
+            Message::SkipSettled(generation)
+        })
+    }
+
+    // Helper method to start downloading and playing a track
+    fn start_track_download(
+        &mut self,
+        track: &crate::models::SoundCloudTrack,
+        token_manager: crate::soundcloud::TokenManager,
+    ) -> Task<Message> {
+        let generation = self.begin_track_load(track);
+
+        // Superseding the previous download above keeps it from playing
+        // while the queue points here; then move past this track
+        if track.stream_url.is_none() {
+            return self.skip_unplayable_track("no stream available".to_string());
+        }
+
         // Use the prefetched stream if it's for this track; otherwise it's
         // stale, so stop its download
         if let Some(prefetched) = self.prefetched_track.take() {
             if prefetched.track_id == track.id {
                 return Task::done(Message::QueueStreamDownloaded(
+                    generation,
                     prefetched.buffer,
                     prefetched.artwork,
                     prefetched.waveform_peaks,
@@ -152,20 +425,26 @@ impl MyApp {
         }
 
         let track_clone = track.clone();
-        Task::perform(
+        let (task, handle) = Task::perform(
             async move { crate::managers::download_track_stream(token_manager, &track_clone).await },
-            |result| match result {
+            move |result| match result {
                 Ok((track_data, image_handle, waveform_peaks, token_manager)) => {
                     Message::QueueStreamDownloaded(
+                        generation,
                         track_data,
                         image_handle,
                         waveform_peaks,
                         token_manager,
                     )
                 }
-                Err((error, token_manager)) => Message::QueueStreamFailed(error, token_manager),
+                Err((error, token_manager)) => {
+                    Message::QueueStreamFailed(generation, error, token_manager)
+                }
             },
         )
+        .abortable();
+        self.stream_download = Some(handle);
+        task
     }
 
     /// Start prefetching the next queue track's stream, if there is one and
@@ -211,10 +490,46 @@ impl MyApp {
         )
     }
 
+    /// Fetch the logged-in user for the profile button and "Me" page
+    fn load_me(&self, token_manager: crate::soundcloud::TokenManager) -> Task<Message> {
+        Task::perform(
+            crate::soundcloud::api_helpers::get_me_with_refresh(token_manager),
+            |result| match result {
+                Ok((user, token_manager)) => Message::MeLoaded(user, token_manager),
+                Err((error, token_manager)) => {
+                    Message::MeLoadFailed(error.to_string(), token_manager)
+                }
+            },
+        )
+    }
+
+    /// Switch to the logged-in user's profile, reusing the cached `/me`
+    fn open_me_page(&mut self) -> Task<Message> {
+        let (Some(me), Some(token_manager)) = (&self.me, self.token_manager.clone()) else {
+            return Task::none();
+        };
+        let (page, task) = pages::UserPage::new_me(token_manager, me.urn.clone());
+        self.page = Box::new(page);
+        task
+    }
+
     fn new() -> (Self, Task<Message>) {
         // The auth page immediately tries to restore a cached session, so
         // returning users skip the login screen entirely.
         let (auth_page, auth_task) = AuthPage::new();
+        let settings = config::load_settings();
+        let discord = discord_presence(&settings);
+        let mut audio_manager = AudioManager::new(settings.output_device.as_deref());
+        audio_manager.set_eq_gains(&settings.eq_gains);
+        audio_manager.set_replay_cache_limit(replay_cache_limit(&settings));
+        let mut queue_manager = QueueManager::new();
+        queue_manager.set_wrap_around(settings.wrap_queue);
+        let toast = (!audio_manager.has_output()).then(|| {
+            (
+                "No audio output device found, playback is disabled".to_string(),
+                Instant::now(),
+            )
+        });
         (
             Self {
                 page: Box::new(auth_page),
@@ -223,13 +538,38 @@ impl MyApp {
                 artwork: None,
                 artwork_anim: Animation::new(true),
                 waveform_peaks: None,
-                audio_manager: AudioManager::new(),
-                queue_manager: QueueManager::new(),
+                scrub_position: None,
+                audio_manager,
+                queue_manager,
                 pending_stream_download: false,
+                ended_handled: false,
+                stream_generation: 0,
+                stream_download: None,
                 token_manager: None,
-                settings: config::load_settings(),
+                settings,
                 prefetched_track: None,
                 prefetch_in_flight: None,
+                track_gains: HashMap::new(),
+                toast,
+                load_failures: 0,
+                playlist_title: None,
+                me: None,
+                me_avatar: None,
+                open_me_when_loaded: false,
+                playlist_picker: None,
+                saved_queue: crate::managers::queue::load_saved_queue(),
+                now_playing: None,
+                liked_changes: HashMap::new(),
+                my_playlists: None,
+                window_geometry: config::load_window_geometry(),
+                window_geometry_changed: None,
+                marquee_offset: 0,
+                marquee_stepped: Instant::now(),
+                marquee_hovered: false,
+                unmuted_volume: None,
+                discord,
+                #[cfg(any(windows, target_os = "macos"))]
+                tray: crate::managers::tray::TrayManager::new(),
             },
             auth_task,
         )
@@ -243,9 +583,131 @@ impl MyApp {
 
         // Handle the main app messages
         let app_task = match message {
+            Message::AuthPage(pages::AuthPageMessage::SessionRestored(Some(token_manager)))
+            | Message::AuthPage(pages::AuthPageMessage::AuthCompleted(Ok(token_manager))) => {
+                self.token_manager = Some(token_manager.clone());
+                self.load_me(token_manager)
+            }
+            Message::CopyLink(url) => {
+                if url.is_empty() {
+                    self.show_toast("No link available");
+                    return Task::none();
+                }
+                self.show_toast("Copied!");
+                iced::clipboard::write(url)
+            }
+            Message::EnqueueTracks(tracks) => {
+                // Nothing to add to, so play them instead
+                if self.queue_manager.is_empty() {
+                    let (Some(first), Some(token_manager)) = (
+                        tracks.iter().find(|t| t.is_playable()).cloned(),
+                        self.token_manager.clone(),
+                    ) else {
+                        self.show_toast("Nothing here can be played");
+                        return Task::none();
+                    };
+                    return Task::done(Message::StartQueue(
+                        first,
+                        std::sync::Arc::new(tracks),
+                        token_manager,
+                    ));
+                }
+                match self.queue_manager.enqueue_last(tracks) {
+                    0 => self.show_toast("Everything playable here is already queued"),
+                    1 => self.show_toast("Added 1 track to the queue"),
+                    added => self.show_toast(format!("Added {} tracks to the queue", added)),
+                }
+                self.save_queue();
+                Task::none()
+            }
+            Message::ShowTrackDetails(track) => {
+                let Some(token_manager) = self.token_manager.clone() else {
+                    return Task::none();
+                };
+                let (page, task) = pages::TrackPage::new(token_manager, track);
+                self.page = Box::new(page);
+                task
+            }
+            Message::TrackPage(pages::TrackPageMessage::SubmitComment(track_id, body)) => {
+                let body = body.trim().to_string();
+                if body.is_empty() {
+                    return Task::none();
+                }
+                // Pin the comment to the playhead when it's on the track being played
+                let timestamp = (self.now_playing == Some(track_id))
+                    .then_some(self.audio_manager.track_position.as_millis() as u64);
+                Task::done(Message::TrackPage(pages::TrackPageMessage::PostComment(
+                    crate::models::SoundCloudComment {
+                        id: 0, // Assigned by SoundCloud once posted
+                        body,
+                        user: self.me.clone().unwrap_or_default(),
+                        timestamp,
+                        created_at: String::new(),
+                    },
+                )))
+            }
+            Message::TrackPage(pages::TrackPageMessage::CommentFailedWithToken(..)) => {
+                self.show_toast("Couldn't post the comment");
+                Task::none()
+            }
+            Message::NavigateToCurrentArtist => {
+                let (Some(track), Some(token_manager)) = (
+                    self.queue_manager.current_track(),
+                    self.token_manager.clone(),
+                ) else {
+                    return Task::none();
+                };
+                let (page, task) = pages::UserPage::new(token_manager, track.user.urn.clone());
+                self.page = Box::new(page);
+                task
+            }
+            Message::NavigateToMe => {
+                if self.me.is_some() {
+                    self.open_me_page()
+                } else if let Some(token_manager) = self.token_manager.clone() {
+                    // The startup fetch failed or is still in flight
+                    self.open_me_when_loaded = true;
+                    self.load_me(token_manager)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::MeLoaded(user, token_manager) => {
+                self.token_manager = Some(token_manager);
+                let avatar_url = user.avatar_url.clone();
+                self.me = Some(user);
+                let avatar_task = Task::perform(
+                    async move { crate::utilities::download_image_with_fallback(&avatar_url).await },
+                    Message::MeAvatarLoaded,
+                );
+                if std::mem::take(&mut self.open_me_when_loaded) {
+                    Task::batch([avatar_task, self.open_me_page()])
+                } else {
+                    avatar_task
+                }
+            }
+            Message::MeLoadFailed(error, token_manager) => {
+                eprintln!("Failed to load your profile: {}", error);
+                self.token_manager = Some(token_manager);
+                if std::mem::take(&mut self.open_me_when_loaded) {
+                    self.show_toast("Couldn't load your profile");
+                }
+                Task::none()
+            }
+            Message::MeAvatarLoaded(handle) => {
+                self.me_avatar = Some(handle);
+                Task::none()
+            }
             Message::StartQueue(track, tracks, token_manager) => {
                 // Store the token manager for future queue operations
                 self.token_manager = Some(token_manager.clone());
+                // Picking a track is a fresh start for the failure count
+                self.load_failures = 0;
+
+                if track.is_blocked() {
+                    self.show_toast(format!("\"{}\" is blocked in your region", track.title));
+                    return Task::none();
+                }
 
                 // Initialize the queue starting from the selected track
                 self.queue_manager.start_queue_from_track(track.id, tracks);
@@ -258,6 +720,7 @@ impl MyApp {
                 }
             }
             Message::QueueStreamDownloaded(
+                generation,
                 track_data,
                 image_handle,
                 waveform_peaks,
@@ -265,17 +728,63 @@ impl MyApp {
             ) => {
                 // Update stored token manager
                 self.token_manager = Some(token_manager);
+                // A track that was skipped before its stream arrived
+                if generation != self.stream_generation {
+                    track_data.cancel();
+                    return Task::none();
+                }
+                self.stream_download = None;
                 // Store waveform peak data
                 self.waveform_peaks = waveform_peaks;
 
+                // Use the track's cached normalization gain; an unmeasured
+                // track plays as-is until its analysis comes back
+                let current = self
+                    .queue_manager
+                    .current_track()
+                    .map(|t| (t.id, t.waveform_url.is_empty()));
+                let cached_gain =
+                    current.and_then(|(track_id, _)| self.track_gains.get(&track_id).copied());
+                self.audio_manager
+                    .set_normalization_gain(if self.settings.normalize_volume {
+                        cached_gain.unwrap_or(1.0)
+                    } else {
+                        1.0
+                    });
+
+                // Decode the audio once it has downloaded for what the stream
+                // doesn't provide: peaks for tracks without a waveform image,
+                // and loudness for normalization
+                let analysis_task = match current {
+                    Some((track_id, needs_peaks))
+                        if needs_peaks
+                            || (self.settings.normalize_volume && cached_gain.is_none()) =>
+                    {
+                        let buffer = std::sync::Arc::clone(&track_data);
+                        Task::perform(
+                            async move {
+                                tokio::task::spawn_blocking(move || {
+                                    crate::managers::decoder::analyze_track(buffer, 1800)
+                                })
+                                .await
+                                .ok()
+                                .and_then(Result::ok)
+                            },
+                            move |analysis| Message::TrackAnalyzed(track_id, analysis),
+                        )
+                    }
+                    _ => Task::none(),
+                };
+
                 // Load the track using AudioManager
                 if let Err(e) = self.audio_manager.load_track(track_data) {
-                    eprintln!("Failed to load track: {}", e);
-                    self.pending_stream_download = false;
-                    return Task::none();
+                    return self.skip_unplayable_track(e);
                 }
 
+                self.load_failures = 0;
+                self.ended_handled = false;
                 self.pending_stream_download = false;
+                self.now_playing = current.map(|(track_id, _)| track_id);
                 self.artwork = image_handle;
 
                 // Fade and pop the new artwork in.
@@ -290,7 +799,29 @@ impl MyApp {
                 );
 
                 // Start buffering the next queue track so it can play instantly
-                self.start_next_track_prefetch()
+                Task::batch([analysis_task, self.start_next_track_prefetch()])
+            }
+            Message::TrackAnalyzed(track_id, analysis) => {
+                let Some(analysis) = analysis else {
+                    return Task::none();
+                };
+                let gain = managers::audio::normalization_gain(analysis.loudness_db);
+                self.track_gains.insert(track_id, gain);
+
+                // Only apply to the track that's still playing
+                if let Some(track) = self
+                    .queue_manager
+                    .current_track()
+                    .filter(|t| t.id == track_id)
+                {
+                    if track.waveform_url.is_empty() {
+                        self.waveform_peaks = Some(analysis.peaks);
+                    }
+                    if self.settings.normalize_volume {
+                        self.audio_manager.set_normalization_gain(gain);
+                    }
+                }
+                Task::none()
             }
             Message::NextTrackPrefetched(
                 track_id,
@@ -328,18 +859,91 @@ impl MyApp {
                 self.token_manager = Some(token_manager);
                 Task::none()
             }
-            Message::QueueStreamFailed(error, token_manager) => {
+            Message::QueueStreamFailed(generation, error, token_manager) => {
                 eprintln!("Failed to download stream: {}", error);
-                self.audio_manager.stream_loading = false;
-                self.pending_stream_download = false;
                 // Update stored token manager
                 self.token_manager = Some(token_manager);
+                if generation != self.stream_generation {
+                    return Task::none();
+                }
+                self.stream_download = None;
+                self.audio_manager.stream_loading = false;
+                self.pending_stream_download = false;
                 Task::none()
             }
+            Message::SkipSettled(generation) => {
+                if generation != self.stream_generation {
+                    return Task::none();
+                }
+                match (
+                    self.queue_manager.current_track().cloned(),
+                    self.token_manager.clone(),
+                ) {
+                    (Some(track), Some(token_manager)) => {
+                        self.start_track_download(&track, token_manager)
+                    }
+                    _ => Task::none(),
+                }
+            }
             Message::PlayPausePlayback => {
+                // Nothing loaded yet, e.g. a queue restored at launch: start
+                // its current track
+                if self.audio_manager.is_empty() && !self.pending_stream_download {
+                    if let (Some(track), Some(token_manager)) = (
+                        self.queue_manager.current_track().cloned(),
+                        self.token_manager.clone(),
+                    ) {
+                        return self.start_track_download(&track, token_manager);
+                    }
+                    return Task::none();
+                }
                 self.audio_manager.toggle_play_pause();
                 Task::none()
             }
+            Message::RestoreQueue => {
+                let Some(saved) = self.saved_queue.take() else {
+                    return Task::none();
+                };
+                self.queue_manager.restore(saved);
+                let Some(track) = self.queue_manager.current_track().cloned() else {
+                    return Task::none();
+                };
+
+                // Show the track as ready to play, without starting it
+                self.title = track.title.clone();
+                self.user = track.user.username.clone();
+                self.marquee_offset = 0;
+                self.audio_manager.track_duration = Duration::from_millis(track.duration);
+                self.audio_manager.track_position = Duration::ZERO;
+                self.audio_manager.progress_bar_value = 0.0;
+                Task::perform(
+                    async move {
+                        crate::utilities::download_image_with_fallback(&track.artwork_url).await
+                    },
+                    move |handle| Message::RestoredArtworkLoaded(track.id, handle),
+                )
+            }
+            Message::DismissSavedQueue => {
+                self.saved_queue = None;
+                Task::none()
+            }
+            Message::RestoredArtworkLoaded(track_id, handle) => {
+                // Ignore it if a track started loading in the meantime
+                if self.artwork.is_none()
+                    && self.queue_manager.current_track().map(|t| t.id) == Some(track_id)
+                {
+                    self.artwork = Some(handle);
+                }
+                Task::none()
+            }
+            // The page steps through its rows as rendered, which depends on
+            // the hide-blocked setting
+            Message::SelectNextTrack => {
+                self.update(Message::MoveSelection(1, self.settings.hide_blocked))
+            }
+            Message::SelectPreviousTrack => {
+                self.update(Message::MoveSelection(-1, self.settings.hide_blocked))
+            }
             Message::SeekForwards => {
                 self.audio_manager.seek_forward(Duration::from_secs(10));
                 Task::none()
@@ -355,11 +959,84 @@ impl MyApp {
                     return Task::done(Message::MediaControlEvent(event));
                 }
 
+                #[cfg(any(windows, target_os = "macos"))]
+                if let Some(tray) = &mut self.tray {
+                    if let Some(message) = tray.poll() {
+                        return Task::done(message);
+                    }
+                    tray.sync(
+                        self.audio_manager.state() == PlayerState::Playing,
+                        self.queue_manager
+                            .current_track()
+                            .map(|track| track.title.as_str()),
+                    );
+                }
+
+                if self
+                    .toast
+                    .as_ref()
+                    .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION)
+                {
+                    self.toast = None;
+                }
+
                 // Update playback position
-                self.audio_manager.update_position();
+                match self.audio_manager.update_position() {
+                    Some(Ok(())) => {
+                        self.show_toast("Audio device disconnected, switched to the default output")
+                    }
+                    Some(Err(e)) => self.show_toast(format!("Audio device disconnected: {}", e)),
+                    None => {}
+                }
+                if self.settings.seekbar_type == config::SeekbarType::Spectrum {
+                    self.audio_manager.update_spectrum();
+                }
+
+                // Save the window geometry once a resize or drag settles
+                if let Some(geometry) = self.window_geometry
+                    && self
+                        .window_geometry_changed
+                        .is_some_and(|changed| changed.elapsed() >= WINDOW_SAVE_DELAY)
+                {
+                    self.window_geometry_changed = None;
+                    if let Err(e) = config::save_window_geometry(&geometry) {
+                        eprintln!("Failed to save window geometry: {}", e);
+                    }
+                }
+
+                // Scroll titles too long for the playback bar, unless hovered
+                if self.marquee_hovered
+                    || !(widgets::marquee_overflows(&self.title, widgets::MARQUEE_MAX_CHARS)
+                        || widgets::marquee_overflows(&self.user, widgets::MARQUEE_MAX_CHARS))
+                {
+                    self.marquee_stepped = Instant::now();
+                } else if self.marquee_stepped.elapsed() >= MARQUEE_STEP_INTERVAL {
+                    self.marquee_offset += 1;
+                    self.marquee_stepped = Instant::now();
+                }
+
+                // Keep the presence through the gap while the next track
+                // downloads; clear it once playback has stopped
+                if let Some(discord) = &mut self.discord
+                    && !self.pending_stream_download
+                {
+                    discord.sync((!self.audio_manager.is_empty()).then(|| NowPlaying {
+                        title: &self.title,
+                        artist: &self.user,
+                        paused: self.audio_manager.is_paused(),
+                        elapsed: self.audio_manager.track_position,
+                        duration: self.audio_manager.track_duration,
+                    }));
+                }
 
                 // Check if track has ended
-                if self.audio_manager.has_track_ended() && !self.pending_stream_download {
+                // Once per track: the end stays in range on every tick until the
+                // next track loads
+                if !self.ended_handled
+                    && self.audio_manager.has_track_ended()
+                    && !self.pending_stream_download
+                {
+                    self.ended_handled = true;
                     return Task::done(Message::TrackEnded);
                 }
 
@@ -369,16 +1046,91 @@ impl MyApp {
                 self.audio_manager.seek_to_position(percent);
                 Task::none()
             }
+            Message::Scrub(percent) => {
+                // Only the progress display follows the drag; seeking on every
+                // step would thrash the decoder
+                self.scrub_position = Some(percent);
+                Task::none()
+            }
+            Message::ScrubRelease => {
+                if let Some(percent) = self.scrub_position.take() {
+                    self.audio_manager.seek_to_position(percent);
+                }
+                Task::none()
+            }
+            Message::WindowOpened(id) => {
+                // A size restored from a bigger monitor may not fit this one
+                let fit = window::monitor_size(id)
+                    .map(move |monitor| Message::MonitorSizeLoaded(id, monitor));
+                #[cfg(windows)]
+                let fit = Task::batch([
+                    fit,
+                    window::run(id, win32_hwnd)
+                        .and_then(|hwnd| Task::done(Message::WindowHandle(hwnd))),
+                ]);
+                fit
+            }
+            Message::MonitorSizeLoaded(id, monitor) => match (monitor, self.window_geometry) {
+                (Some(monitor), Some(geometry))
+                    if geometry.width > monitor.width || geometry.height > monitor.height =>
+                {
+                    window::resize(
+                        id,
+                        iced::Size::new(
+                            geometry.width.min(monitor.width),
+                            geometry.height.min(monitor.height),
+                        ),
+                    )
+                }
+                _ => Task::none(),
+            },
+            Message::WindowResized(size) => {
+                let geometry = self.window_geometry.get_or_insert(config::WindowGeometry {
+                    width: size.width,
+                    height: size.height,
+                    x: None,
+                    y: None,
+                });
+                geometry.width = size.width;
+                geometry.height = size.height;
+                self.window_geometry_changed = Some(Instant::now());
+                Task::none()
+            }
+            Message::WindowMoved(position) => {
+                // Nothing saved yet means the window opened at the default size
+                let default_size = window::Settings::default().size;
+                let geometry = self.window_geometry.get_or_insert(config::WindowGeometry {
+                    width: default_size.width,
+                    height: default_size.height,
+                    x: None,
+                    y: None,
+                });
+                geometry.x = Some(position.x);
+                geometry.y = Some(position.y);
+                self.window_geometry_changed = Some(Instant::now());
+                Task::none()
+            }
+            #[cfg(windows)]
+            Message::WindowHandle(hwnd) => {
+                // SMTC hooks into the window, so media keys and the Windows
+                // media overlay only work once it has the handle
+                self.audio_manager
+                    .init_media_controls(Some(hwnd as *mut std::ffi::c_void));
+                Task::none()
+            }
             Message::MediaControlEvent(event) => {
                 match event {
                     souvlaki::MediaControlEvent::Play => {
+                        if self.audio_manager.is_empty() {
+                            return self.update(Message::PlayPausePlayback);
+                        }
                         self.audio_manager.play();
                     }
                     souvlaki::MediaControlEvent::Pause => {
                         self.audio_manager.pause();
                     }
                     souvlaki::MediaControlEvent::Toggle => {
-                        self.audio_manager.toggle_play_pause();
+                        return self.update(Message::PlayPausePlayback);
                     }
                     souvlaki::MediaControlEvent::Next => {
                         return self.update(Message::NextTrack);
@@ -395,18 +1147,19 @@ impl MyApp {
                         }
                     },
                     souvlaki::MediaControlEvent::SetPosition(position) => {
-                        if !self.audio_manager.is_empty() {
-                            let _ = self.audio_manager.sink.try_seek(position.0);
-                        }
+                        self.audio_manager.seek_to(position.0);
                     }
                     _ => {}
                 }
                 Task::none()
             }
+            #[cfg(any(windows, target_os = "macos"))]
+            Message::Quit => iced::exit(),
             Message::NextTrack => {
+                self.load_failures = 0;
                 if let Some(next_track) = self.queue_manager.next_track().cloned() {
                     if let Some(token_manager) = self.token_manager.clone() {
-                        self.start_track_download(&next_track, token_manager)
+                        self.skip_to_track(&next_track, token_manager)
                     } else {
                         eprintln!("No token manager available for next track");
                         Task::none()
@@ -416,9 +1169,17 @@ impl MyApp {
                 }
             }
             Message::PreviousTrack => {
-                if let Some(prev_track) = self.queue_manager.previous_track().cloned() {
+                self.load_failures = 0;
+                // Like other players: the first press restarts the current
+                // track, a second press (or one near the start) goes back
+                if !self.audio_manager.is_empty()
+                    && self.audio_manager.track_position > PREVIOUS_RESTART_THRESHOLD
+                {
+                    self.audio_manager.seek_to(Duration::ZERO);
+                    Task::none()
+                } else if let Some(prev_track) = self.queue_manager.previous_track().cloned() {
                     if let Some(token_manager) = self.token_manager.clone() {
-                        self.start_track_download(&prev_track, token_manager)
+                        self.skip_to_track(&prev_track, token_manager)
                     } else {
                         eprintln!("No token manager available for previous track");
                         Task::none()
@@ -427,8 +1188,22 @@ impl MyApp {
                     Task::none()
                 }
             }
+            Message::JumpToQueueIndex(index) => {
+                if let Some(track) = self.queue_manager.jump_to(index).cloned() {
+                    if let Some(token_manager) = self.token_manager.clone() {
+                        self.skip_to_track(&track, token_manager)
+                    } else {
+                        eprintln!("No token manager available for queued track");
+                        Task::none()
+                    }
+                } else {
+                    Task::none()
+                }
+            }
             Message::ToggleRepeatMode => {
                 self.settings.repeat_mode = self.settings.repeat_mode.toggle();
+                self.audio_manager
+                    .set_replay_cache_limit(replay_cache_limit(&self.settings));
 
                 if let Err(e) = config::save_settings(&self.settings) {
                     eprintln!("Failed to save settings: {}", e);
@@ -436,55 +1211,613 @@ impl MyApp {
 
                 Task::none()
             }
-            Message::TrackEnded => {
-                match self.settings.repeat_mode {
-                    config::RepeatMode::One => {
-                        // Repeat current track - reload from stored data
-                        if let Some(track_data) = self.audio_manager.current_track_data.clone() {
-                            // Reload the track using the stored data
-                            if let Err(e) = self.audio_manager.load_track(track_data) {
-                                eprintln!("Failed to reload track for repeat: {}", e);
-                            }
-                        }
-                        Task::none()
-                    }
-                    config::RepeatMode::All => {
-                        // Try to play next track, or restart queue
-                        if self.queue_manager.has_next() {
-                            Task::done(Message::NextTrack)
-                        } else if self.queue_manager.queue_length() > 0 {
-                            // Queue finished - restart from beginning
-                            if let Some(token_manager) = self.token_manager.clone() {
-                                self.queue_manager.reset_to_beginning();
-                                if let Some(first_track) =
-                                    self.queue_manager.current_track().cloned()
-                                {
-                                    self.start_track_download(&first_track, token_manager)
-                                } else {
-                                    Task::none()
-                                }
-                            } else {
-                                eprintln!("No token manager available for queue restart");
-                                self.audio_manager.clear();
-                                Task::none()
-                            }
-                        } else {
-                            // Empty queue, stop playback
-                            self.audio_manager.clear();
-                            Task::none()
-                        }
-                    }
+            Message::SetSeekbarType(seekbar_type) => {
+                self.settings.seekbar_type = seekbar_type;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
                 }
-            }
-            _ => Task::none(),
-        };
 
-        // Combine both tasks
+                Task::none()
+            }
+            Message::LibraryExported(result) => {
+                match result {
+                    Ok(Some(path)) => self.show_toast(format!("Exported to {}", path.display())),
+                    Ok(None) => {}
+                    Err(e) => self.show_toast(format!("Export failed: {}", e)),
+                }
+                Task::none()
+            }
+            Message::SetTrackSort(sort) => {
+                self.settings.track_sort = sort;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleWrapQueue => {
+                self.settings.wrap_queue = !self.settings.wrap_queue;
+                self.queue_manager.set_wrap_around(self.settings.wrap_queue);
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::MarqueeHovered(hovered) => {
+                self.marquee_hovered = hovered;
+                Task::none()
+            }
+            Message::SetVolume(volume) => {
+                self.unmuted_volume = None;
+                self.audio_manager.set_volume(volume);
+                self.show_toast(format!(
+                    "Volume {}%",
+                    (self.audio_manager.volume() * 100.0).round()
+                ));
+                Task::none()
+            }
+            Message::VolumeUp | Message::VolumeDown => {
+                let step = self.settings.volume_step.amount();
+                // Stepping while muted starts from the volume before muting
+                let volume = self
+                    .unmuted_volume
+                    .unwrap_or_else(|| self.audio_manager.volume());
+                let volume = if matches!(message, Message::VolumeUp) {
+                    volume + step
+                } else {
+                    volume - step
+                };
+                self.update(Message::SetVolume(volume))
+            }
+            Message::ToggleMute => {
+                match self.unmuted_volume.take() {
+                    Some(volume) => {
+                        self.audio_manager.set_volume(volume);
+                        self.show_toast(format!("Volume {}%", (volume * 100.0).round()));
+                    }
+                    None => {
+                        self.unmuted_volume = Some(self.audio_manager.volume());
+                        self.audio_manager.set_volume(0.0);
+                        self.show_toast("Muted");
+                    }
+                }
+                Task::none()
+            }
+            Message::SetVolumeStep(step) => {
+                self.settings.volume_step = step;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::SetDefaultPage(page) => {
+                self.settings.default_page = page;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleRememberLastPage => {
+                self.settings.remember_last_page = !self.settings.remember_last_page;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::NavigateToFeed | Message::NavigateToLikes | Message::NavigateToSearch => {
+                // The page itself handles the switch; just note where it went
+                let page = match message {
+                    Message::NavigateToFeed => config::StartPage::Feed,
+                    Message::NavigateToLikes => config::StartPage::Likes,
+                    _ => config::StartPage::Search,
+                };
+                if self.settings.remember_last_page && self.settings.last_page != Some(page) {
+                    self.settings.last_page = Some(page);
+                    if let Err(e) = config::save_settings(&self.settings) {
+                        eprintln!("Failed to save settings: {}", e);
+                    }
+                }
+                Task::none()
+            }
+            Message::SetUiTickMs(ms) => {
+                // Saved once the slider is released (SaveSettings)
+                self.settings.ui_tick_ms = Some(ms);
+                Task::none()
+            }
+            Message::SetRepeatCacheSecs(secs) => {
+                self.settings.repeat_cache_secs = Some(secs);
+                self.audio_manager
+                    .set_replay_cache_limit(replay_cache_limit(&self.settings));
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::SetFeedAutorefresh(minutes) => {
+                self.settings.feed_autorefresh_mins = minutes;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleDiscordPresence => {
+                self.settings.discord_presence = !self.settings.discord_presence;
+                // Dropping the presence clears it from Discord
+                self.discord = discord_presence(&self.settings);
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleRateLimitIndicator => {
+                self.settings.show_rate_limit = !self.settings.show_rate_limit;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleHideBlocked => {
+                self.settings.hide_blocked = !self.settings.hide_blocked;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleTheme => {
+                self.settings.theme = self.settings.theme.toggle();
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ZoomIn | Message::ZoomOut | Message::ResetZoom => {
+                match message {
+                    Message::ZoomIn => self.settings.zoom(1),
+                    Message::ZoomOut => self.settings.zoom(-1),
+                    _ => self.settings.ui_scale = None,
+                }
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleAutoplay => {
+                self.settings.autoplay = !self.settings.autoplay;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::SetOutputDevice(device) => {
+                if let Err(e) = self.audio_manager.set_output_device(device.as_deref()) {
+                    eprintln!("Failed to switch output device: {}", e);
+                }
+                self.settings.output_device = device;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::ToggleNormalizeVolume => {
+                self.settings.normalize_volume = !self.settings.normalize_volume;
+
+                // Takes effect right away if the current track was measured
+                let gain = self
+                    .queue_manager
+                    .current_track()
+                    .and_then(|t| self.track_gains.get(&t.id).copied());
+                self.audio_manager.set_normalization_gain(match gain {
+                    Some(gain) if self.settings.normalize_volume => gain,
+                    _ => 1.0,
+                });
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::SetEqBand { band, gain } => {
+                // Saved once the slider is released (SaveSettings)
+                if let Some(slot) = self.settings.eq_gains.get_mut(band) {
+                    *slot = gain;
+                    self.audio_manager.set_eq_gains(&self.settings.eq_gains);
+                }
+                Task::none()
+            }
+            Message::ApplyEqPreset(preset) => {
+                self.settings.eq_gains = preset.gains();
+                self.audio_manager.set_eq_gains(&self.settings.eq_gains);
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::SaveSettings => {
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+                Task::none()
+            }
+            Message::ToggleTimeDisplay => {
+                self.settings.show_remaining = !self.settings.show_remaining;
+
+                if let Err(e) = config::save_settings(&self.settings) {
+                    eprintln!("Failed to save settings: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::TrackEnded => {
+                match self.settings.repeat_mode {
+                    config::RepeatMode::One => {
+                        // Repeat current track - from its kept decoded
+                        // samples if short enough, else from stored data
+                        if self.audio_manager.current_track_data.is_some() {
+                            if let Err(e) = self.audio_manager.replay_track() {
+                                return self.skip_unplayable_track(e);
+                            }
+                            self.ended_handled = false;
+                        }
+                        Task::none()
+                    }
+                    config::RepeatMode::All => {
+                        // Try to play next track, or restart queue. With
+                        // wrap-around on there's always a next track, and
+                        // NextTrack does the restart itself.
+                        if self.queue_manager.has_next() {
+                            Task::done(Message::NextTrack)
+                        } else if self.queue_manager.queue_length() > 0 {
+                            // Queue finished - restart from beginning
+                            if let Some(token_manager) = self.token_manager.clone() {
+                                self.queue_manager.reset_to_beginning();
+                                if let Some(first_track) =
+                                    self.queue_manager.current_track().cloned()
+                                {
+                                    self.start_track_download(&first_track, token_manager)
+                                } else {
+                                    Task::none()
+                                }
+                            } else {
+                                eprintln!("No token manager available for queue restart");
+                                self.audio_manager.clear();
+                                Task::none()
+                            }
+                        } else {
+                            // Empty queue, stop playback
+                            self.audio_manager.clear();
+                            Task::none()
+                        }
+                    }
+                    config::RepeatMode::Off => {
+                        if self.queue_manager.has_next() {
+                            Task::done(Message::NextTrack)
+                        } else if self.settings.autoplay
+                            && let Some(track_id) = self.queue_manager.current_track().map(|t| t.id)
+                            && let Some(token_manager) = self.token_manager.clone()
+                        {
+                            // Hold off further TrackEnded ticks while related
+                            // tracks are fetched
+                            self.pending_stream_download = true;
+                            Task::perform(
+                                crate::soundcloud::api_helpers::get_related_tracks_with_refresh(
+                                    token_manager,
+                                    track_id,
+                                ),
+                                |result| match result {
+                                    Ok((tracks, token_manager)) => Message::RelatedTracksLoaded(
+                                        tracks.collection,
+                                        token_manager,
+                                    ),
+                                    Err((error, token_manager)) => Message::RelatedTracksFailed(
+                                        error.to_string(),
+                                        token_manager,
+                                    ),
+                                },
+                            )
+                        } else {
+                            // End of the queue, stop playback
+                            self.audio_manager.clear();
+                            Task::none()
+                        }
+                    }
+                }
+            }
+            Message::RelatedTracksLoaded(tracks, token_manager) => {
+                self.token_manager = Some(token_manager);
+                self.pending_stream_download = false;
+
+                self.queue_manager.enqueue_last(tracks);
+
+                if self.queue_manager.has_next() {
+                    Task::done(Message::NextTrack)
+                } else {
+                    // Nothing related (or all already queued), stop cleanly
+                    self.audio_manager.clear();
+                    Task::none()
+                }
+            }
+            Message::RelatedTracksFailed(error, token_manager) => {
+                eprintln!("Failed to load related tracks: {}", error);
+                self.token_manager = Some(token_manager);
+                self.pending_stream_download = false;
+                self.audio_manager.clear();
+                Task::none()
+            }
+            Message::PromptSaveQueue => {
+                self.playlist_title = Some(String::new());
+                Task::none()
+            }
+            Message::PlaylistTitleChanged(title) => {
+                self.playlist_title = Some(title);
+                Task::none()
+            }
+            Message::CancelSaveQueue => {
+                self.playlist_title = None;
+                Task::none()
+            }
+            Message::SaveQueueAsPlaylist(title) => {
+                let title = title.trim().to_string();
+                let track_ids: Vec<u64> = self
+                    .queue_manager
+                    .get_queue()
+                    .iter()
+                    .map(|t| t.id)
+                    .collect();
+                if title.is_empty() {
+                    self.show_toast("Enter a title for the playlist");
+                    return Task::none();
+                }
+                let Some(token_manager) = self.token_manager.clone() else {
+                    return Task::none();
+                };
+                if track_ids.is_empty() {
+                    self.show_toast("The queue is empty");
+                    return Task::none();
+                }
+
+                self.playlist_title = None;
+                Task::perform(
+                    crate::soundcloud::api_helpers::create_playlist_with_refresh(
+                        token_manager,
+                        title,
+                        track_ids,
+                    ),
+                    |result| match result {
+                        Ok((playlist, token_manager)) => {
+                            Message::PlaylistCreated(playlist, token_manager)
+                        }
+                        Err((error, token_manager)) => {
+                            Message::PlaylistCreateFailed(error.to_string(), token_manager)
+                        }
+                    },
+                )
+            }
+            Message::PlaylistCreated(playlist, token_manager) => {
+                self.token_manager = Some(token_manager);
+                // Refetch for the playlist picker so it includes the new one
+                self.my_playlists = None;
+                self.show_toast(format!(
+                    "Saved the queue as \"{}\" ({})",
+                    playlist.title, playlist.urn
+                ));
+                Task::none()
+            }
+            Message::PlaylistCreateFailed(error, token_manager) => {
+                eprintln!("Failed to create playlist: {}", error);
+                self.token_manager = Some(token_manager);
+                self.show_toast(format!("Couldn't save the queue: {}", error));
+                Task::none()
+            }
+            Message::AddCurrentToPlaylist => match self.queue_manager.current_track().cloned() {
+                Some(track) => Task::done(Message::AddToPlaylist(track)),
+                None => Task::none(),
+            },
+            Message::AddToPlaylist(track) => {
+                let Some(token_manager) = self.token_manager.clone() else {
+                    return Task::none();
+                };
+                self.playlist_picker = Some(track);
+                if self.my_playlists.is_some() {
+                    return Task::none();
+                }
+                Task::perform(
+                    crate::soundcloud::api_helpers::get_my_playlists_with_refresh(token_manager),
+                    |result| match result {
+                        Ok((playlists, token_manager)) => {
+                            Message::MyPlaylistsLoaded(playlists.collection, token_manager)
+                        }
+                        Err((error, token_manager)) => {
+                            Message::MyPlaylistsFailed(error.to_string(), token_manager)
+                        }
+                    },
+                )
+            }
+            Message::MyPlaylistsLoaded(playlists, token_manager) => {
+                self.token_manager = Some(token_manager);
+                self.my_playlists = Some(playlists);
+                Task::none()
+            }
+            Message::MyPlaylistsFailed(error, token_manager) => {
+                eprintln!("Failed to load playlists: {}", error);
+                self.token_manager = Some(token_manager);
+                self.playlist_picker = None;
+                self.show_toast(format!("Couldn't load your playlists: {}", error));
+                Task::none()
+            }
+            Message::AddTrackToPlaylist(playlist) => {
+                let (Some(track), Some(token_manager)) =
+                    (self.playlist_picker.take(), self.token_manager.clone())
+                else {
+                    return Task::none();
+                };
+                let title = playlist.title.clone();
+                Task::perform(
+                    crate::soundcloud::api_helpers::add_track_to_playlist_with_refresh(
+                        token_manager,
+                        playlist.urn,
+                        track.id,
+                    ),
+                    move |result| match result {
+                        Ok(token_manager) => {
+                            Message::TrackAddedToPlaylist(title.clone(), token_manager)
+                        }
+                        Err((error, token_manager)) => {
+                            Message::AddToPlaylistFailed(error.to_string(), token_manager)
+                        }
+                    },
+                )
+            }
+            Message::TrackAddedToPlaylist(title, token_manager) => {
+                self.token_manager = Some(token_manager);
+                self.show_toast(format!("Added to \"{}\"", title));
+                Task::none()
+            }
+            Message::AddToPlaylistFailed(error, token_manager) => {
+                eprintln!("Failed to add track to playlist: {}", error);
+                self.token_manager = Some(token_manager);
+                self.show_toast(format!("Couldn't add to the playlist: {}", error));
+                Task::none()
+            }
+            Message::ClosePlaylistPicker => {
+                self.playlist_picker = None;
+                Task::none()
+            }
+            Message::LikeCurrentTrack => {
+                let (Some(track), Some(token_manager)) = (
+                    self.queue_manager.current_track().cloned(),
+                    self.token_manager.clone(),
+                ) else {
+                    return Task::none();
+                };
+                let to_result = |liked: bool| {
+                    move |result: Result<_, (crate::soundcloud::AuthError, _)>| match result {
+                        Ok((track_id, token_manager)) => {
+                            Message::CurrentTrackLikeChanged(track_id, liked, token_manager)
+                        }
+                        Err((error, token_manager)) => {
+                            Message::LikeCurrentTrackFailed(error.to_string(), token_manager)
+                        }
+                    }
+                };
+                if self.is_liked(&track) {
+                    Task::perform(
+                        crate::soundcloud::api_helpers::unlike_track_with_refresh(
+                            token_manager,
+                            track.id,
+                        ),
+                        to_result(false),
+                    )
+                } else {
+                    Task::perform(
+                        crate::soundcloud::api_helpers::like_track_with_refresh(
+                            token_manager,
+                            track,
+                        ),
+                        to_result(true),
+                    )
+                }
+            }
+            Message::CurrentTrackLikeChanged(track_id, liked, token_manager) => {
+                self.token_manager = Some(token_manager);
+                self.liked_changes.insert(track_id, liked);
+                self.show_toast(if liked {
+                    "Added to your likes"
+                } else {
+                    "Removed from your likes"
+                });
+                Task::none()
+            }
+            Message::LikeCurrentTrackFailed(error, token_manager) => {
+                eprintln!("Failed to like track: {}", error);
+                self.token_manager = Some(token_manager);
+                self.show_toast(format!("Couldn't like the track: {}", error));
+                Task::none()
+            }
+            _ => Task::none(),
+        };
+
+        // Combine both tasks
         Task::batch([page_task, app_task])
     }
 
     fn subscription(&self) -> iced::Subscription<Message> {
         let keyboard_listerer = event::listen_with(|event, status, _| match (event, status) {
+            // Ctrl (Cmd on macOS) with the arrows skips tracks and changes the
+            // volume; without it they seek and move the selection below
+            (
+                Event::Keyboard(KeyPressed {
+                    key: Key::Named(named),
+                    modifiers,
+                    ..
+                }),
+                Status::Ignored,
+            ) if modifiers.command() => match named {
+                Named::ArrowRight => Some(Message::NextTrack),
+                Named::ArrowLeft => Some(Message::PreviousTrack),
+                Named::ArrowUp => Some(Message::VolumeUp),
+                Named::ArrowDown => Some(Message::VolumeDown),
+                _ => None,
+            },
+            // Ctrl +/-/0 zoom the UI like a browser
+            (
+                Event::Keyboard(KeyPressed {
+                    key: Key::Character(c),
+                    modifiers,
+                    ..
+                }),
+                Status::Ignored,
+            ) if modifiers.command() => match c.as_str() {
+                "=" | "+" => Some(Message::ZoomIn),
+                "-" => Some(Message::ZoomOut),
+                "0" => Some(Message::ResetZoom),
+                _ => None,
+            },
+            // Letters only arrive as Ignored while no text input has focus,
+            // so typing in the search box never triggers these
+            (
+                Event::Keyboard(KeyPressed {
+                    key: Key::Character(c),
+                    modifiers,
+                    ..
+                }),
+                Status::Ignored,
+            ) if modifiers.is_empty() => match c.as_str() {
+                "n" => Some(Message::NextTrack),
+                "p" => Some(Message::PreviousTrack),
+                "m" => Some(Message::ToggleMute),
+                _ => None,
+            },
             (
                 Event::Keyboard(KeyPressed {
                     key: Key::Named(Named::Space),
@@ -506,17 +1839,70 @@ impl MyApp {
                 }),
                 Status::Ignored,
             ) => Some(Message::SeekBackwards),
+            (
+                Event::Keyboard(KeyPressed {
+                    key: Key::Named(Named::ArrowDown),
+                    ..
+                }),
+                Status::Ignored,
+            ) => Some(Message::SelectNextTrack),
+            (
+                Event::Keyboard(KeyPressed {
+                    key: Key::Named(Named::ArrowUp),
+                    ..
+                }),
+                Status::Ignored,
+            ) => Some(Message::SelectPreviousTrack),
+            // A focused text input captures Enter, so submitting a search
+            // never plays the selection
+            (
+                Event::Keyboard(KeyPressed {
+                    key: Key::Named(Named::Enter),
+                    ..
+                }),
+                Status::Ignored,
+            ) => Some(Message::PlaySelectedTrack),
             _ => None,
         });
 
+        // Position, track-end and spectrum updates only matter while audio is
+        // playing; otherwise a slow tick still picks up media-control events
+        // (e.g. play from the OS overlay) and expires toasts
+        let playing_interval = self.settings.ui_tick_interval();
+        let tick_interval = if self.audio_manager.state() == PlayerState::Playing {
+            playing_interval
+        } else {
+            // Never faster than while playing
+            IDLE_TICK_INTERVAL.max(playing_interval)
+        };
+
         let mut subscriptions = vec![
             keyboard_listerer,
-            time::every(Duration::from_millis(100)).map(|_| Message::UiTick), // More frequent for media control responsiveness
+            time::every(tick_interval).map(|_| Message::UiTick),
         ];
 
-        // While the now-playing artwork or any list artwork is animating, redraw
-        // every frame for a smooth fade.
-        if self.artwork_anim.is_animating(Instant::now()) || self.page.is_animating() {
+        // Only the feed page acts on this, and only when scrolled to the top
+        if let Some(minutes) = self.settings.feed_autorefresh_mins.filter(|&m| m > 0) {
+            subscriptions.push(
+                time::every(Duration::from_secs(u64::from(minutes) * 60))
+                    .map(|_| Message::FeedAutorefresh),
+            );
+        }
+
+        subscriptions.push(window::open_events().map(Message::WindowOpened));
+        subscriptions.push(event::listen_with(|event, _, _| match event {
+            Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+            Event::Window(window::Event::Moved(position)) => Some(Message::WindowMoved(position)),
+            _ => None,
+        }));
+
+        // While the now-playing artwork or any list artwork is animating, or a
+        // spinner is showing, redraw every frame.
+        let picker_loading = self.playlist_picker.is_some() && self.my_playlists.is_none();
+        if self.artwork_anim.is_animating(Instant::now())
+            || self.page.is_animating()
+            || picker_loading
+        {
             subscriptions.push(window::frames().map(|_| Message::UiTick));
         }
 
@@ -524,26 +1910,85 @@ impl MyApp {
     }
 
     fn view(&self) -> iced::Element<'_, Message> {
+        let mut page = stack![
+            container(self.page.view(self.now_playing, self.settings.hide_blocked))
+                .padding(5)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ];
+        if let Some(track) = &self.playlist_picker {
+            page = page.push(
+                container(widgets::playlist_picker(
+                    &track.title,
+                    self.my_playlists.as_deref(),
+                ))
+                .center(Length::Fill),
+            );
+        }
+        if self.settings.show_rate_limit
+            && let Some(info) = crate::soundcloud::api::rate_limit().filter(|i| i.is_low())
+        {
+            page = page.push(
+                container(
+                    text(format!(
+                        "{} of {} API requests left",
+                        info.remaining, info.limit
+                    ))
+                    .size(12)
+                    .style(text::secondary),
+                )
+                .align_right(Length::Fill)
+                .padding([4, 12]),
+            );
+        }
+        if let Some(saved) = &self.saved_queue
+            && let Some(track) = saved.current_track()
+            && self.token_manager.is_some()
+        {
+            page = page.push(
+                container(widgets::restore_queue_prompt(
+                    &track.title,
+                    saved.tracks.len(),
+                ))
+                .center_x(Length::Fill)
+                .padding(16),
+            );
+        }
+        if let Some((message, _)) = &self.toast {
+            page = page.push(
+                container(widgets::toast(message))
+                    .align_bottom(Length::Fill)
+                    .center_x(Length::Fill)
+                    .padding(16),
+            );
+        }
+
         column![
             widgets::get_playback_bar(
                 self.artwork.clone(),
                 self.artwork_anim.interpolate(0.0, 1.0, Instant::now()),
                 &self.title,
                 &self.user,
+                self.queue_manager.current_track(),
+                self.queue_manager
+                    .current_track()
+                    .is_some_and(|track| self.is_liked(track)),
                 self.audio_manager.track_position,
                 self.audio_manager.track_duration,
-                self.audio_manager.progress_bar_value,
-                self.audio_manager.stream_loading,
-                !self.audio_manager.is_empty() && !self.audio_manager.is_paused(),
+                self.scrub_position
+                    .unwrap_or(self.audio_manager.progress_bar_value),
+                self.audio_manager.state(),
                 self.queue_manager.current_position(),
                 self.queue_manager.queue_length(),
-                self.waveform_peaks.clone(),
+                self.waveform_peaks.as_deref(),
+                &self.audio_manager.spectrum_bars,
+                self.playlist_title.as_deref(),
+                self.me_avatar.clone(),
+                self.audio_manager.volume(),
+                self.marquee_offset,
                 &self.settings,
             ),
-            container(self.page.view())
-                .padding(5)
-                .width(Length::Fill)
-                .height(Length::FillPortion(1)),
+            page.height(Length::FillPortion(1)),
         ]
         .into()
     }
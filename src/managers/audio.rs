@@ -1,14 +1,53 @@
 use std::{
-    sync::{Arc, mpsc},
-    time::Duration,
+    ffi::c_void,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
 };
 
-use rodio::{Decoder, OutputStream, Sink};
+#[cfg(feature = "rodio-decoder")]
+use rodio::Decoder;
+use rodio::cpal::traits::HostTrait;
+use rodio::{DeviceTrait, OutputStream, OutputStreamBuilder, Sink, Source};
 use souvlaki::{MediaControls, MediaMetadata, MediaPlayback, PlatformConfig};
 
-use crate::managers::audio_buffer::SharedAudioBuffer;
+use crate::managers::audio_buffer::{SharedAudioBuffer, StreamReader};
+#[cfg(not(feature = "rodio-decoder"))]
+use crate::managers::decoder::SymphoniaSource;
+use crate::managers::equalizer::{EQ_BANDS, EqControls, EqSource};
+use crate::managers::replay_cache::{RecordingSource, ReplayCache};
+use crate::managers::spectrum::{SPECTRUM_BANDS, SpectrumTap, TappedSource};
+
+/// How far short of the downloaded edge a clamped seek lands, so playback
+/// doesn't immediately stall waiting on the next segment
+#[cfg(not(feature = "rodio-decoder"))]
+const SEEK_HEADROOM: Duration = Duration::from_secs(1);
+
+/// How far the position may drift from what OS media controls were last told
+/// (extrapolated while playing, as they do themselves) before they're updated
+const MEDIA_POSITION_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// Loudness normalization aims every track at this gated RMS level (dBFS)
+const TARGET_LOUDNESS_DB: f32 = -16.0;
+
+/// Limits on the normalization correction; boosts stay small because rodio
+/// clips anything pushed past full scale
+const MAX_NORMALIZATION_BOOST_DB: f32 = 3.0;
+const MAX_NORMALIZATION_CUT_DB: f32 = 12.0;
+
+/// Linear volume factor that brings a track measured at `loudness_db` to the
+/// normalization target
+pub fn normalization_gain(loudness_db: f32) -> f32 {
+    let correction_db = (TARGET_LOUDNESS_DB - loudness_db)
+        .clamp(-MAX_NORMALIZATION_CUT_DB, MAX_NORMALIZATION_BOOST_DB);
+    10f32.powf(correction_db / 20.0)
+}
 
 /// Find the start of an ADTS frame at or before the given byte offset
+#[cfg(feature = "rodio-decoder")]
 fn find_adts_frame_start(data: &[u8], target_offset: usize) -> usize {
     // Start from target and scan backward to find ADTS sync word
     let mut offset = target_offset.min(data.len().saturating_sub(7));
@@ -51,47 +90,135 @@ fn find_adts_frame_start(data: &[u8], target_offset: usize) -> usize {
     0
 }
 
+/// Names of the output devices currently available on the default host
+pub fn list_output_devices() -> Vec<String> {
+    match rodio::cpal::default_host().output_devices() {
+        Ok(devices) => devices.filter_map(|d| d.name().ok()).collect(),
+        Err(e) => {
+            eprintln!("Failed to list output devices: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Open a stream on the named output device, falling back to the system
+/// default when no name is given or the device is gone. `device_lost` is
+/// raised if the device disappears while the stream is open.
+fn open_output_stream(
+    device_name: Option<&str>,
+    device_lost: &Arc<AtomicBool>,
+) -> Result<OutputStream, String> {
+    let host = rodio::cpal::default_host();
+
+    if let Some(name) = device_name {
+        let device = host
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == name)));
+        match device {
+            Some(device) => match open_stream_on(device, device_lost) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => eprintln!("Failed to open output device {}: {}", name, e),
+            },
+            None => eprintln!("Output device {} not found, using the default", name),
+        }
+    }
+
+    let device = host
+        .default_output_device()
+        .ok_or("No audio output device available")?;
+    open_stream_on(device, device_lost)
+}
+
+fn open_stream_on(
+    device: rodio::Device,
+    device_lost: &Arc<AtomicBool>,
+) -> Result<OutputStream, String> {
+    let device_lost = Arc::clone(device_lost);
+    OutputStreamBuilder::from_device(device)
+        .map_err(|e| e.to_string())?
+        .with_error_callback(move |e| match e {
+            rodio::cpal::StreamError::DeviceNotAvailable => {
+                device_lost.store(true, Ordering::Relaxed)
+            }
+            e => eprintln!("Audio output error: {}", e),
+        })
+        .open_stream_or_fallback()
+        .map_err(|e| e.to_string())
+}
+
+/// Create a decoder for a (possibly still downloading) AAC stream
+#[cfg(not(feature = "rodio-decoder"))]
+fn decode(reader: StreamReader) -> Result<SymphoniaSource, String> {
+    SymphoniaSource::new(reader, "aac").map_err(|e| format!("Failed to create decoder: {}", e))
+}
+
+/// Create a decoder for a (possibly still downloading) AAC stream
+#[cfg(feature = "rodio-decoder")]
+fn decode(reader: StreamReader) -> Result<Decoder<StreamReader>, String> {
+    Decoder::builder()
+        .with_data(reader)
+        .with_hint("aac")
+        .build()
+        .map_err(|e| format!("Failed to create decoder: {}", e))
+}
+
+/// What the player is doing, as shown in the playback bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerState {
+    Idle,    // Nothing queued in the sink and nothing on its way
+    Loading, // A track's stream is being fetched
+    Playing,
+    Paused,
+}
+
 /// Manages audio playback state, seeking, and OS media controls integration
 pub struct AudioManager {
-    pub stream: OutputStream,
+    stream: Option<OutputStream>, // None when no output device could be opened
     pub sink: Sink,
     pub track_duration: Duration,
     pub track_position: Duration,
     pub progress_bar_value: f32,
     pub stream_loading: bool,
     pub current_track_data: Option<Arc<SharedAudioBuffer>>, // Streamed track data, also used for backward seeking
-    position_offset: Duration, // Offset to add to sink.get_pos() after seeking
-    media_controls: MediaControls,
+    position_offset: Duration, // Offset to add to sink.get_pos() after a rebuild seek (rodio-decoder only)
+    device_lost: Arc<AtomicBool>, // Raised by the stream's error callback when the device goes away
+    user_volume: f32,          // Manual volume (0.0 to 1.0)
+    normalization_gain: f32,   // Per-track loudness correction, 1.0 when off
+    eq: Arc<EqControls>,       // Band gains, shared with every source built
+    spectrum_tap: Arc<SpectrumTap>, // Recent samples for the visualizer
+    pub spectrum_bars: Vec<f32>, // Smoothed band levels, refreshed by update_spectrum
+    replay_cache_limit: Option<Duration>, // Longest track whose decoded samples are kept for replay; None is off
+    replay_cache: Option<Arc<ReplayCache>>, // Decoded samples of the current track, when within the limit
+    media_controls: Option<MediaControls>,  // None when the OS integration failed to start
+    media_reported: Option<(bool, Duration, Instant)>, // Paused flag and position last sent to media controls, and when
+    media_event_sender: mpsc::Sender<souvlaki::MediaControlEvent>, // Handed to each MediaControls instance
     pub media_event_receiver: mpsc::Receiver<souvlaki::MediaControlEvent>,
 }
 
 impl AudioManager {
-    /// Initialize a new AudioManager with default audio output and media controls
-    pub fn new() -> Self {
-        let stream = rodio::OutputStreamBuilder::open_default_stream()
-            .expect("Failed to open default audio output stream");
-        let sink = Sink::connect_new(stream.mixer());
-
-        // Initialize media controls with channel
-        let (sender, receiver) = mpsc::channel();
-        let hwnd = None; // For Windows, you might need to get the window handle
-        let config = PlatformConfig {
-            dbus_name: "rustwave",
-            display_name: "Rustwave",
-            hwnd,
+    /// Initialize a new AudioManager on the given output device (the system
+    /// default if None or unavailable) with media controls (deferred on Windows)
+    pub fn new(output_device: Option<&str>) -> Self {
+        // Without an output device the app still runs, just without audio,
+        // until one is picked in settings
+        let device_lost = Arc::new(AtomicBool::new(false));
+        let stream = match open_output_stream(output_device, &device_lost) {
+            Ok(stream) => Some(stream),
+            Err(e) => {
+                eprintln!("No audio output, playback disabled: {}", e);
+                None
+            }
+        };
+        let sink = match &stream {
+            Some(stream) => Sink::connect_new(stream.mixer()),
+            None => Sink::new().0,
         };
 
-        let mut media_controls =
-            MediaControls::new(config).expect("Failed to initialize media controls");
-
-        // Attach the event handler
-        media_controls
-            .attach(move |event| {
-                let _ = sender.send(event);
-            })
-            .expect("Failed to attach media controls event handler");
+        // Media control events arrive on this channel once controls are up
+        let (sender, receiver) = mpsc::channel();
 
-        Self {
+        let mut manager = Self {
             stream,
             sink,
             track_duration: Duration::from_secs(0),
@@ -100,13 +227,141 @@ impl AudioManager {
             stream_loading: false,
             current_track_data: None,
             position_offset: Duration::from_secs(0),
-            media_controls,
+            device_lost,
+            user_volume: 1.0,
+            normalization_gain: 1.0,
+            eq: EqControls::new(&[0.0; EQ_BANDS]),
+            spectrum_tap: SpectrumTap::new(),
+            spectrum_bars: vec![0.0; SPECTRUM_BANDS],
+            replay_cache_limit: None,
+            replay_cache: None,
+            media_controls: None,
+            media_reported: None,
+            media_event_sender: sender,
             media_event_receiver: receiver,
+        };
+
+        // Windows controls attach to the app window, so they start once its
+        // handle is known (see init_media_controls)
+        if cfg!(not(windows)) {
+            manager.init_media_controls(None);
         }
+        manager
+    }
+
+    /// Start OS media controls, replacing any already running. `hwnd` is the
+    /// app window's handle, which Windows requires; other platforms pass None.
+    pub fn init_media_controls(&mut self, hwnd: Option<*mut c_void>) {
+        let config = PlatformConfig {
+            dbus_name: "rustwave",
+            display_name: "Rustwave",
+            hwnd,
+        };
+
+        // Minimal Linux setups have no DBus session; play on without OS
+        // media integration there rather than failing to start
+        let sender = self.media_event_sender.clone();
+        self.media_controls = MediaControls::new(config)
+            .and_then(|mut controls| {
+                // Attach the event handler
+                controls.attach(move |event| {
+                    let _ = sender.send(event);
+                })?;
+                Ok(controls)
+            })
+            .inspect_err(|e| eprintln!("Media controls unavailable: {:?}", e))
+            .ok();
+    }
+
+    /// Whether an output device is open; without one tracks can't be loaded
+    pub fn has_output(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// A fresh sink on the output stream's mixer. Without an output device
+    /// it's a detached sink that never gets a source appended.
+    fn new_sink(&self) -> Sink {
+        match &self.stream {
+            Some(stream) => Sink::connect_new(stream.mixer()),
+            None => Sink::new().0,
+        }
+    }
+
+    /// Set the loudness correction for the current track; multiplies with the
+    /// manual volume
+    pub fn set_normalization_gain(&mut self, gain: f32) {
+        self.normalization_gain = gain;
+        self.apply_volume();
+    }
+
+    /// The manual volume, 0.0 to 1.0
+    pub fn volume(&self) -> f32 {
+        self.user_volume
+    }
+
+    /// Set the manual volume, clamped to 0.0 to 1.0
+    pub fn set_volume(&mut self, volume: f32) {
+        self.user_volume = volume.clamp(0.0, 1.0);
+        self.apply_volume();
+    }
+
+    /// Push the combined manual and normalization volume to the sink. Sinks
+    /// start at full volume, so call this whenever one is recreated.
+    fn apply_volume(&self) {
+        self.sink
+            .set_volume(self.user_volume * self.normalization_gain);
+    }
+
+    /// Move playback to another output device (None for the system default),
+    /// picking the current track back up at the same position
+    pub fn set_output_device(&mut self, name: Option<&str>) -> Result<(), String> {
+        let stream = open_output_stream(name, &self.device_lost)?;
+
+        let position = self.position_offset + self.sink.get_pos();
+        let was_paused = self.sink.is_paused();
+        let was_playing = !self.sink.empty();
+
+        // The old sink feeds the old stream's mixer, so both are replaced
+        self.stream = Some(stream);
+        self.sink = self.new_sink();
+        self.apply_volume();
+        self.position_offset = Duration::from_secs(0);
+
+        if let Some(buffer) = self.current_track_data.clone()
+            && was_playing
+        {
+            self.sink
+                .append(self.playback_chain(decode(buffer.reader_at(0))?));
+            if was_paused {
+                self.sink.pause();
+            }
+            self.seek_to_absolute(position);
+        }
+
+        Ok(())
+    }
+
+    /// Set the equalizer band gains (dB); applies to the playing track and
+    /// any source built later, so it survives sink rebuilds
+    pub fn set_eq_gains(&self, gains: &[f32; EQ_BANDS]) {
+        self.eq.set_gains(gains);
+    }
+
+    /// Chain the playback stages (equalizer, then the visualizer tap) onto a
+    /// freshly decoded source
+    fn playback_chain<S: Source>(&self, source: S) -> TappedSource<EqSource<S>> {
+        TappedSource::new(
+            EqSource::new(source, Arc::clone(&self.eq)),
+            Arc::clone(&self.spectrum_tap),
+        )
     }
 
     /// Load and play a track from a (possibly still downloading) audio buffer
     pub fn load_track(&mut self, buffer: Arc<SharedAudioBuffer>) -> Result<(), String> {
+        if self.stream.is_none() {
+            return Err("No audio output device available".to_string());
+        }
+
         // Stop the previous track's download and wake any reader blocked on
         // it, so its source drains off the shared mixer
         if let Some(old) = self.current_track_data.take()
@@ -118,26 +373,105 @@ impl AudioManager {
         // Resume the download if this buffer was a paused prefetch
         buffer.activate();
 
+        // Start the position over now rather than on the next update_position:
+        // a reload of the same track (repeat one) would otherwise still read
+        // as ended and fire TrackEnded again
         self.position_offset = Duration::from_secs(0);
+        self.track_position = Duration::from_secs(0);
+        self.progress_bar_value = 0.0;
 
         // Recreate a fresh Sink on our existing, long-lived stream's mixer
-        self.sink = Sink::connect_new(self.stream.mixer());
+        self.sink = self.new_sink();
+        self.apply_volume();
 
-        let source = Decoder::builder()
-            .with_data(buffer.reader_at(0))
-            .with_hint("aac")
-            .build()
-            .map_err(|e| format!("Failed to create decoder: {}", e))?;
+        let source = decode(buffer.reader_at(0))?;
 
         self.current_track_data = Some(buffer);
         self.sink.clear();
-        self.sink.append(source);
+        // The limit is only set while repeat one is on
+        self.replay_cache = self
+            .replay_cache_limit
+            .filter(|limit| self.track_duration <= *limit)
+            .map(|limit| {
+                ReplayCache::new(
+                    limit,
+                    self.track_duration,
+                    source.channels(),
+                    source.sample_rate(),
+                )
+            });
+        match &self.replay_cache {
+            Some(cache) => self
+                .sink
+                .append(self.playback_chain(RecordingSource::new(source, Arc::clone(cache)))),
+            None => self.sink.append(self.playback_chain(source)),
+        }
         self.sink.play();
         self.stream_loading = false;
+        self.set_media_playback(MediaPlayback::Playing {
+            progress: Some(souvlaki::MediaPosition(Duration::from_secs(0))),
+        });
 
         Ok(())
     }
 
+    /// Keep the decoded samples of tracks up to `limit` long so `replay_track`
+    /// can restart them without decoding again, at roughly 21 MB per minute of
+    /// audio. Only set while repeat one is on; None turns it off and frees the
+    /// samples. Switching it on mid-track records from the track's next start,
+    /// i.e. the first repeat decodes again and the ones after use the samples.
+    pub fn set_replay_cache_limit(&mut self, limit: Option<Duration>) {
+        self.replay_cache_limit = limit;
+        if limit.is_none() {
+            self.replay_cache = None;
+        }
+    }
+
+    /// Restart the current track from the top, from its decoded samples when
+    /// they were kept, otherwise by decoding the stored data again
+    pub fn replay_track(&mut self) -> Result<(), String> {
+        let cached = self
+            .replay_cache
+            .as_ref()
+            .and_then(|cache| cache.replay(self.track_position));
+        let Some(source) = cached else {
+            return match self.current_track_data.clone() {
+                Some(buffer) => self.load_track(buffer),
+                None => Ok(()),
+            };
+        };
+
+        self.position_offset = Duration::from_secs(0);
+        self.track_position = Duration::from_secs(0);
+        self.progress_bar_value = 0.0;
+
+        self.sink = self.new_sink();
+        self.apply_volume();
+        self.sink.append(self.playback_chain(source));
+        self.sink.play();
+        self.set_media_playback(MediaPlayback::Playing {
+            progress: Some(souvlaki::MediaPosition(Duration::from_secs(0))),
+        });
+
+        Ok(())
+    }
+
+    /// Report the playback state to OS media controls, if available
+    fn set_media_playback(&mut self, playback: MediaPlayback) {
+        self.media_reported = match &playback {
+            MediaPlayback::Playing {
+                progress: Some(position),
+            } => Some((false, position.0, Instant::now())),
+            MediaPlayback::Paused {
+                progress: Some(position),
+            } => Some((true, position.0, Instant::now())),
+            _ => None,
+        };
+        if let Some(controls) = &mut self.media_controls {
+            let _ = controls.set_playback(playback);
+        }
+    }
+
     /// Update track metadata in OS media controls
     pub fn update_metadata(&mut self, title: &str, artist: &str, duration: Duration) {
         let metadata = MediaMetadata {
@@ -147,8 +481,10 @@ impl AudioManager {
             cover_url: None,
             duration: Some(duration),
         };
-        let _ = self.media_controls.set_metadata(metadata);
-        let _ = self.media_controls.set_playback(MediaPlayback::Playing {
+        if let Some(controls) = &mut self.media_controls {
+            let _ = controls.set_metadata(metadata);
+        }
+        self.set_media_playback(MediaPlayback::Playing {
             progress: Some(souvlaki::MediaPosition(Duration::from_secs(0))),
         });
     }
@@ -158,12 +494,12 @@ impl AudioManager {
         if !self.sink.empty() {
             if self.sink.is_paused() {
                 self.sink.play();
-                let _ = self.media_controls.set_playback(MediaPlayback::Playing {
+                self.set_media_playback(MediaPlayback::Playing {
                     progress: Some(souvlaki::MediaPosition(self.track_position)),
                 });
             } else {
                 self.sink.pause();
-                let _ = self.media_controls.set_playback(MediaPlayback::Paused {
+                self.set_media_playback(MediaPlayback::Paused {
                     progress: Some(souvlaki::MediaPosition(self.track_position)),
                 });
             }
@@ -174,7 +510,7 @@ impl AudioManager {
     pub fn play(&mut self) {
         if !self.sink.empty() && self.sink.is_paused() {
             self.sink.play();
-            let _ = self.media_controls.set_playback(MediaPlayback::Playing {
+            self.set_media_playback(MediaPlayback::Playing {
                 progress: Some(souvlaki::MediaPosition(self.track_position)),
             });
         }
@@ -184,7 +520,7 @@ impl AudioManager {
     pub fn pause(&mut self) {
         if !self.sink.empty() && !self.sink.is_paused() {
             self.sink.pause();
-            let _ = self.media_controls.set_playback(MediaPlayback::Paused {
+            self.set_media_playback(MediaPlayback::Paused {
                 progress: Some(souvlaki::MediaPosition(self.track_position)),
             });
         }
@@ -199,12 +535,57 @@ impl AudioManager {
         }
     }
 
+    /// Seek to an absolute position in place through the decoder, clamped to
+    /// the portion of the track downloaded so far
+    #[cfg(not(feature = "rodio-decoder"))]
+    pub fn seek_to_absolute(&mut self, position: Duration) -> bool {
+        let Some(buffer) = self.current_track_data.clone() else {
+            return false;
+        };
+        // A recording with a gap in it can't stand in for the track
+        if let Some(cache) = &self.replay_cache {
+            cache.invalidate();
+        }
+
+        let available = buffer.available();
+        if available == 0 {
+            return false;
+        }
+        // While the download is still running the final size is extrapolated
+        // from segment progress; exact once complete
+        let estimated_total = buffer.estimated_total().max(available);
+
+        // Clamp to downloaded data so the audio thread never blocks waiting
+        // for a far-ahead seek target
+        let downloaded = self
+            .track_duration
+            .mul_f64(available as f64 / estimated_total as f64);
+        let target = if position < downloaded {
+            position
+        } else {
+            downloaded.saturating_sub(SEEK_HEADROOM)
+        };
+
+        match self.sink.try_seek(target) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Seek failed: {}", e);
+                false
+            }
+        }
+    }
+
     /// Seek to an absolute position by recreating the decoder from the appropriate byte offset
     /// For ADTS streams, we calculate the byte offset and find the nearest frame boundary
+    #[cfg(feature = "rodio-decoder")]
     pub fn seek_to_absolute(&mut self, position: Duration) -> bool {
         let Some(buffer) = self.current_track_data.clone() else {
             return false;
         };
+        // A recording with a gap in it can't stand in for the track
+        if let Some(cache) = &self.replay_cache {
+            cache.invalidate();
+        }
 
         let was_paused = self.sink.is_paused();
 
@@ -240,15 +621,12 @@ impl AudioManager {
         };
 
         // Recreate the sink and decoder from the offset
-        self.sink = Sink::connect_new(self.stream.mixer());
+        self.sink = self.new_sink();
+        self.apply_volume();
 
-        match Decoder::builder()
-            .with_data(buffer.reader_at(start_offset))
-            .with_hint("aac")
-            .build()
-        {
+        match decode(buffer.reader_at(start_offset)) {
             Ok(source) => {
-                self.sink.append(source);
+                self.sink.append(self.playback_chain(source));
                 self.position_offset = effective_position;
 
                 if was_paused {
@@ -260,12 +638,8 @@ impl AudioManager {
             }
             Err(_) => {
                 // Fall back to playing from beginning
-                if let Ok(source) = Decoder::builder()
-                    .with_data(buffer.reader_at(0))
-                    .with_hint("aac")
-                    .build()
-                {
-                    self.sink.append(source);
+                if let Ok(source) = decode(buffer.reader_at(0)) {
+                    self.sink.append(self.playback_chain(source));
                     self.position_offset = Duration::from_secs(0);
                     if was_paused {
                         self.sink.pause();
@@ -291,16 +665,43 @@ impl AudioManager {
 
     /// Seek to a specific position as a percentage (0.0 to 100.0)
     pub fn seek_to_position(&mut self, percent: f32) {
-        if !self.sink.empty() {
-            let new_position = self.track_duration.mul_f32(percent / 100.0);
-            if self.seek_to_absolute(new_position) {
-                self.progress_bar_value = percent;
-            }
+        self.seek_to(self.track_duration.mul_f32(percent / 100.0));
+    }
+
+    /// Seek to an absolute position and show it straight away, rather than
+    /// on the next update_position, so OS media controls see it too
+    pub fn seek_to(&mut self, position: Duration) {
+        if self.sink.empty() || !self.seek_to_absolute(position) {
+            return;
+        }
+
+        let position = position.min(self.track_duration);
+        self.track_position = position;
+        self.progress_bar_value = if self.track_duration.is_zero() {
+            0.0
+        } else {
+            (position.as_secs_f32() / self.track_duration.as_secs_f32()) * 100.0
+        };
+
+        let progress = Some(souvlaki::MediaPosition(position));
+        if self.sink.is_paused() {
+            self.set_media_playback(MediaPlayback::Paused { progress });
+        } else {
+            self.set_media_playback(MediaPlayback::Playing { progress });
         }
     }
 
-    /// Update playback position and progress bar (call this on a timer)
-    pub fn update_position(&mut self) {
+    /// Update playback position and progress bar (call this on a timer).
+    /// If the output device went away since the last call, playback moves to
+    /// the system default device and the outcome of that is returned.
+    pub fn update_position(&mut self) -> Option<Result<(), String>> {
+        // The dead stream stops pulling samples, so the sink's position is
+        // still where the audio cut out
+        let recovery = self
+            .device_lost
+            .swap(false, Ordering::Relaxed)
+            .then(|| self.set_output_device(None));
+
         if !self.sink.empty() {
             // Add position_offset to get absolute track position after seeking
             let new_position = self.position_offset + self.sink.get_pos();
@@ -309,17 +710,47 @@ impl AudioManager {
             self.progress_bar_value =
                 (new_position.as_secs_f32() / self.track_duration.as_secs_f32()) * 100.0;
 
-            // Update media controls with current position
-            let playback_state = if self.sink.is_paused() {
-                MediaPlayback::Paused {
-                    progress: Some(souvlaki::MediaPosition(self.track_position)),
-                }
-            } else {
-                MediaPlayback::Playing {
-                    progress: Some(souvlaki::MediaPosition(self.track_position)),
+            // Update media controls only when they'd otherwise show the wrong
+            // position, not on every tick
+            let paused = self.sink.is_paused();
+            let stale = match self.media_reported {
+                Some((was_paused, position, at)) if was_paused == paused => {
+                    let expected = if paused {
+                        position
+                    } else {
+                        position + at.elapsed()
+                    };
+                    expected.abs_diff(new_position) > MEDIA_POSITION_TOLERANCE
                 }
+                _ => true,
             };
-            let _ = self.media_controls.set_playback(playback_state);
+            if stale {
+                let progress = Some(souvlaki::MediaPosition(new_position));
+                self.set_media_playback(if paused {
+                    MediaPlayback::Paused { progress }
+                } else {
+                    MediaPlayback::Playing { progress }
+                });
+            }
+        }
+
+        recovery
+    }
+
+    /// Refresh the visualizer bars from the latest decoded samples (call this
+    /// on the UI tick while the spectrum seekbar is shown)
+    pub fn update_spectrum(&mut self) {
+        let levels = if self.sink.empty() || self.sink.is_paused() {
+            None
+        } else {
+            self.spectrum_tap.bands(SPECTRUM_BANDS)
+        };
+
+        // Bars jump up to new peaks but fall back gradually, so they read as
+        // motion rather than flicker at the tick rate
+        for (i, bar) in self.spectrum_bars.iter_mut().enumerate() {
+            let level = levels.as_ref().map_or(0.0, |l| l[i]);
+            *bar = level.max(*bar * 0.7);
         }
     }
 
@@ -338,8 +769,9 @@ impl AudioManager {
         if let Some(buffer) = &self.current_track_data {
             buffer.cancel();
         }
+        self.replay_cache = None;
         self.sink.clear();
-        let _ = self.media_controls.set_playback(MediaPlayback::Stopped);
+        self.set_media_playback(MediaPlayback::Stopped);
     }
 
     /// Check if sink is empty
@@ -351,4 +783,18 @@ impl AudioManager {
     pub fn is_paused(&self) -> bool {
         self.sink.is_paused()
     }
+
+    /// Current player state. Loading wins over whatever the sink still holds,
+    /// since it is cleared as soon as the next track starts loading.
+    pub fn state(&self) -> PlayerState {
+        if self.stream_loading {
+            PlayerState::Loading
+        } else if self.sink.empty() {
+            PlayerState::Idle
+        } else if self.sink.is_paused() {
+            PlayerState::Paused
+        } else {
+            PlayerState::Playing
+        }
+    }
 }
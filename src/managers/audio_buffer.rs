@@ -108,6 +108,12 @@ impl SharedAudioBuffer {
         self.inner.lock().unwrap().cancelled
     }
 
+    /// Whether every segment has downloaded, i.e. the stream wasn't cut short
+    /// by a cancel or a failed download
+    pub fn is_complete(&self) -> bool {
+        self.inner.lock().unwrap().completed_segments >= self.total_segments
+    }
+
     /// Number of bytes buffered so far.
     pub fn available(&self) -> usize {
         self.inner.lock().unwrap().data.len()
@@ -128,6 +134,7 @@ impl SharedAudioBuffer {
     }
 
     /// Run `f` against the currently buffered bytes (e.g. ADTS frame scans).
+    #[cfg_attr(not(feature = "rodio-decoder"), allow(dead_code))]
     pub fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
         let inner = self.inner.lock().unwrap();
         f(&inner.data)
@@ -254,6 +261,14 @@ mod tests {
         assert_eq!(n, 0, "cancelled stream must EOF, not block");
         assert!(buffer.is_cancelled());
         assert_eq!(buffer.available(), 2, "buffered data survives cancel");
+        assert!(!buffer.is_complete(), "a cancelled download is partial");
+
+        // Cancelling once every segment is in leaves the stream whole
+        let buffer = SharedAudioBuffer::new(2, 0, true);
+        buffer.append_segment(&[1]);
+        buffer.append_segment(&[2]);
+        buffer.cancel();
+        assert!(buffer.is_complete());
     }
 
     #[tokio::test]
@@ -0,0 +1,310 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use rodio::Source;
+use rodio::source::SeekError;
+use symphonia::core::audio::{Channels, SampleBuffer, SignalSpec};
+use symphonia::core::codecs::{CODEC_TYPE_NULL, Decoder, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::managers::audio_buffer::{SharedAudioBuffer, StreamReader};
+
+/// Frames folded into each coarse peak while decoding, before the final
+/// downsample to the requested width (~23ms at 44.1kHz)
+const PEAK_BLOCK_FRAMES: usize = 1024;
+
+impl MediaSource for StreamReader {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    // The final size isn't known while the download is running; leaving it
+    // unset keeps symphonia from probing the end of the stream
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Symphonia-backed `Source` over a streamed track buffer. Seeks go through
+/// the demuxer in place, so moving backwards doesn't have to rebuild the
+/// source the way rodio's non-seekable `Decoder` does.
+pub struct SymphoniaSource {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    spec: SignalSpec,
+    buffer: SampleBuffer<f32>,
+    offset: usize, // Next sample to hand out from `buffer`
+    total_duration: Option<Duration>,
+}
+
+impl SymphoniaSource {
+    /// Probe the stream's container (ADTS, MP4, FLAC, MP3, Ogg, WAV) and
+    /// decode the first packet so the signal spec is known before playback
+    pub fn new(reader: StreamReader, extension_hint: &str) -> Result<Self, String> {
+        let mss = MediaSourceStream::new(Box::new(reader), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension(extension_hint);
+
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                mss,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|e| format!("Unrecognized audio format: {}", e))?;
+        let format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or("No playable audio track in stream")?;
+        let track_id = track.id;
+        let total_duration = track
+            .codec_params
+            .time_base
+            .zip(track.codec_params.n_frames)
+            .map(|(base, frames)| base.calc_time(frames).into());
+        let spec = SignalSpec::new(
+            track.codec_params.sample_rate.unwrap_or(44_100),
+            track
+                .codec_params
+                .channels
+                .unwrap_or(Channels::FRONT_LEFT | Channels::FRONT_RIGHT),
+        );
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(|e| format!("Unsupported codec: {}", e))?;
+
+        let mut source = Self {
+            format,
+            decoder,
+            track_id,
+            spec,
+            buffer: SampleBuffer::new(0, spec),
+            offset: 0,
+            total_duration,
+        };
+        if !source.decode_next_packet() {
+            return Err("No audio frames found in stream".to_string());
+        }
+        Ok(source)
+    }
+
+    /// Decode packets until one yields audio and refill the sample buffer.
+    /// Returns false at the end of the stream.
+    fn decode_next_packet(&mut self) -> bool {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::ResetRequired) => {
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(_) => return false,
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            match self.decoder.decode(&packet) {
+                Ok(decoded) if decoded.frames() > 0 => {
+                    let spec = *decoded.spec();
+                    let needed = decoded.capacity() * spec.channels.count();
+                    if spec != self.spec || self.buffer.capacity() < needed {
+                        self.buffer = SampleBuffer::new(decoded.capacity() as u64, spec);
+                        self.spec = spec;
+                    }
+                    self.buffer.copy_interleaved_ref(decoded);
+                    self.offset = 0;
+                    return true;
+                }
+                // Metadata-only packets and corrupt frames are skipped rather
+                // than ending playback
+                Ok(_) | Err(Error::DecodeError(_)) => continue,
+                Err(_) => return false,
+            }
+        }
+    }
+}
+
+impl Iterator for SymphoniaSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.offset >= self.buffer.len() && !self.decode_next_packet() {
+            return None;
+        }
+        let sample = self.buffer.samples()[self.offset];
+        self.offset += 1;
+        Some(sample)
+    }
+}
+
+impl Source for SymphoniaSource {
+    fn current_span_len(&self) -> Option<usize> {
+        Some(self.buffer.len())
+    }
+
+    fn channels(&self) -> u16 {
+        self.spec.channels.count() as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.spec.rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let target = self.total_duration.map_or(pos, |total| pos.min(total));
+        // Remember which channel comes next so the interleaving stays aligned
+        let active_channel = self.offset % self.channels().max(1) as usize;
+
+        self.format
+            .seek(
+                SeekMode::Coarse,
+                SeekTo::Time {
+                    time: target.into(),
+                    track_id: Some(self.track_id),
+                },
+            )
+            .map_err(|e| SeekError::Other(Box::new(e)))?;
+
+        // The demuxer moved without the decoder knowing; drop its state and
+        // whatever was decoded before the seek
+        self.decoder.reset();
+        self.offset = self.buffer.len();
+        for _ in 0..active_channel {
+            self.next();
+        }
+        Ok(())
+    }
+}
+
+/// Results of decoding a whole track off the UI thread
+#[derive(Debug, Clone)]
+pub struct TrackAnalysis {
+    pub peaks: Vec<f32>,  // Waveform peaks, see downsample_peaks
+    pub loudness_db: f32, // Gated RMS loudness in dBFS
+}
+
+/// Decode a whole track to compute waveform peaks (max amplitude per bucket,
+/// normalized to the loudest bucket) for tracks without a SoundCloud waveform
+/// image, and its loudness for volume normalization. Blocks until the stream
+/// finishes downloading, so run it off the UI thread. A buffer cancelled
+/// before it finished ends it early with an error, since the audio is partial.
+pub fn analyze_track(
+    buffer: Arc<SharedAudioBuffer>,
+    target_width: usize,
+) -> Result<TrackAnalysis, String> {
+    let source = SymphoniaSource::new(buffer.reader_at(0), "aac")?;
+    let block_len = PEAK_BLOCK_FRAMES * source.channels().max(1) as usize;
+
+    let mut peak_blocks = Vec::new();
+    let mut power_blocks = Vec::new();
+    let mut block_peak = 0.0f32;
+    let mut block_energy = 0.0f64;
+    let mut block_fill = 0;
+    for sample in source {
+        block_peak = block_peak.max(sample.abs());
+        block_energy += (sample as f64) * (sample as f64);
+        block_fill += 1;
+        if block_fill == block_len {
+            peak_blocks.push(block_peak);
+            power_blocks.push(block_energy / block_len as f64);
+            block_peak = 0.0;
+            block_energy = 0.0;
+            block_fill = 0;
+        }
+    }
+    if block_fill > 0 {
+        peak_blocks.push(block_peak);
+        power_blocks.push(block_energy / block_fill as f64);
+    }
+    if !buffer.is_complete() {
+        return Err("Stream stopped before it finished downloading".to_string());
+    }
+    if peak_blocks.is_empty() {
+        return Err("No audio decoded for analysis".to_string());
+    }
+
+    Ok(TrackAnalysis {
+        peaks: downsample_peaks(&peak_blocks, target_width),
+        loudness_db: gated_loudness(&power_blocks),
+    })
+}
+
+/// Mean power of the blocks in dBFS, ignoring silence and quiet passages the
+/// way loudness meters gate them, so intros and fades don't drag it down
+fn gated_loudness(powers: &[f64]) -> f32 {
+    let to_db = |power: f64| (10.0 * power.max(1e-12).log10()) as f32;
+    let mean_db = |blocks: &[f64]| to_db(blocks.iter().sum::<f64>() / blocks.len().max(1) as f64);
+
+    // Absolute gate at -70 dBFS, then a relative gate 10 dB under what's left
+    let audible: Vec<f64> = powers
+        .iter()
+        .copied()
+        .filter(|&p| to_db(p) > -70.0)
+        .collect();
+    if audible.is_empty() {
+        return -70.0;
+    }
+    let threshold = mean_db(&audible) - 10.0;
+    let gated: Vec<f64> = audible
+        .iter()
+        .copied()
+        .filter(|&p| to_db(p) > threshold)
+        .collect();
+    mean_db(&gated)
+}
+
+/// Reduce block peaks to `target_width` buckets, scaled so the loudest is 1.0
+fn downsample_peaks(blocks: &[f32], target_width: usize) -> Vec<f32> {
+    let mut peaks: Vec<f32> = (0..target_width)
+        .map(|i| {
+            let start = i * blocks.len() / target_width;
+            let end = ((i + 1) * blocks.len() / target_width).max(start + 1);
+            blocks[start..end].iter().copied().fold(0.0, f32::max)
+        })
+        .collect();
+
+    let loudest = peaks.iter().copied().fold(0.0, f32::max);
+    if loudest > 0.0 {
+        peaks.iter_mut().for_each(|peak| *peak /= loudest);
+    }
+    peaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsample_takes_bucket_max_and_normalizes() {
+        let blocks = [0.1, 0.5, 0.2, 0.25, 0.0, 0.05];
+        assert_eq!(downsample_peaks(&blocks, 3), vec![1.0, 0.5, 0.1]);
+
+        // Fewer blocks than buckets repeats blocks rather than indexing past the end
+        assert_eq!(downsample_peaks(&[0.5, 1.0], 4), vec![0.5, 0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn loudness_ignores_silence_and_quiet_passages() {
+        // A -20 dBFS body with a silent intro and a -40 dBFS fade
+        let mut powers = vec![0.0; 50];
+        powers.extend(vec![0.01; 100]);
+        powers.extend(vec![0.0001; 30]);
+        assert!((gated_loudness(&powers) - -20.0).abs() < 0.01);
+
+        assert_eq!(gated_loudness(&[0.0; 10]), -70.0);
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use discord_rich_presence::activity::{Activity, ActivityType, StatusDisplayType, Timestamps};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+
+/// How long to wait before trying Discord again after it wasn't reachable
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(15);
+/// Drift in the computed start time, in seconds, that means the user seeked
+const RESYNC_THRESHOLD_SECS: i64 = 2;
+
+/// The track to show, as of this tick
+pub struct NowPlaying<'a> {
+    pub title: &'a str,
+    pub artist: &'a str,
+    pub paused: bool,
+    pub elapsed: Duration,
+    pub duration: Duration,
+}
+
+/// What was last sent to Discord
+#[derive(Debug, Clone, PartialEq)]
+struct Shown {
+    title: String,
+    artist: String,
+    started_at: Option<i64>, // Unix seconds the track would have started at; None while paused
+    duration_secs: i64,
+}
+
+impl Shown {
+    fn new(now_playing: &NowPlaying, now: i64) -> Self {
+        Self {
+            title: now_playing.title.to_string(),
+            artist: now_playing.artist.to_string(),
+            started_at: (!now_playing.paused).then(|| now - now_playing.elapsed.as_secs() as i64),
+            duration_secs: now_playing.duration.as_secs() as i64,
+        }
+    }
+
+    /// Whether `self` still describes `other` closely enough to skip a resend
+    fn matches(&self, other: &Shown) -> bool {
+        let same_start = match (self.started_at, other.started_at) {
+            (Some(a), Some(b)) => (a - b).abs() < RESYNC_THRESHOLD_SECS,
+            (a, b) => a == b,
+        };
+        self.title == other.title
+            && self.artist == other.artist
+            && self.duration_secs == other.duration_secs
+            && same_start
+    }
+}
+
+/// Discord rich presence ("Listening to {title} by {artist}"). Everything
+/// fails silently: without Discord running the presence just isn't shown.
+/// The IPC calls block, so they run on a worker thread; this side only
+/// decides what should be shown and hands it over.
+pub struct DiscordPresence {
+    sender: mpsc::Sender<Option<Shown>>, // Dropping it stops the worker, which clears the presence
+    shown: Option<Shown>,                // Last handed to the worker
+}
+
+impl DiscordPresence {
+    pub fn new(app_id: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let app_id = app_id.to_string();
+        std::thread::spawn(move || run_worker(&app_id, receiver));
+        Self {
+            sender,
+            shown: None,
+        }
+    }
+
+    /// Bring the presence in line with playback; `None` clears it. Only
+    /// queues an update when something visible changed, so it's cheap per tick.
+    pub fn sync(&mut self, now_playing: Option<NowPlaying>) {
+        let target = now_playing.map(|np| Shown::new(&np, crate::utilities::unix_now()));
+        let unchanged = match (&self.shown, &target) {
+            (Some(shown), Some(target)) => shown.matches(target),
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return;
+        }
+        let _ = self.sender.send(target.clone());
+        self.shown = target;
+    }
+}
+
+/// Owns the IPC client: applies each update from `receiver`, reconnecting
+/// on an interval while Discord isn't reachable, until the sender is dropped
+fn run_worker(app_id: &str, receiver: mpsc::Receiver<Option<Shown>>) {
+    let mut client: Option<DiscordIpcClient> = None;
+    let mut last_attempt: Option<Instant> = None;
+    let mut target: Option<Shown> = None;
+    let mut synced = true; // Whether Discord shows `target`
+
+    loop {
+        match receiver.recv_timeout(RECONNECT_INTERVAL) {
+            Ok(update) => {
+                // Only the newest of a backlog matters
+                target = receiver.try_iter().last().unwrap_or(update);
+                synced = false;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        if synced {
+            continue;
+        }
+
+        let Some(target) = &target else {
+            if let Some(connected) = &mut client
+                && connected.clear_activity().is_err()
+            {
+                client = None;
+            }
+            synced = true;
+            continue;
+        };
+
+        if client.is_none()
+            && last_attempt.is_none_or(|attempt| attempt.elapsed() >= RECONNECT_INTERVAL)
+        {
+            last_attempt = Some(Instant::now());
+            let mut connecting = DiscordIpcClient::new(app_id);
+            if connecting.connect().is_ok() {
+                client = Some(connecting);
+            }
+        }
+        // Not reachable: retried when the receive times out
+        let Some(connected) = &mut client else {
+            continue;
+        };
+
+        let state = format!("by {}", target.artist);
+        let mut activity = Activity::new()
+            .activity_type(ActivityType::Listening)
+            .status_display_type(StatusDisplayType::Details)
+            .details(target.title.as_str())
+            .state(state.as_str());
+        if let Some(started_at) = target.started_at {
+            // Discord draws a progress bar from the start and end times
+            activity = activity.timestamps(
+                Timestamps::new()
+                    .start(started_at * 1000)
+                    .end((started_at + target.duration_secs) * 1000),
+            );
+        }
+
+        if connected.set_activity(activity).is_ok() {
+            synced = true;
+        } else {
+            // Discord went away; reconnect on a later pass
+            client = None;
+        }
+    }
+
+    if let Some(mut connected) = client {
+        let _ = connected.clear_activity();
+        let _ = connected.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(started_at: Option<i64>) -> Shown {
+        Shown {
+            title: "Track".to_string(),
+            artist: "Artist".to_string(),
+            started_at,
+            duration_secs: 200,
+        }
+    }
+
+    #[test]
+    fn only_seeks_and_state_changes_need_a_resend() {
+        // Ticks drift by under a second as the position advances
+        assert!(shown(Some(1000)).matches(&shown(Some(1001))));
+        // A seek moves the computed start time
+        assert!(!shown(Some(1000)).matches(&shown(Some(970))));
+        // Pausing drops the timestamps
+        assert!(!shown(Some(1000)).matches(&shown(None)));
+        assert!(shown(None).matches(&shown(None)));
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    f32::consts::TAU,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use rodio::Source;
+use rodio::source::SeekError;
+use serde::{Deserialize, Serialize};
+
+/// Number of equalizer bands
+pub const EQ_BANDS: usize = 8;
+
+/// Centre frequency of each band in Hz
+pub const EQ_FREQUENCIES: [f32; EQ_BANDS] = [
+    60.0, 170.0, 310.0, 600.0, 1_000.0, 3_000.0, 6_000.0, 12_000.0,
+];
+
+/// Band gain range in dB
+pub const EQ_MAX_GAIN: f32 = 12.0;
+
+/// Bandwidth of each peaking filter; ~1.4 octaves keeps neighbouring bands
+/// overlapping without a dip between them
+const EQ_Q: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EqPreset {
+    Flat,
+    BassBoost,
+    Vocal,
+    Treble,
+}
+
+impl EqPreset {
+    pub const ALL: [EqPreset; 4] = [
+        EqPreset::Flat,
+        EqPreset::BassBoost,
+        EqPreset::Vocal,
+        EqPreset::Treble,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EqPreset::Flat => "Flat",
+            EqPreset::BassBoost => "Bass Boost",
+            EqPreset::Vocal => "Vocal",
+            EqPreset::Treble => "Treble",
+        }
+    }
+
+    /// Band gains in dB, lowest band first
+    pub fn gains(&self) -> [f32; EQ_BANDS] {
+        match self {
+            EqPreset::Flat => [0.0; EQ_BANDS],
+            EqPreset::BassBoost => [6.0, 5.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            EqPreset::Vocal => [-2.0, -1.0, 0.0, 2.0, 4.0, 4.0, 2.0, 0.0],
+            EqPreset::Treble => [0.0, 0.0, 0.0, 0.0, 1.0, 3.0, 5.0, 6.0],
+        }
+    }
+}
+
+/// Band gains shared between the UI and the audio thread. Changes apply to
+/// the playing source without rebuilding it, and carry over to every source
+/// built afterwards.
+pub struct EqControls {
+    gains: [AtomicU32; EQ_BANDS], // f32 bits, in dB
+    version: AtomicU64,           // Bumped on every change so sources know to recompute
+}
+
+impl EqControls {
+    pub fn new(gains: &[f32; EQ_BANDS]) -> Arc<Self> {
+        let controls = Arc::new(Self {
+            gains: std::array::from_fn(|_| AtomicU32::new(0)),
+            version: AtomicU64::new(0),
+        });
+        controls.set_gains(gains);
+        controls
+    }
+
+    pub fn set_gains(&self, gains: &[f32; EQ_BANDS]) {
+        for (slot, gain) in self.gains.iter().zip(gains) {
+            slot.store(gain.to_bits(), Ordering::Relaxed);
+        }
+        self.version.fetch_add(1, Ordering::Release);
+    }
+
+    fn gains(&self) -> [f32; EQ_BANDS] {
+        std::array::from_fn(|i| f32::from_bits(self.gains[i].load(Ordering::Relaxed)))
+    }
+}
+
+/// Normalized biquad coefficients (RBJ audio EQ cookbook peaking filter)
+#[derive(Clone, Copy)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Biquad {
+    fn peaking(freq: f32, gain_db: f32, sample_rate: f32) -> Self {
+        let a = 10f32.powf(gain_db / 40.0);
+        let w0 = TAU * freq / sample_rate;
+        let alpha = w0.sin() / (2.0 * EQ_Q);
+        let cos_w0 = w0.cos();
+        let a0 = 1.0 + alpha / a;
+        Self {
+            b0: (1.0 + alpha * a) / a0,
+            b1: (-2.0 * cos_w0) / a0,
+            b2: (1.0 - alpha * a) / a0,
+            a1: (-2.0 * cos_w0) / a0,
+            a2: (1.0 - alpha / a) / a0,
+        }
+    }
+
+    /// Transposed direct form II step; `state` is this filter's per-channel memory
+    fn process(&self, x: f32, state: &mut [f32; 2]) -> f32 {
+        let y = self.b0 * x + state[0];
+        state[0] = self.b1 * x - self.a1 * y + state[1];
+        state[1] = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+/// Applies the equalizer to a source, one peaking filter per non-flat band
+pub struct EqSource<S> {
+    inner: S,
+    controls: Arc<EqControls>,
+    version: u64,
+    sample_rate: u32,
+    channels: u16,
+    filters: Vec<Biquad>,
+    state: Vec<[f32; 2]>, // filters.len() entries per channel
+    preamp: f32,          // Headroom so boosted bands don't clip
+    channel: u16,         // Channel of the next sample
+}
+
+impl<S: Source> EqSource<S> {
+    pub fn new(inner: S, controls: Arc<EqControls>) -> Self {
+        let mut source = Self {
+            inner,
+            controls,
+            version: u64::MAX,
+            sample_rate: 0,
+            channels: 0,
+            filters: Vec::new(),
+            state: Vec::new(),
+            preamp: 1.0,
+            channel: 0,
+        };
+        source.refresh();
+        source
+    }
+
+    /// Recompute the filters if the gains or the stream format changed
+    fn refresh(&mut self) {
+        let version = self.controls.version.load(Ordering::Acquire);
+        let sample_rate = self.inner.sample_rate();
+        let channels = self.inner.channels().max(1);
+        if version == self.version && sample_rate == self.sample_rate && channels == self.channels {
+            return;
+        }
+
+        let gains = self.controls.gains();
+        self.filters = EQ_FREQUENCIES
+            .iter()
+            .zip(gains)
+            // Bands above Nyquist can't be filtered; flat ones are skipped
+            .filter(|&(&freq, gain)| gain != 0.0 && freq < sample_rate as f32 / 2.0)
+            .map(|(&freq, gain)| Biquad::peaking(freq, gain, sample_rate as f32))
+            .collect();
+        let max_boost = gains.iter().copied().fold(0.0, f32::max);
+        self.preamp = 10f32.powf(-max_boost / 20.0);
+
+        // Filter memory only carries over if the layout is unchanged
+        if channels != self.channels || self.state.len() != self.filters.len() * channels as usize {
+            self.state = vec![[0.0; 2]; self.filters.len() * channels as usize];
+        }
+        self.version = version;
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+    }
+}
+
+impl<S: Source> Iterator for EqSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            self.refresh();
+        }
+        let sample = self.inner.next()?;
+
+        let channel = self.channel as usize;
+        self.channel = (self.channel + 1) % self.channels;
+        if self.filters.is_empty() {
+            return Some(sample);
+        }
+
+        let bands = self.filters.len();
+        let state = &mut self.state[channel * bands..(channel + 1) * bands];
+        let out = self
+            .filters
+            .iter()
+            .zip(state)
+            .fold(sample * self.preamp, |x, (filter, state)| {
+                filter.process(x, state)
+            });
+        Some(out)
+    }
+}
+
+impl<S: Source> Source for EqSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        // Ringing from before the seek would click into the new position
+        self.state.iter_mut().for_each(|s| *s = [0.0; 2]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steady-state RMS gain of a peaking filter on a sine at `freq`
+    fn gain_at(filter: &Biquad, freq: f32) -> f32 {
+        let mut state = [0.0; 2];
+        let samples = (0..44_100).map(|i| (TAU * freq * i as f32 / 44_100.0).sin());
+        let (mut input, mut output) = (0.0, 0.0);
+        for (i, x) in samples.enumerate() {
+            let y = filter.process(x, &mut state);
+            // Skip the attack so only the settled response is measured
+            if i > 4_410 {
+                input += x * x;
+                output += y * y;
+            }
+        }
+        (output / input).sqrt()
+    }
+
+    #[test]
+    fn peaking_filter_boosts_its_band_and_leaves_others() {
+        let filter = Biquad::peaking(1_000.0, 6.0, 44_100.0);
+        let db = |gain: f32| 20.0 * gain.log10();
+
+        assert!((db(gain_at(&filter, 1_000.0)) - 6.0).abs() < 0.2);
+        assert!(db(gain_at(&filter, 60.0)).abs() < 0.5);
+        assert!(db(gain_at(&filter, 12_000.0)).abs() < 0.5);
+    }
+}
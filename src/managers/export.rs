@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::models::SoundCloudTrack;
+
+/// File format for exporting a track list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Json, ExportFormat::Csv];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+        })
+    }
+}
+
+/// One exported row
+#[derive(Serialize)]
+struct ExportedTrack<'a> {
+    id: u64,
+    title: &'a str,
+    artist: &'a str,
+    permalink_url: &'a str,
+    duration_ms: u64,
+}
+
+impl<'a> From<&'a SoundCloudTrack> for ExportedTrack<'a> {
+    fn from(track: &'a SoundCloudTrack) -> Self {
+        Self {
+            id: track.id,
+            title: &track.title,
+            artist: &track.user.username,
+            permalink_url: &track.permalink_url,
+            duration_ms: track.duration,
+        }
+    }
+}
+
+/// The file contents for `tracks` in `format`
+pub fn export_tracks(tracks: &[SoundCloudTrack], format: ExportFormat) -> Result<String, String> {
+    let rows: Vec<ExportedTrack> = tracks.iter().map(ExportedTrack::from).collect();
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&rows).map_err(|e| e.to_string()),
+        ExportFormat::Csv => {
+            let mut csv = String::from("id,title,artist,permalink_url,duration_ms\n");
+            for row in rows {
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    row.id,
+                    csv_field(row.title),
+                    csv_field(row.artist),
+                    csv_field(row.permalink_url),
+                    row.duration_ms
+                ));
+            }
+            Ok(csv)
+        }
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Ask where to save and write the export there. Ok(None) if the user
+/// cancelled the dialog.
+pub async fn save_export(
+    tracks: std::sync::Arc<Vec<SoundCloudTrack>>,
+    format: ExportFormat,
+    file_stem: &str,
+) -> Result<Option<PathBuf>, String> {
+    let contents = export_tracks(&tracks, format)?;
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_file_name(format!("{}.{}", file_stem, format.extension()))
+        .add_filter(format.to_string(), &[format.extension()])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let path = file.path().to_path_buf();
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))?;
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SoundCloudUser, test_track};
+
+    fn track(id: u64, title: &str) -> SoundCloudTrack {
+        SoundCloudTrack {
+            user: SoundCloudUser {
+                username: "Artist".to_string(),
+                ..SoundCloudUser::default()
+            },
+            permalink_url: format!("https://soundcloud.com/artist/{}", id),
+            ..test_track(id, title)
+        }
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let csv = export_tracks(
+            &[track(1, "Plain"), track(2, "Say \"hi\", world")],
+            ExportFormat::Csv,
+        )
+        .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,title,artist,permalink_url,duration_ms");
+        assert_eq!(
+            lines[1],
+            "1,Plain,Artist,https://soundcloud.com/artist/1,180000"
+        );
+        assert_eq!(
+            lines[2],
+            "2,\"Say \"\"hi\"\", world\",Artist,https://soundcloud.com/artist/2,180000"
+        );
+    }
+
+    #[test]
+    fn json_lists_the_exported_fields() {
+        let json = export_tracks(&[track(1, "Plain")], ExportFormat::Json).unwrap();
+        let rows: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(rows[0]["artist"], "Artist");
+        assert_eq!(rows[0]["duration_ms"], 180_000);
+        assert_eq!(rows[0]["permalink_url"], "https://soundcloud.com/artist/1");
+    }
+}
@@ -1,11 +1,21 @@
 pub mod audio;
 pub mod audio_buffer;
+pub mod decoder;
+pub mod discord;
+pub mod equalizer;
+pub mod export;
 pub mod queue;
+pub mod replay_cache;
+pub mod search_history;
+pub mod spectrum;
 pub mod stream;
 pub mod track_list;
+#[cfg(any(windows, target_os = "macos"))]
+pub mod tray;
 
 // Re-export for convenience
-pub use audio::AudioManager;
+pub use audio::{AudioManager, PlayerState};
 pub use queue::QueueManager;
+pub use search_history::SearchHistory;
 pub use stream::{download_track_stream, prefetch_track_stream};
-pub use track_list::TrackListManager;
+pub use track_list::{TrackActivity, TrackListManager};
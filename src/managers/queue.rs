@@ -1,11 +1,56 @@
+use crate::config;
 use crate::models::SoundCloudTrack;
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::sync::Arc;
+
+/// How many previously played tracks the back stack remembers
+const MAX_HISTORY: usize = 100;
+
+/// The queue as saved between runs: its tracks in play order and which one
+/// is current. Repeat mode lives in the settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQueue {
+    pub tracks: Vec<SoundCloudTrack>,
+    pub current_index: Option<usize>,
+}
+
+impl SavedQueue {
+    pub fn current_track(&self) -> Option<&SoundCloudTrack> {
+        self.tracks.get(self.current_index?)
+    }
+}
+
+fn saved_queue_path() -> std::path::PathBuf {
+    config::get_data_dir().join("queue.json")
+}
+
+/// The queue saved by the last run, if any; a missing or unreadable file
+/// counts as none
+pub fn load_saved_queue() -> Option<SavedQueue> {
+    let contents = fs::read_to_string(saved_queue_path()).ok()?;
+    serde_json::from_str::<SavedQueue>(&contents)
+        .ok()
+        .filter(|saved| saved.current_track().is_some())
+}
+
+pub fn save_queue(saved: &SavedQueue) -> Result<(), Box<dyn std::error::Error>> {
+    let path = saved_queue_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string(saved)?)?;
+    Ok(())
+}
 
 #[derive(Debug, Clone)]
 pub struct QueueManager {
     queue: VecDeque<SoundCloudTrack>,
     current_index: Option<usize>,
-    original_tracks: Vec<SoundCloudTrack>, // Keep reference to original track list
+    original_tracks: Arc<Vec<SoundCloudTrack>>, // Keep reference to original track list
+    history: VecDeque<SoundCloudTrack>,         // Tracks played before the current one, newest last
+    wrap_around: bool,                          // Step from the last track to the first and back
 }
 
 impl QueueManager {
@@ -13,18 +58,45 @@ impl QueueManager {
         Self {
             queue: VecDeque::new(),
             current_index: None,
-            original_tracks: Vec::new(),
+            original_tracks: Arc::new(Vec::new()),
+            history: VecDeque::new(),
+            wrap_around: false,
+        }
+    }
+
+    /// Loop next/previous around the ends of the queue. Independent of
+    /// RepeatMode: with it on there is always a next track, so the queue
+    /// never runs out.
+    pub fn set_wrap_around(&mut self, wrap_around: bool) {
+        self.wrap_around = wrap_around;
+    }
+
+    /// Push the outgoing current track onto the play history
+    fn record_current(&mut self) {
+        if let Some(track) = self.current_track().cloned() {
+            if self.history.len() == MAX_HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back(track);
         }
     }
 
     /// Initialize queue from a specific track in the track list
-    pub fn start_queue_from_track(&mut self, track_id: u64, tracks: Vec<SoundCloudTrack>) {
-        self.original_tracks = tracks.clone();
+    pub fn start_queue_from_track(&mut self, track_id: u64, tracks: Arc<Vec<SoundCloudTrack>>) {
+        self.record_current();
 
-        // Use the get_track_queue function from utilities
-        let queue_tracks = crate::utilities::get_track_queue(track_id, tracks);
+        // Use the get_track_queue function from utilities. A track missing
+        // from the list (e.g. the list reloaded underneath it) plays the
+        // whole list rather than stopping playback. Only the queued part of
+        // the shared list is copied, leaving out tracks that can't play here.
+        let queue_tracks = crate::utilities::get_track_queue(track_id, &tracks);
 
-        self.queue = queue_tracks.into_iter().collect();
+        self.queue = queue_tracks
+            .iter()
+            .filter(|t| t.is_playable())
+            .cloned()
+            .collect();
+        self.original_tracks = tracks;
         self.current_index = if self.queue.is_empty() { None } else { Some(0) };
     }
 
@@ -37,55 +109,102 @@ impl QueueManager {
         }
     }
 
-    /// Move to the next track in the queue
+    /// Move to the next track in the queue, wrapping to the first if enabled
     pub fn next_track(&mut self) -> Option<&SoundCloudTrack> {
-        if let Some(current) = self.current_index
-            && current + 1 < self.queue.len()
-        {
-            self.current_index = Some(current + 1);
-            return self.current_track();
+        let next = self.next_index()?;
+        self.record_current();
+        self.current_index = Some(next);
+        self.current_track()
+    }
+
+    /// Index the next track would play from, if any
+    fn next_index(&self) -> Option<usize> {
+        let current = self.current_index?;
+        if current + 1 < self.queue.len() {
+            Some(current + 1)
+        } else if self.wrap_around {
+            Some(0)
+        } else {
+            None
         }
-        None
     }
 
-    /// Move to the previous track in the queue
+    /// Move to the previous track in the queue. At the start of the queue,
+    /// wrap to the last track if enabled, or else step back through the play
+    /// history (e.g. after a repeat-all restart or a new queue), re-inserting
+    /// that track at the front.
     pub fn previous_track(&mut self) -> Option<&SoundCloudTrack> {
-        if let Some(current) = self.current_index
-            && current > 0
-        {
+        let current = self.current_index?;
+        if current == 0 && self.wrap_around {
+            self.current_index = Some(self.queue.len() - 1);
+            return self.current_track();
+        }
+        if current > 0 {
             self.current_index = Some(current - 1);
+            // This track was recorded when we moved past it
+            if self
+                .history
+                .back()
+                .is_some_and(|t| Some(t.id) == self.current_track().map(|c| c.id))
+            {
+                self.history.pop_back();
+            }
             return self.current_track();
         }
-        None
+
+        let track = self.history.pop_back()?;
+        self.queue.push_front(track);
+        self.current_index = Some(0);
+        self.current_track()
+    }
+
+    /// Make the track at `index` current, recording the outgoing one in the
+    /// play history. None (and no change) if the index is out of range.
+    pub fn jump_to(&mut self, index: usize) -> Option<&SoundCloudTrack> {
+        if index >= self.queue.len() {
+            return None;
+        }
+        if self.current_index != Some(index) {
+            self.record_current();
+            self.current_index = Some(index);
+        }
+        self.current_track()
+    }
+
+    /// Add tracks to the end of the queue, skipping any already queued and
+    /// any that can't be played. Returns how many were added.
+    pub fn enqueue_last(&mut self, tracks: impl IntoIterator<Item = SoundCloudTrack>) -> usize {
+        let before = self.queue.len();
+        let mut queued: HashSet<u64> = self.queue.iter().map(|t| t.id).collect();
+        for track in tracks {
+            if track.is_playable() && queued.insert(track.id) {
+                self.queue.push_back(track);
+            }
+        }
+        self.queue.len() - before
     }
 
     /// Peek at the next track without advancing the queue position
     pub fn peek_next(&self) -> Option<&SoundCloudTrack> {
-        let current = self.current_index?;
-        self.queue.get(current + 1)
+        self.queue.get(self.next_index()?)
     }
 
     /// Check if there's a next track available
     pub fn has_next(&self) -> bool {
-        if let Some(current) = self.current_index {
-            current + 1 < self.queue.len()
-        } else {
-            false
-        }
+        self.next_index().is_some()
     }
 
     /// Check if there's a previous track available
     #[allow(dead_code)]
     pub fn has_previous(&self) -> bool {
         if let Some(current) = self.current_index {
-            current > 0
+            current > 0 || self.wrap_around || !self.history.is_empty()
         } else {
             false
         }
     }
 
-    /// Get the current queue as a vector for display purposes
-    #[allow(dead_code)]
+    /// Get the current queue as a vector, in play order
     pub fn get_queue(&self) -> Vec<&SoundCloudTrack> {
         self.queue.iter().collect()
     }
@@ -105,21 +224,39 @@ impl QueueManager {
     pub fn clear(&mut self) {
         self.queue.clear();
         self.current_index = None;
-        self.original_tracks.clear();
+        self.original_tracks = Arc::new(Vec::new());
+        self.history.clear();
     }
 
     /// Reset the queue position to the first track
     pub fn reset_to_beginning(&mut self) {
         if !self.queue.is_empty() {
+            self.record_current();
             self.current_index = Some(0);
         }
     }
 
     /// Check if the queue is empty
-    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
+
+    /// Copy of the queue and position for saving between runs
+    pub fn snapshot(&self) -> SavedQueue {
+        SavedQueue {
+            tracks: self.queue.iter().cloned().collect(),
+            current_index: self.current_index,
+        }
+    }
+
+    /// Replace the queue with a saved one. The play history starts empty.
+    pub fn restore(&mut self, saved: SavedQueue) {
+        let current_index = saved.current_index.filter(|&i| i < saved.tracks.len());
+        self.queue = saved.tracks.into();
+        self.current_index = current_index;
+        self.original_tracks = Arc::new(Vec::new());
+        self.history.clear();
+    }
 }
 
 impl Default for QueueManager {
@@ -127,3 +264,148 @@ impl Default for QueueManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_track;
+
+    fn track(id: u64) -> SoundCloudTrack {
+        test_track(id, &format!("Track {id}"))
+    }
+
+    fn current_id(queue: &QueueManager) -> Option<u64> {
+        queue.current_track().map(|t| t.id)
+    }
+
+    #[test]
+    fn wrap_around_loops_both_ends() {
+        let mut queue = QueueManager::new();
+        queue.set_wrap_around(true);
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), track(2), track(3)]));
+
+        assert_eq!(queue.previous_track().map(|t| t.id), Some(3));
+        assert!(queue.has_next());
+        assert_eq!(queue.peek_next().map(|t| t.id), Some(1));
+        assert_eq!(queue.next_track().map(|t| t.id), Some(1));
+
+        queue.set_wrap_around(false);
+        queue.next_track();
+        queue.next_track();
+        assert!(!queue.has_next());
+    }
+
+    #[test]
+    fn enqueue_last_skips_queued_and_unplayable_tracks() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), track(2)]));
+
+        let mut blocked = track(4);
+        blocked.access = "blocked".to_string();
+        let mut unstreamable = track(5);
+        unstreamable.stream_url = None;
+        let added = queue.enqueue_last(vec![track(2), track(3), blocked, unstreamable, track(3)]);
+
+        assert_eq!(added, 1);
+        assert_eq!(current_id(&queue), Some(1));
+        let ids: Vec<u64> = queue.get_queue().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+    }
+
+    #[test]
+    fn unknown_start_track_queues_the_whole_list() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(99, Arc::new(vec![track(1), track(2), track(3)]));
+
+        assert_eq!(current_id(&queue), Some(1));
+        assert_eq!(queue.queue_length(), 3);
+    }
+
+    #[test]
+    fn previous_walks_back_past_a_repeat_restart() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), track(2), track(3)]));
+        queue.next_track();
+        queue.next_track();
+        queue.reset_to_beginning(); // repeat-all wrap from 3 back to 1
+
+        assert_eq!(queue.previous_track().map(|t| t.id), Some(3));
+        assert_eq!(queue.previous_track().map(|t| t.id), Some(2));
+        // Forward again follows the re-inserted history into the queue
+        assert_eq!(queue.next_track().map(|t| t.id), Some(3));
+        assert_eq!(queue.next_track().map(|t| t.id), Some(1));
+    }
+
+    #[test]
+    fn previous_within_queue_does_not_repeat_history() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), track(2)]));
+        queue.next_track();
+
+        assert_eq!(queue.previous_track().map(|t| t.id), Some(1));
+        // Track 1 was consumed from history on the way back
+        assert!(queue.previous_track().is_none());
+        assert_eq!(current_id(&queue), Some(1));
+    }
+
+    #[test]
+    fn jump_to_moves_within_the_queue_and_records_history() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), track(2), track(3)]));
+
+        assert_eq!(queue.jump_to(2).map(|t| t.id), Some(3));
+        assert_eq!(queue.current_position(), Some(2));
+        assert!(!queue.has_next());
+        assert!(queue.jump_to(3).is_none());
+        assert_eq!(current_id(&queue), Some(3));
+
+        // Back goes to the track that was playing before the jump
+        assert_eq!(queue.previous_track().map(|t| t.id), Some(2));
+    }
+
+    #[test]
+    fn previous_reaches_the_last_queue_and_history_is_capped() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1)]));
+        queue.start_queue_from_track(2, Arc::new(vec![track(2)]));
+        assert_eq!(queue.previous_track().map(|t| t.id), Some(1));
+
+        let tracks: Vec<_> = (10..10 + MAX_HISTORY as u64 + 20).map(track).collect();
+        queue.start_queue_from_track(10, Arc::new(tracks));
+        while queue.next_track().is_some() {}
+        assert_eq!(queue.history.len(), MAX_HISTORY);
+    }
+
+    #[test]
+    fn snapshot_restores_queue_and_position() {
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), track(2), track(3)]));
+        queue.next_track();
+
+        let saved: SavedQueue =
+            serde_json::from_str(&serde_json::to_string(&queue.snapshot()).unwrap()).unwrap();
+        let mut restored = QueueManager::new();
+        restored.restore(saved);
+        assert_eq!(restored.queue_length(), 3);
+        assert_eq!(restored.current_track().map(|t| t.id), Some(2));
+        assert_eq!(restored.peek_next().map(|t| t.id), Some(3));
+
+        // A stale index doesn't point past the end
+        restored.restore(SavedQueue {
+            tracks: vec![track(1)],
+            current_index: Some(4),
+        });
+        assert!(restored.current_track().is_none());
+    }
+
+    #[test]
+    fn blocked_tracks_are_left_out_of_the_queue() {
+        let mut blocked = track(2);
+        blocked.access = "blocked".to_string();
+        let mut queue = QueueManager::new();
+        queue.start_queue_from_track(1, Arc::new(vec![track(1), blocked, track(3)]));
+
+        assert_eq!(queue.queue_length(), 2);
+        assert_eq!(queue.next_track().map(|t| t.id), Some(3));
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rodio::Source;
+use rodio::source::SeekError;
+
+/// Samples the audio thread collects before taking the cache's lock
+const FLUSH_SAMPLES: usize = 4096;
+
+/// How far a recording may trail the playback position and still count as
+/// covering it (samples not yet flushed when the track ends)
+const COVERAGE_SLACK: Duration = Duration::from_secs(1);
+
+/// Room reserved past the track's listed duration, which can be a little
+/// short of what actually decodes
+const RESERVE_SLACK: Duration = Duration::from_secs(2);
+
+/// Decoded samples of the current track, recorded by `RecordingSource` while
+/// it plays the first time so repeat one can restart without decoding again.
+///
+/// Decoded audio is far bigger than the AAC stream it came from: 44.1kHz
+/// stereo costs about 21 MB per minute, which is why only tracks up to a
+/// length limit get recorded, and only while repeat one is on. The whole
+/// track's worth is reserved up front so the audio thread never reallocates.
+pub struct ReplayCache {
+    max_duration: Duration,
+    recording: Mutex<Recording>,
+}
+
+struct Recording {
+    samples: Arc<Vec<f32>>,
+    channels: u16,
+    sample_rate: u32,
+    valid: bool,  // False once a seek, format change or the length limit left it unusable
+    frozen: bool, // Set on the first replay, after which nothing more is recorded
+}
+
+impl ReplayCache {
+    /// A cache for a track listed as `track_duration` long, decoding to
+    /// `channels` at `sample_rate`
+    pub fn new(
+        max_duration: Duration,
+        track_duration: Duration,
+        channels: u16,
+        sample_rate: u32,
+    ) -> Arc<Self> {
+        let reserve = (track_duration + RESERVE_SLACK).min(max_duration);
+        let capacity = (reserve.as_secs_f64() * sample_rate as f64 * channels as f64) as usize;
+        Arc::new(Self {
+            max_duration,
+            recording: Mutex::new(Recording {
+                samples: Arc::new(Vec::with_capacity(capacity)),
+                channels,
+                sample_rate,
+                valid: true,
+                frozen: false,
+            }),
+        })
+    }
+
+    /// Give up on the recording, e.g. after a seek skipped part of the track.
+    /// A recording that already replayed is complete and stays usable.
+    pub fn invalidate(&self) {
+        let mut recording = self.recording.lock().unwrap();
+        if !recording.frozen {
+            recording.valid = false;
+            recording.samples = Arc::new(Vec::new());
+        }
+    }
+
+    fn push(&self, samples: &[f32], channels: u16, sample_rate: u32) {
+        let mut recording = self.recording.lock().unwrap();
+        if recording.frozen || !recording.valid {
+            return;
+        }
+
+        let max_samples =
+            (self.max_duration.as_secs_f64() * sample_rate as f64 * channels as f64) as usize;
+        if channels != recording.channels
+            || sample_rate != recording.sample_rate
+            || recording.samples.len() + samples.len() > max_samples
+        {
+            recording.valid = false;
+            recording.samples = Arc::new(Vec::new());
+            return;
+        }
+        // Unshared until the first replay freezes it, so this appends in place
+        // to the capacity reserved up front
+        Arc::make_mut(&mut recording.samples).extend_from_slice(samples);
+    }
+
+    /// A source replaying the recording from the start, or None if it is
+    /// unusable or doesn't reach `position` (where playback ended)
+    pub fn replay(&self, position: Duration) -> Option<CachedSource> {
+        let mut recording = self.recording.lock().unwrap();
+        if !recording.valid || recording.samples.is_empty() {
+            return None;
+        }
+
+        let frames = recording.samples.len() / recording.channels.max(1) as usize;
+        let recorded = Duration::from_secs_f64(frames as f64 / recording.sample_rate as f64);
+        if recorded + COVERAGE_SLACK < position {
+            return None;
+        }
+
+        recording.frozen = true;
+        Some(CachedSource {
+            samples: Arc::clone(&recording.samples),
+            position: 0,
+            channels: recording.channels,
+            sample_rate: recording.sample_rate,
+        })
+    }
+}
+
+/// Passes a source through unchanged while recording its samples into a
+/// `ReplayCache`
+pub struct RecordingSource<S> {
+    inner: S,
+    cache: Arc<ReplayCache>,
+    pending: Vec<f32>, // Samples not yet flushed to the cache
+}
+
+impl<S: Source> RecordingSource<S> {
+    pub fn new(inner: S, cache: Arc<ReplayCache>) -> Self {
+        Self {
+            inner,
+            cache,
+            pending: Vec::with_capacity(FLUSH_SAMPLES),
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            self.cache.push(
+                &self.pending,
+                self.inner.channels(),
+                self.inner.sample_rate(),
+            );
+            self.pending.clear();
+        }
+    }
+}
+
+impl<S: Source> Iterator for RecordingSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        match self.inner.next() {
+            Some(sample) => {
+                self.pending.push(sample);
+                if self.pending.len() >= FLUSH_SAMPLES {
+                    self.flush();
+                }
+                Some(sample)
+            }
+            None => {
+                self.flush();
+                None
+            }
+        }
+    }
+}
+
+impl<S: Source> Source for RecordingSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        // The recording now has a gap, so it can't stand in for the track
+        self.pending.clear();
+        self.cache.invalidate();
+        Ok(())
+    }
+}
+
+/// Plays back samples recorded by a `ReplayCache`
+pub struct CachedSource {
+    samples: Arc<Vec<f32>>,
+    position: usize,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl Iterator for CachedSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied()?;
+        self.position += 1;
+        Some(sample)
+    }
+}
+
+impl Source for CachedSource {
+    fn current_span_len(&self) -> Option<usize> {
+        Some(self.samples.len() - self.position)
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let frames = self.samples.len() / self.channels.max(1) as usize;
+        Some(Duration::from_secs_f64(
+            frames as f64 / self.sample_rate as f64,
+        ))
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        // Land on a frame boundary so the channels stay in order
+        let frame = (pos.as_secs_f64() * self.sample_rate as f64) as usize;
+        self.position = (frame * self.channels as usize).min(self.samples.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    fn record(cache: &Arc<ReplayCache>, samples: Vec<f32>) -> Vec<f32> {
+        RecordingSource::new(SamplesBuffer::new(2, 100, samples), Arc::clone(cache)).collect()
+    }
+
+    #[test]
+    fn replays_what_was_recorded() {
+        let cache = ReplayCache::new(Duration::from_secs(10), Duration::from_secs(2), 2, 100);
+        let samples: Vec<f32> = (0..400).map(|i| i as f32).collect();
+        assert_eq!(record(&cache, samples.clone()), samples);
+
+        let mut replay = cache.replay(Duration::from_secs(2)).unwrap();
+        assert_eq!(replay.total_duration(), Some(Duration::from_secs(2)));
+        replay.try_seek(Duration::from_secs(1)).unwrap();
+        assert_eq!(replay.next(), Some(200.0));
+    }
+
+    #[test]
+    fn unusable_after_seek_or_over_limit() {
+        let cache = ReplayCache::new(Duration::from_secs(10), Duration::from_secs(2), 2, 100);
+        let inner = SamplesBuffer::new(2, 100, vec![0.0; 400]);
+        let mut source = RecordingSource::new(inner, Arc::clone(&cache));
+        source.try_seek(Duration::from_secs(1)).unwrap();
+        source.by_ref().for_each(drop);
+        assert!(cache.replay(Duration::ZERO).is_none());
+
+        let cache = ReplayCache::new(Duration::from_secs(1), Duration::from_secs(2), 2, 100);
+        record(&cache, vec![0.0; 400]);
+        assert!(cache.replay(Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn short_recording_does_not_cover_later_position() {
+        let cache = ReplayCache::new(Duration::from_secs(10), Duration::from_secs(2), 2, 100);
+        record(&cache, vec![0.0; 200]);
+        assert!(cache.replay(Duration::from_secs(5)).is_none());
+        assert!(cache.replay(Duration::from_secs(1)).is_some());
+    }
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// How many past queries are remembered
+const MAX_HISTORY: usize = 20;
+
+/// Recent search queries, most recent first, persisted as JSON in the data dir
+pub struct SearchHistory {
+    queries: Vec<String>,
+    file_path: Option<PathBuf>, // None keeps the history in memory only
+}
+
+impl SearchHistory {
+    /// Load the saved history; a missing or unreadable file starts empty
+    pub fn load() -> Self {
+        let file_path = config::get_data_dir().join("search_history.json");
+        let queries = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Vec<String>>(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            queries,
+            file_path: Some(file_path),
+        }
+    }
+
+    pub fn queries(&self) -> &[String] {
+        &self.queries
+    }
+
+    /// Move a query to the front, dropping an earlier copy (ignoring case)
+    /// and the oldest entry past the cap
+    pub fn record(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.queries.retain(|q| !q.eq_ignore_ascii_case(query));
+        self.queries.insert(0, query.to_string());
+        self.queries.truncate(MAX_HISTORY);
+        self.save();
+    }
+
+    pub fn clear(&mut self) {
+        self.queries.clear();
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Some(file_path) = &self.file_path
+            && let Err(e) = write_queries(file_path, &self.queries)
+        {
+            eprintln!("Failed to save search history: {}", e);
+        }
+    }
+}
+
+fn write_queries(file_path: &Path, queries: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file_path, serde_json::to_string_pretty(queries)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_memory() -> SearchHistory {
+        SearchHistory {
+            queries: Vec::new(),
+            file_path: None,
+        }
+    }
+
+    #[test]
+    fn record_keeps_unique_queries_newest_first_and_capped() {
+        let mut history = in_memory();
+        history.record("techno");
+        history.record("house");
+        history.record("  Techno ");
+        history.record("");
+        assert_eq!(history.queries(), ["Techno", "house"]);
+
+        for i in 0..MAX_HISTORY + 5 {
+            history.record(&format!("query {i}"));
+        }
+        assert_eq!(history.queries().len(), MAX_HISTORY);
+        assert_eq!(history.queries()[0], format!("query {}", MAX_HISTORY + 4));
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use rodio::Source;
+use rodio::source::SeekError;
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+
+/// Number of bars the visualizer draws
+pub const SPECTRUM_BANDS: usize = 48;
+
+/// Mono samples analysed per spectrum update (~46ms at 44.1kHz)
+const FFT_SIZE: usize = 2048;
+
+/// Frames the audio thread collects before taking the tap's lock
+const FLUSH_FRAMES: usize = 512;
+
+/// Frequency range spread across the bars, log-spaced
+const MIN_FREQ: f32 = 40.0;
+const MAX_FREQ: f32 = 16_000.0;
+
+/// Bars map this many dB below full scale to zero height
+const DB_FLOOR: f32 = 60.0;
+
+/// Ring of the most recently decoded samples (downmixed to mono), written by
+/// the audio thread through `TappedSource` and analysed on the UI tick
+pub struct SpectrumTap {
+    samples: Mutex<VecDeque<f32>>,
+    sample_rate: AtomicU32,
+    fft: Arc<dyn Fft<f32>>,
+}
+
+impl SpectrumTap {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            samples: Mutex::new(VecDeque::with_capacity(FFT_SIZE * 2)),
+            sample_rate: AtomicU32::new(44_100),
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+        })
+    }
+
+    /// Drop buffered samples, e.g. when the track changes or seeks
+    pub fn clear(&self) {
+        self.samples.lock().unwrap().clear();
+    }
+
+    fn push(&self, frames: &[f32], sample_rate: u32) {
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+        let mut samples = self.samples.lock().unwrap();
+        samples.extend(frames);
+        let excess = samples.len().saturating_sub(FFT_SIZE);
+        samples.drain(..excess);
+    }
+
+    /// Band levels (0.0 to 1.0) for the latest window of samples, or None
+    /// until a full window has been decoded
+    pub fn bands(&self, count: usize) -> Option<Vec<f32>> {
+        let mut spectrum: Vec<Complex<f32>> = {
+            let samples = self.samples.lock().unwrap();
+            if samples.len() < FFT_SIZE {
+                return None;
+            }
+            // Hann window to keep bass energy from smearing across the bars
+            samples
+                .iter()
+                .enumerate()
+                .map(|(i, &s)| {
+                    let phase = std::f32::consts::TAU * i as f32 / (FFT_SIZE - 1) as f32;
+                    Complex::new(s * 0.5 * (1.0 - phase.cos()), 0.0)
+                })
+                .collect()
+        };
+        self.fft.process(&mut spectrum);
+
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed) as f32;
+        let bin_hz = sample_rate / FFT_SIZE as f32;
+        let max_bin = FFT_SIZE / 2;
+        let max_freq = MAX_FREQ.min(sample_rate / 2.0);
+        // A full-scale sine peaks at FFT_SIZE / 4 after the Hann window
+        let full_scale = FFT_SIZE as f32 / 4.0;
+
+        let bands = (0..count)
+            .map(|band| {
+                let freq_at =
+                    |edge: usize| MIN_FREQ * (max_freq / MIN_FREQ).powf(edge as f32 / count as f32);
+                let start = ((freq_at(band) / bin_hz) as usize).clamp(1, max_bin - 1);
+                let end = ((freq_at(band + 1) / bin_hz) as usize).clamp(start + 1, max_bin);
+                let magnitude = spectrum[start..end]
+                    .iter()
+                    .map(|c| c.norm())
+                    .fold(0.0, f32::max);
+                let db = 20.0 * (magnitude / full_scale).max(1e-6).log10();
+                ((db + DB_FLOOR) / DB_FLOOR).clamp(0.0, 1.0)
+            })
+            .collect();
+        Some(bands)
+    }
+}
+
+/// Passes a source through unchanged while copying its samples into a
+/// `SpectrumTap` for the visualizer
+pub struct TappedSource<S> {
+    inner: S,
+    tap: Arc<SpectrumTap>,
+    pending: Vec<f32>, // Mono frames not yet flushed to the tap
+    frame_sum: f32,
+    frame_channel: u16,
+}
+
+impl<S: Source> TappedSource<S> {
+    pub fn new(inner: S, tap: Arc<SpectrumTap>) -> Self {
+        tap.clear();
+        Self {
+            inner,
+            tap,
+            pending: Vec::with_capacity(FLUSH_FRAMES),
+            frame_sum: 0.0,
+            frame_channel: 0,
+        }
+    }
+}
+
+impl<S: Source> Iterator for TappedSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.inner.next()?;
+        let channels = self.inner.channels().max(1);
+
+        self.frame_sum += sample;
+        self.frame_channel += 1;
+        if self.frame_channel >= channels {
+            self.pending.push(self.frame_sum / channels as f32);
+            self.frame_sum = 0.0;
+            self.frame_channel = 0;
+            if self.pending.len() >= FLUSH_FRAMES {
+                self.tap.push(&self.pending, self.inner.sample_rate());
+                self.pending.clear();
+            }
+        }
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for TappedSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        // Samples from before the seek would show up as a stale frame
+        self.pending.clear();
+        self.tap.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sine_lights_up_its_own_band() {
+        let tap = SpectrumTap::new();
+        assert!(tap.bands(SPECTRUM_BANDS).is_none(), "needs a full window");
+
+        let sine: Vec<f32> = (0..FFT_SIZE)
+            .map(|i| (std::f32::consts::TAU * 1000.0 * i as f32 / 44_100.0).sin())
+            .collect();
+        tap.push(&sine, 44_100);
+
+        let bands = tap.bands(SPECTRUM_BANDS).unwrap();
+        let loudest = (0..bands.len())
+            .max_by(|&a, &b| bands[a].total_cmp(&bands[b]))
+            .unwrap();
+        let band_low =
+            MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(loudest as f32 / SPECTRUM_BANDS as f32);
+        let band_high =
+            MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf((loudest + 1) as f32 / SPECTRUM_BANDS as f32);
+        assert!(
+            (band_low..band_high).contains(&1000.0),
+            "1kHz landed in {band_low}..{band_high}"
+        );
+        assert!(bands[loudest] > 0.9);
+    }
+}
@@ -117,14 +117,8 @@ async fn start_track_stream(
     ));
 
     // Artwork and waveform download concurrently with the audio buffering
-    let artwork_fut = async {
-        if track.artwork_url.is_empty() {
-            return None;
-        }
-        crate::utilities::download_image(&track.artwork_url)
-            .await
-            .ok()
-    };
+    let artwork_fut =
+        async { Some(crate::utilities::download_image_with_fallback(&track.artwork_url).await) };
     let waveform_fut = async {
         if track.waveform_url.is_empty() {
             return None;
@@ -1,12 +1,17 @@
 use crate::Message;
-use crate::models::SoundCloudTrack;
+use crate::config::TrackSort;
+use crate::models::{ActivityKind, SoundCloudTrack};
 use crate::widgets::get_track_widget;
 use iced::Element;
 use iced::Task;
+use iced::advanced::widget::{Id, operate, operation};
 use iced::animation::Animation;
 use iced::widget::image::Handle;
-use iced::widget::{Column, column, sensor};
+use iced::widget::scrollable::RelativeOffset;
+use iced::widget::{Column, column, container, sensor};
+use iced::{Theme, border};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 // Start fetching a track's artwork when its row is within this many pixels of the viewport.
@@ -14,23 +19,30 @@ const IMAGE_PREFETCH_DISTANCE: f32 = 300.0;
 // How long a track's artwork takes to fade in once it has loaded.
 const IMAGE_FADE: Duration = Duration::from_millis(400);
 
+/// A track's id, whether it was posted or reposted, and when (Unix seconds)
+pub type TrackActivity = (u64, ActivityKind, Option<i64>);
+
 /// Manages common track list functionality shared across multiple pages
 pub struct TrackListManager {
-    tracks: Vec<SoundCloudTrack>,
+    tracks: Arc<Vec<SoundCloudTrack>>, // Shared with queues started from the list
     track_images: HashMap<u64, Handle>,
     requested: HashSet<u64>,
     image_fades: HashMap<u64, Animation<bool>>,
-    current_track_id: u64,
+    activity: HashMap<u64, (ActivityKind, Option<i64>)>, // Posted or reposted and when, for lists that show it
+    selected: Option<u64>,                               // Track under the keyboard cursor
+    visible: Option<HashSet<u64>>, // When set, only these tracks are shown, e.g. a page's filter
 }
 
 impl TrackListManager {
     pub fn new() -> Self {
         Self {
-            tracks: Vec::new(),
+            tracks: Arc::new(Vec::new()),
             track_images: HashMap::new(),
             requested: HashSet::new(),
             image_fades: HashMap::new(),
-            current_track_id: 0,
+            activity: HashMap::new(),
+            selected: None,
+            visible: None,
         }
     }
 
@@ -38,24 +50,155 @@ impl TrackListManager {
         &self.tracks
     }
 
+    /// The list as loaded, shared rather than copied, e.g. to start a queue
+    pub fn shared_tracks(&self) -> Arc<Vec<SoundCloudTrack>> {
+        Arc::clone(&self.tracks)
+    }
+
     pub fn set_tracks(&mut self, tracks: Vec<SoundCloudTrack>) {
-        self.tracks = tracks;
+        self.tracks = Arc::new(tracks);
         self.track_images.clear();
         self.requested.clear();
         self.image_fades.clear();
+        self.activity.clear();
+        self.selected = None;
+    }
+
+    /// Append a further page of tracks, skipping any already in the list
+    /// (pages can overlap, and the feed repeats reposted tracks)
+    pub fn append_tracks(&mut self, tracks: Vec<SoundCloudTrack>) {
+        let mut seen: HashSet<u64> = self.tracks.iter().map(|t| t.id).collect();
+        Arc::make_mut(&mut self.tracks).extend(tracks.into_iter().filter(|t| seen.insert(t.id)));
+    }
+
+    /// Put newly posted tracks at the head of the list, skipping any already
+    /// in it, e.g. after re-fetching the first page of a feed
+    pub fn prepend_tracks(&mut self, tracks: Vec<SoundCloudTrack>) {
+        let mut seen: HashSet<u64> = self.tracks.iter().map(|t| t.id).collect();
+        let mut fresh: Vec<SoundCloudTrack> =
+            tracks.into_iter().filter(|t| seen.insert(t.id)).collect();
+        fresh.extend(self.tracks.iter().cloned());
+        self.tracks = Arc::new(fresh);
+    }
+
+    /// Show only the tracks in `ids`, or all of them for `None`
+    pub fn set_visible(&mut self, ids: Option<HashSet<u64>>) {
+        self.visible = ids;
+    }
+
+    /// Whether anything in the list can be played here
+    pub fn has_playable_tracks(&self) -> bool {
+        self.tracks.iter().any(SoundCloudTrack::is_playable)
+    }
+
+    /// The track to start a "Play all" from and the list to queue, shuffled
+    /// first for "Shuffle all". None when nothing in the list can play.
+    pub fn play_all(&self, shuffle: bool) -> Option<(SoundCloudTrack, Arc<Vec<SoundCloudTrack>>)> {
+        let tracks = if shuffle {
+            let mut tracks = self.tracks.to_vec();
+            crate::utilities::shuffle(&mut tracks);
+            Arc::new(tracks)
+        } else {
+            self.shared_tracks()
+        };
+        let first = tracks.iter().find(|t| t.is_playable())?.clone();
+        Some((first, tracks))
+    }
+
+    /// The tracks in `sort` order, e.g. to queue them as displayed. Blocked
+    /// tracks are kept either way; queues leave out anything unplayable.
+    pub fn sorted_tracks(&self, sort: TrackSort) -> Arc<Vec<SoundCloudTrack>> {
+        if sort == TrackSort::Unsorted && self.visible.is_none() {
+            return self.shared_tracks();
+        }
+        Arc::new(self.sorted(sort, false).into_iter().cloned().collect())
+    }
+
+    /// The rows as shown: `sort` order, without region-blocked tracks when
+    /// `hide_blocked` is set
+    fn sorted(&self, sort: TrackSort, hide_blocked: bool) -> Vec<&SoundCloudTrack> {
+        let mut tracks: Vec<&SoundCloudTrack> = self
+            .tracks
+            .iter()
+            .filter(|t| !(hide_blocked && t.is_blocked()))
+            .filter(|t| self.visible.as_ref().is_none_or(|ids| ids.contains(&t.id)))
+            .collect();
+        let posted = |t: &SoundCloudTrack| crate::utilities::parse_timestamp(&t.created_at);
+        // Stable sorts, so ties keep the API order; each key is worked out once per track
+        match sort {
+            TrackSort::Unsorted => {}
+            TrackSort::Newest => tracks.sort_by_cached_key(|t| std::cmp::Reverse(posted(t))),
+            TrackSort::Oldest => tracks.sort_by_cached_key(|t| posted(t).unwrap_or(i64::MAX)),
+            TrackSort::TitleAsc => tracks.sort_by_cached_key(|t| t.title.to_lowercase()),
+        }
+        tracks
+    }
+
+    /// Move the keyboard selection `step` rows through the list as shown in
+    /// `sort` order (and `hide_blocked`, as rendered), wrapping at the ends,
+    /// and scroll the list's Scrollable (`scroll_id`) to keep the selected
+    /// row in view
+    pub fn move_selection(
+        &mut self,
+        sort: TrackSort,
+        hide_blocked: bool,
+        step: isize,
+        scroll_id: &'static str,
+    ) -> Task<Message> {
+        let ids: Vec<u64> = self
+            .sorted(sort, hide_blocked)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        if ids.is_empty() {
+            return Task::none();
+        }
+
+        let len = ids.len() as isize;
+        let index = match self
+            .selected
+            .and_then(|id| ids.iter().position(|&i| i == id))
+        {
+            Some(position) => (position as isize + step).rem_euclid(len),
+            None if step < 0 => len - 1,
+            None => 0,
+        } as usize;
+        self.selected = Some(ids[index]);
+
+        // Rows are all the same height, so the row's position in the list is
+        // also how far down to scroll
+        let y = if ids.len() > 1 {
+            index as f32 / (ids.len() - 1) as f32
+        } else {
+            0.0
+        };
+        operate(operation::scrollable::snap_to(
+            Id::new(scroll_id),
+            RelativeOffset {
+                x: None,
+                y: Some(y),
+            },
+        ))
     }
 
-    pub fn append_tracks(&mut self, mut tracks: Vec<SoundCloudTrack>) {
-        self.tracks.append(&mut tracks);
+    /// The track under the keyboard cursor, if any
+    pub fn selected_track(&self) -> Option<&SoundCloudTrack> {
+        let selected = self.selected?;
+        self.tracks.iter().find(|t| t.id == selected)
     }
 
-    #[allow(dead_code)]
-    pub fn current_track_id(&self) -> u64 {
-        self.current_track_id
+    /// Record whether tracks were posted or reposted, and when, so their
+    /// rows are labelled e.g. "reposted 3h ago"
+    pub fn set_activity(&mut self, activity: impl IntoIterator<Item = TrackActivity>) {
+        // A track listed twice keeps the activity of the row that's shown
+        for (track_id, kind, time) in activity {
+            self.activity.entry(track_id).or_insert((kind, time));
+        }
     }
 
-    pub fn set_current_track_id(&mut self, track_id: u64) {
-        self.current_track_id = track_id;
+    /// Whether a track was posted or reposted, for lists that record it
+    pub fn activity_kind(&self, track_id: u64) -> Option<ActivityKind> {
+        self.activity.get(&track_id).map(|&(kind, _)| kind)
     }
 
     /// Handle a track image being loaded, kicking off its fade-in.
@@ -75,12 +218,7 @@ impl TrackListManager {
     /// Lazily download a single track's artwork on demand (driven by the row's
     /// visibility sensor). Returns `Task::none()` if the image is already loaded
     /// or a request is already in flight, so it is safe to call repeatedly.
-    pub fn load_image_task<F>(
-        &mut self,
-        track_id: u64,
-        on_loaded: F,
-        on_failed: fn(u64) -> Message,
-    ) -> Task<Message>
+    pub fn load_image_task<F>(&mut self, track_id: u64, on_loaded: F) -> Task<Message>
     where
         F: Fn(u64, Handle) -> Message + Send + 'static,
     {
@@ -92,18 +230,12 @@ impl TrackListManager {
             return Task::none();
         };
 
+        // Tracks without artwork get the placeholder, keeping rows aligned
         let artwork_url = track.artwork_url.clone();
-        if artwork_url.is_empty() {
-            return Task::none();
-        }
-
         self.requested.insert(track_id);
         Task::perform(
-            async move { crate::utilities::download_image(&artwork_url).await },
-            move |result| match result {
-                Ok(handle) => on_loaded(track_id, handle),
-                Err(_) => on_failed(track_id),
-            },
+            async move { crate::utilities::download_image_with_fallback(&artwork_url).await },
+            move |handle| on_loaded(track_id, handle),
         )
     }
 
@@ -111,8 +243,41 @@ impl TrackListManager {
     /// Takes closures to map track interactions to page-specific messages.
     /// `on_request_image` is fired (via a visibility sensor) when a row scrolls
     /// into view, so artwork is only downloaded as the user reaches it.
+    /// The `now_playing` track's row is highlighted, and region-blocked
+    /// tracks are left out when `hide_blocked` is set.
     pub fn render_tracks<F1, F2, F3, F4>(
         &self,
+        now_playing: Option<u64>,
+        hide_blocked: bool,
+        on_play: F1,
+        on_user_click: F2,
+        on_like: F3,
+        on_request_image: F4,
+    ) -> Column<'_, Message>
+    where
+        F1: Fn(SoundCloudTrack) -> Message + Clone + 'static,
+        F2: Fn(String) -> Message + Clone + 'static,
+        F3: Fn(SoundCloudTrack) -> Message + Clone + 'static,
+        F4: Fn(u64) -> Message + Clone + 'static,
+    {
+        self.render_sorted_tracks(
+            TrackSort::Unsorted,
+            now_playing,
+            hide_blocked,
+            on_play,
+            on_user_click,
+            on_like,
+            on_request_image,
+        )
+    }
+
+    /// `render_tracks`, reordered client-side by `sort`
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_sorted_tracks<F1, F2, F3, F4>(
+        &self,
+        sort: TrackSort,
+        now_playing: Option<u64>,
+        hide_blocked: bool,
         on_play: F1,
         on_user_click: F2,
         on_like: F3,
@@ -125,33 +290,70 @@ impl TrackListManager {
         F4: Fn(u64) -> Message + Clone + 'static,
     {
         let now = Instant::now();
-        self.tracks.iter().fold(column![], |col, track| {
-            let track_id = track.id;
-            let image_handle = self.track_images.get(&track_id).cloned();
-            let image_opacity = self
-                .image_fades
-                .get(&track_id)
-                .map(|fade| fade.interpolate(0.0, 1.0, now))
-                .unwrap_or(1.0);
-            let widget = get_track_widget(
-                track,
-                image_handle,
-                image_opacity,
-                on_play.clone(),
-                on_user_click.clone(),
-                on_like.clone(),
-            );
-
-            // Wrap each row in a sensor so its artwork loads only when it nears
-            // the viewport. load_image_task() guards against duplicate requests,
-            // so firing on_show again after the image is loaded is harmless.
-            let on_request = on_request_image.clone();
-            let row: Element<'_, Message> = sensor(widget)
-                .on_show(move |_| on_request(track_id))
-                .anticipate(IMAGE_PREFETCH_DISTANCE)
-                .into();
-            col.push(row)
-        })
+        let unix_now = crate::utilities::unix_now();
+        self.sorted(sort, hide_blocked)
+            .into_iter()
+            .fold(column![], |col, track| {
+                let track_id = track.id;
+                let image_handle = self.track_images.get(&track_id).cloned();
+                let image_opacity = self
+                    .image_fades
+                    .get(&track_id)
+                    .map(|fade| fade.interpolate(0.0, 1.0, now))
+                    .unwrap_or(1.0);
+                let posted = self.activity.get(&track_id).map(|&(kind, time)| {
+                    let verb = match kind {
+                        ActivityKind::Post => "posted",
+                        ActivityKind::Repost => "reposted",
+                    };
+                    match time {
+                        Some(time) => {
+                            format!(
+                                "{} {}",
+                                verb,
+                                crate::utilities::format_time_ago(time, unix_now)
+                            )
+                        }
+                        None => verb.to_string(),
+                    }
+                });
+                let widget = get_track_widget(
+                    track,
+                    image_handle,
+                    image_opacity,
+                    posted,
+                    now_playing == Some(track_id),
+                    on_play.clone(),
+                    on_user_click.clone(),
+                    on_like.clone(),
+                );
+
+                let widget: Element<'_, Message> = if self.selected == Some(track_id) {
+                    container(widget).style(selected_row_style).into()
+                } else {
+                    widget.into()
+                };
+
+                // Wrap each row in a sensor so its artwork loads only when it nears
+                // the viewport. load_image_task() guards against duplicate requests,
+                // so firing on_show again after the image is loaded is harmless.
+                let on_request = on_request_image.clone();
+                let row: Element<'_, Message> = sensor(widget)
+                    .on_show(move |_| on_request(track_id))
+                    .anticipate(IMAGE_PREFETCH_DISTANCE)
+                    .into();
+                col.push(row)
+            })
+    }
+}
+
+/// Highlight behind the keyboard-selected row
+fn selected_row_style(theme: &Theme) -> container::Style {
+    let palette = theme.extended_palette();
+    container::Style {
+        background: Some(palette.background.strong.color.into()),
+        border: border::rounded(8),
+        ..container::Style::default()
     }
 }
 
@@ -160,3 +362,144 @@ impl Default for TrackListManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_track;
+
+    fn track(id: u64, title: &str, created_at: &str) -> SoundCloudTrack {
+        SoundCloudTrack {
+            created_at: created_at.to_string(),
+            ..test_track(id, title)
+        }
+    }
+
+    #[test]
+    fn sorts_by_date_and_title_with_undated_tracks_last() {
+        let mut list = TrackListManager::new();
+        list.set_tracks(vec![
+            track(1, "beta", "2020/01/01 00:00:00 +0000"),
+            track(2, "Alpha", ""),
+            track(3, "gamma", "2023/06/01 00:00:00 +0000"),
+        ]);
+        let ids = |sort| -> Vec<u64> { list.sorted_tracks(sort).iter().map(|t| t.id).collect() };
+
+        assert_eq!(ids(TrackSort::Unsorted), [1, 2, 3]);
+        assert_eq!(ids(TrackSort::Newest), [3, 1, 2]);
+        assert_eq!(ids(TrackSort::Oldest), [1, 3, 2]);
+        assert_eq!(ids(TrackSort::TitleAsc), [2, 1, 3]);
+    }
+
+    #[test]
+    fn hide_blocked_leaves_blocked_tracks_out_of_the_rows() {
+        let mut list = TrackListManager::new();
+        let mut blocked = track(2, "b", "");
+        blocked.access = "blocked".to_string();
+        list.set_tracks(vec![track(1, "a", ""), blocked, track(3, "c", "")]);
+        let ids = |hide_blocked| -> Vec<u64> {
+            list.sorted(TrackSort::Unsorted, hide_blocked)
+                .iter()
+                .map(|t| t.id)
+                .collect()
+        };
+
+        assert_eq!(ids(false), [1, 2, 3]);
+        assert_eq!(ids(true), [1, 3]);
+    }
+
+    #[test]
+    fn append_skips_tracks_already_listed() {
+        let mut list = TrackListManager::new();
+        list.set_tracks(vec![track(1, "a", ""), track(2, "b", "")]);
+        list.append_tracks(vec![
+            track(2, "b", ""),
+            track(3, "c", ""),
+            track(3, "c", ""),
+        ]);
+        let ids: Vec<u64> = list.tracks().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+    }
+
+    #[test]
+    fn prepend_puts_new_tracks_first_and_skips_listed_ones() {
+        let mut list = TrackListManager::new();
+        list.set_tracks(vec![track(1, "a", ""), track(2, "b", "")]);
+        list.prepend_tracks(vec![
+            track(4, "d", ""),
+            track(3, "c", ""),
+            track(1, "a", ""),
+        ]);
+        let ids: Vec<u64> = list.tracks().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [4, 3, 1, 2]);
+    }
+
+    #[test]
+    fn visible_set_limits_the_shown_and_queued_tracks() {
+        let mut list = TrackListManager::new();
+        list.set_tracks(vec![
+            track(1, "a", ""),
+            track(2, "b", ""),
+            track(3, "c", ""),
+        ]);
+        list.set_visible(Some(HashSet::from([1, 3])));
+        let ids: Vec<u64> = list
+            .sorted_tracks(TrackSort::Unsorted)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, [1, 3]);
+
+        list.set_visible(None);
+        assert_eq!(list.sorted_tracks(TrackSort::Unsorted).len(), 3);
+    }
+
+    #[test]
+    fn play_all_starts_from_the_first_playable_track() {
+        let mut list = TrackListManager::new();
+        assert!(!list.has_playable_tracks());
+        assert!(list.play_all(false).is_none());
+
+        let mut blocked = track(1, "a", "");
+        blocked.access = "blocked".to_string();
+        let mut unstreamable = track(2, "b", "");
+        unstreamable.stream_url = None;
+        list.set_tracks(vec![blocked.clone(), unstreamable.clone()]);
+        assert!(!list.has_playable_tracks());
+
+        list.set_tracks(vec![
+            blocked,
+            unstreamable,
+            track(3, "c", ""),
+            track(4, "d", ""),
+        ]);
+        assert!(list.has_playable_tracks());
+        let (first, tracks) = list.play_all(false).unwrap();
+        assert_eq!(first.id, 3);
+        assert_eq!(tracks.len(), 4);
+
+        let (first, tracks) = list.play_all(true).unwrap();
+        assert!(first.is_playable());
+        assert_eq!(tracks.len(), 4);
+    }
+
+    #[test]
+    fn selection_follows_the_sorted_order_and_wraps() {
+        let mut list = TrackListManager::new();
+        list.set_tracks(vec![
+            track(1, "beta", ""),
+            track(2, "alpha", ""),
+            track(3, "gamma", ""),
+        ]);
+        let mut step = |step| {
+            let _ = list.move_selection(TrackSort::TitleAsc, false, step, "test_scroll");
+            list.selected_track().map(|t| t.id)
+        };
+
+        assert_eq!(step(1), Some(2));
+        assert_eq!(step(1), Some(1));
+        assert_eq!(step(1), Some(3));
+        assert_eq!(step(1), Some(2));
+        assert_eq!(step(-1), Some(3));
+    }
+}
@@ -0,0 +1,98 @@
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+use crate::Message;
+
+/// System tray icon with playback controls. Only built on Windows and macOS,
+/// where `tray-icon` needs no extra system libraries.
+pub struct TrayManager {
+    tray: TrayIcon, // Dropping it removes the icon
+    play_pause: MenuItem,
+    next: MenuItem,
+    previous: MenuItem,
+    quit: MenuItem,
+    tooltip: Option<String>, // Last tooltip set, to skip redundant OS calls
+    playing: bool,
+}
+
+impl TrayManager {
+    /// Create the tray icon; None if the platform refuses it
+    pub fn new() -> Option<Self> {
+        let play_pause = MenuItem::new("Play", true, None);
+        let next = MenuItem::new("Next", true, None);
+        let previous = MenuItem::new("Previous", true, None);
+        let quit = MenuItem::new("Quit", true, None);
+
+        let menu = Menu::new();
+        let appended = [
+            menu.append(&play_pause),
+            menu.append(&next),
+            menu.append(&previous),
+            menu.append(&PredefinedMenuItem::separator()),
+            menu.append(&quit),
+        ];
+        if let Some(Err(e)) = appended.into_iter().find(Result::is_err) {
+            eprintln!("Tray menu unavailable: {}", e);
+            return None;
+        }
+
+        let tray = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("Rustwave")
+            .with_icon(load_icon()?)
+            .build()
+            .inspect_err(|e| eprintln!("Tray icon unavailable: {}", e))
+            .ok()?;
+
+        Some(Self {
+            tray,
+            play_pause,
+            next,
+            previous,
+            quit,
+            tooltip: None,
+            playing: false,
+        })
+    }
+
+    /// The app message for the next pending menu click, if any
+    pub fn poll(&self) -> Option<Message> {
+        let event = MenuEvent::receiver().try_recv().ok()?;
+        match event.id {
+            id if id == *self.play_pause.id() => Some(Message::PlayPausePlayback),
+            id if id == *self.next.id() => Some(Message::NextTrack),
+            id if id == *self.previous.id() => Some(Message::PreviousTrack),
+            id if id == *self.quit.id() => Some(Message::Quit),
+            _ => None,
+        }
+    }
+
+    /// Mirror the playback state: the play/pause label and the current track
+    /// title as the tooltip
+    pub fn sync(&mut self, playing: bool, track_title: Option<&str>) {
+        if playing != self.playing {
+            self.playing = playing;
+            self.play_pause
+                .set_text(if playing { "Pause" } else { "Play" });
+        }
+
+        let tooltip = track_title.map(str::to_string);
+        if tooltip != self.tooltip {
+            let _ = self
+                .tray
+                .set_tooltip(Some(tooltip.as_deref().unwrap_or("Rustwave")));
+            self.tooltip = tooltip;
+        }
+    }
+}
+
+fn load_icon() -> Option<Icon> {
+    let image = image::load_from_memory(include_bytes!("../../assets/icon.png"))
+        .inspect_err(|e| eprintln!("Failed to decode tray icon: {}", e))
+        .ok()?
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height)
+        .inspect_err(|e| eprintln!("Failed to load tray icon: {}", e))
+        .ok()
+}
@@ -7,6 +7,25 @@ pub struct SoundCloudActivity {
     #[serde(rename(deserialize = "type"))]
     pub activity_type: String,
     pub origin: SoundCloudTrack,
+    pub created_at: Option<String>,
+}
+
+/// How an activity's origin got into the feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    Post,
+    Repost,
+}
+
+impl SoundCloudActivity {
+    /// `track-repost` is a repost; `track` and anything else a post
+    pub fn kind(&self) -> ActivityKind {
+        if self.activity_type.ends_with("-repost") {
+            ActivityKind::Repost
+        } else {
+            ActivityKind::Post
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
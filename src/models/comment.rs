@@ -0,0 +1,20 @@
+use serde::Deserialize;
+
+use super::{SoundCloudUser, deserialize_null_default};
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SoundCloudComments {
+    pub collection: Vec<SoundCloudComment>,
+    pub next_href: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SoundCloudComment {
+    pub id: u64,
+    #[serde(deserialize_with = "deserialize_null_default")]
+    pub body: String,
+    pub user: SoundCloudUser,
+    pub timestamp: Option<u64>, // Position in the track it was left at, in ms
+    #[serde(deserialize_with = "deserialize_null_default")]
+    pub created_at: String,
+}
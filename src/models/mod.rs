@@ -11,6 +11,7 @@ where
 
 // Module declarations
 mod activity;
+mod comment;
 mod item;
 mod message;
 mod playlist;
@@ -19,9 +20,12 @@ mod track;
 mod user;
 
 // Re-exports to maintain the same public API
-pub use activity::SoundCloudActivityCollection;
+pub use activity::{ActivityKind, SoundCloudActivityCollection};
+pub use comment::{SoundCloudComment, SoundCloudComments};
 pub use playlist::{SoundCloudPlaylist, SoundCloudPlaylists};
-pub use search::SearchResults;
+pub use search::{SearchResults, TrackFilters};
+#[cfg(test)]
+pub use track::test_track;
 pub use track::{SoundCloudStreams, SoundCloudTrack, SoundCloudTracks};
 pub use user::{SoundCloudUser, SoundCloudUserProfile, SoundCloudUsers};
 
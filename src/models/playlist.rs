@@ -13,6 +13,8 @@ pub struct SoundCloudPlaylist {
     #[serde(deserialize_with = "deserialize_null_default")]
     pub artwork_url: String,
     pub track_count: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub permalink_url: String, // Public soundcloud.com page
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
@@ -5,6 +5,132 @@ pub struct SearchResults {
     pub tracks: Vec<SoundCloudTrack>,
     pub tracks_next_href: Option<String>,
     pub users: Vec<SoundCloudUser>,
+    pub users_next_href: Option<String>,
     pub playlists: Vec<SoundCloudPlaylist>,
     pub playlists_next_href: Option<String>,
 }
+
+/// Optional length and tempo bounds for track searches. Unset bounds are
+/// left out of the request entirely.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrackFilters {
+    pub min_duration_secs: Option<u32>,
+    pub max_duration_secs: Option<u32>,
+    pub min_bpm: Option<u32>,
+    pub max_bpm: Option<u32>,
+}
+
+impl TrackFilters {
+    /// Parses the search page's filter fields. Lengths are `m` or `m:ss`,
+    /// tempos whole BPM; blank fields mean no bound.
+    pub fn parse(
+        min_duration: &str,
+        max_duration: &str,
+        min_bpm: &str,
+        max_bpm: &str,
+    ) -> Result<Self, String> {
+        let filters = Self {
+            min_duration_secs: parse_bound(min_duration, "Min length", parse_minutes)?,
+            max_duration_secs: parse_bound(max_duration, "Max length", parse_minutes)?,
+            min_bpm: parse_bound(min_bpm, "Min BPM", parse_bpm)?,
+            max_bpm: parse_bound(max_bpm, "Max BPM", parse_bpm)?,
+        };
+        if let (Some(from), Some(to)) = (filters.min_duration_secs, filters.max_duration_secs)
+            && from > to
+        {
+            return Err("Min length is longer than max length".to_string());
+        }
+        if let (Some(from), Some(to)) = (filters.min_bpm, filters.max_bpm)
+            && from > to
+        {
+            return Err("Min BPM is above max BPM".to_string());
+        }
+        Ok(filters)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `/tracks` query params for the set bounds (durations in milliseconds)
+    pub fn query_params(&self) -> Vec<(&'static str, String)> {
+        let ms = |secs: u32| (u64::from(secs) * 1000).to_string();
+        [
+            ("duration[from]", self.min_duration_secs.map(ms)),
+            ("duration[to]", self.max_duration_secs.map(ms)),
+            ("bpm[from]", self.min_bpm.map(|bpm| bpm.to_string())),
+            ("bpm[to]", self.max_bpm.map(|bpm| bpm.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .collect()
+    }
+}
+
+fn parse_bound(
+    input: &str,
+    label: &str,
+    parse: fn(&str) -> Option<u32>,
+) -> Result<Option<u32>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    parse(input)
+        .map(Some)
+        .ok_or_else(|| format!("{} \"{}\" isn't valid", label, input))
+}
+
+/// `m` or `m:ss` to seconds
+fn parse_minutes(input: &str) -> Option<u32> {
+    let (minutes, seconds) = match input.split_once(':') {
+        Some((minutes, seconds)) => {
+            let seconds: u32 = seconds.parse().ok()?;
+            if seconds >= 60 {
+                return None;
+            }
+            (minutes.parse::<u32>().ok()?, seconds)
+        }
+        None => (input.parse::<u32>().ok()?, 0),
+    };
+    minutes.checked_mul(60)?.checked_add(seconds)
+}
+
+fn parse_bpm(input: &str) -> Option<u32> {
+    input.parse().ok().filter(|bpm| (1..=999).contains(bpm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_filters_add_no_params() {
+        let filters = TrackFilters::parse("", " ", "", "").unwrap();
+        assert!(filters.is_empty());
+        assert!(filters.query_params().is_empty());
+    }
+
+    #[test]
+    fn filters_become_millisecond_and_bpm_params() {
+        let filters = TrackFilters::parse("2:30", "8", "120", "").unwrap();
+        assert_eq!(
+            filters.query_params(),
+            vec![
+                ("duration[from]", "150000".to_string()),
+                ("duration[to]", "480000".to_string()),
+                ("bpm[from]", "120".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_or_inverted_ranges_are_rejected() {
+        assert!(TrackFilters::parse("1:75", "", "", "").is_err());
+        assert!(TrackFilters::parse("abc", "", "", "").is_err());
+        assert!(TrackFilters::parse("", "", "0", "").is_err());
+        assert!(TrackFilters::parse("10", "5", "", "").is_err());
+        assert!(TrackFilters::parse("", "", "140", "120").is_err());
+        assert!(TrackFilters::parse("5", "5", "120", "120").is_ok());
+    }
+}
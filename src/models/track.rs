@@ -47,4 +47,100 @@ pub struct SoundCloudTrack {
     pub genre: String,
     #[serde(deserialize_with = "deserialize_null_default")]
     pub created_at: String,
+    // Only filled in by the full track detail response
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub description: String,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub tag_list: String,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub permalink_url: String, // Public soundcloud.com page
+    #[serde(default)]
+    pub user_favorite: Option<bool>, // Liked by the signed-in user when the track was fetched
+}
+
+impl SoundCloudTrack {
+    /// Whether SoundCloud won't stream the track here, e.g. region-locked
+    pub fn is_blocked(&self) -> bool {
+        self.access == "blocked"
+    }
+
+    /// Whether the track can be streamed here: it has a stream and isn't blocked
+    pub fn is_playable(&self) -> bool {
+        self.stream_url.is_some() && !self.is_blocked()
+    }
+
+    /// Tags from `tag_list`, which is space separated with multi-word tags
+    /// in double quotes, e.g. `techno "deep house"`
+    pub fn tags(&self) -> Vec<String> {
+        self.tag_list
+            .split('"')
+            .enumerate()
+            .flat_map(|(i, part)| {
+                // Odd parts sit between a pair of quotes
+                if i % 2 == 1 {
+                    vec![part.trim()]
+                } else {
+                    part.split_whitespace().collect()
+                }
+            })
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// A streamable three-minute track for tests, blank apart from `id` and `title`
+#[cfg(test)]
+pub fn test_track(id: u64, title: &str) -> SoundCloudTrack {
+    SoundCloudTrack {
+        id,
+        stream_url: Some(format!("https://api.soundcloud.com/tracks/{id}/stream")),
+        title: title.to_string(),
+        user: SoundCloudUser::default(),
+        duration: 180_000,
+        access: "playable".to_string(),
+        playback_count: None,
+        favoritings_count: None,
+        reposts_count: None,
+        artwork_url: String::new(),
+        waveform_url: String::new(),
+        genre: String::new(),
+        created_at: String::new(),
+        description: String::new(),
+        tag_list: String::new(),
+        permalink_url: String::new(),
+        user_favorite: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_split_on_spaces_and_keep_quoted_phrases() {
+        let track: SoundCloudTrack = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "stream_url": null,
+            "title": "Track",
+            "user": { "urn": "soundcloud:users:1", "username": "user", "full_name": null, "avatar_url": null, "followers_count": null },
+            "duration": 1000,
+            "access": "playable",
+            "playback_count": null,
+            "favoritings_count": null,
+            "reposts_count": null,
+            "artwork_url": null,
+            "waveform_url": null,
+            "genre": null,
+            "created_at": "2024/01/01",
+            "tag_list": "techno \"deep house\" ambient",
+        }))
+        .unwrap();
+
+        assert_eq!(track.tags(), ["techno", "deep house", "ambient"]);
+        assert!(
+            track.description.is_empty(),
+            "missing fields default to empty"
+        );
+    }
 }
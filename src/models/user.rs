@@ -7,6 +7,7 @@ use super::deserialize_null_default;
 #[derive(Deserialize, Debug, Clone)]
 pub struct SoundCloudUsers {
     pub collection: Vec<SoundCloudUser>,
+    pub next_href: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, Default)]
@@ -19,6 +20,36 @@ pub struct SoundCloudUser {
     #[serde(deserialize_with = "deserialize_null_default")]
     pub avatar_url: String,
     pub followers_count: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub permalink_url: String, // Public soundcloud.com profile page
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub city: String,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub country: String,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub plan: String, // Subscription, e.g. "Pro Unlimited"; "Free" or empty without one
+    #[serde(default)]
+    pub verified: Option<bool>, // Not every response carries it
+}
+
+impl SoundCloudUser {
+    /// "City, Country" from whichever of the two are set
+    pub fn location(&self) -> Option<String> {
+        let parts: Vec<&str> = [self.city.trim(), self.country.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.verified.unwrap_or(false)
+    }
+
+    /// Whether the user pays for a Pro plan
+    pub fn is_pro(&self) -> bool {
+        self.plan.to_lowercase().contains("pro")
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -29,3 +60,45 @@ pub struct SoundCloudUserProfile {
     pub playlists: Vec<SoundCloudPlaylist>,
     pub playlists_next_href: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_extras_default_when_missing() {
+        let user: SoundCloudUser = serde_json::from_value(serde_json::json!({
+            "urn": "soundcloud:users:1",
+            "username": "user",
+            "full_name": null,
+            "avatar_url": null,
+            "followers_count": null,
+            "city": null,
+        }))
+        .unwrap();
+
+        assert_eq!(user.location(), None);
+        assert!(!user.is_verified());
+        assert!(!user.is_pro());
+    }
+
+    #[test]
+    fn location_joins_the_parts_that_are_set() {
+        let user = SoundCloudUser {
+            city: "Berlin".to_string(),
+            country: "Germany".to_string(),
+            plan: "Pro Unlimited".to_string(),
+            ..SoundCloudUser::default()
+        };
+        assert_eq!(user.location().as_deref(), Some("Berlin, Germany"));
+        assert!(user.is_pro());
+
+        let user = SoundCloudUser {
+            country: "Japan".to_string(),
+            plan: "Free".to_string(),
+            ..SoundCloudUser::default()
+        };
+        assert_eq!(user.location().as_deref(), Some("Japan"));
+        assert!(!user.is_pro());
+    }
+}
@@ -1,14 +1,24 @@
-use crate::pages::likes_page::LikesPage;
+use crate::config;
+use crate::constants::{self, Credentials};
 use crate::soundcloud::TokenManager;
 use crate::soundcloud::auth;
 use crate::widgets::spinner;
 use crate::{Message, Page};
-use iced::widget::{button, column, container, text};
+use iced::widget::{button, column, container, text, text_input};
 use iced::{Alignment, Font, Length, Task};
+use tracing::warn;
+
+/// Where SoundCloud developers register apps to get a client ID and secret
+const SOUNDCLOUD_APPS_URL: &str = "https://soundcloud.com/you/apps";
 
 #[derive(Debug, Clone)]
 pub enum AuthPageMessage {
     LoginPressed,
+    ClientIdChanged(String),
+    ClientSecretChanged(String),
+    RedirectUrlChanged(String),
+    SaveCredentials,
+    OpenAppsPage,
     SessionRestored(Option<TokenManager>),
     AuthCompleted(Result<TokenManager, String>),
 }
@@ -16,6 +26,13 @@ pub enum AuthPageMessage {
 type Ma = AuthPageMessage;
 
 enum AuthState {
+    /// First run: no SoundCloud app credentials in `.env` or the config dir
+    NeedsCredentials {
+        client_id: String,
+        client_secret: String,
+        redirect_url: String,
+        error: Option<String>,
+    },
     /// Trying to restore a session from a cached token on startup
     CheckingSession,
     /// No usable cached token; the user has to sign in
@@ -31,15 +48,89 @@ pub struct AuthPage {
 
 impl AuthPage {
     pub fn new() -> (Self, Task<Message>) {
+        if constants::credentials().is_none() {
+            return (
+                Self {
+                    state: AuthState::NeedsCredentials {
+                        client_id: String::new(),
+                        client_secret: String::new(),
+                        redirect_url: constants::DEFAULT_REDIRECT_URL.to_string(),
+                        error: None,
+                    },
+                },
+                Task::none(),
+            );
+        }
+
         (
             Self {
                 state: AuthState::CheckingSession,
             },
-            Task::perform(auth::try_cached_authentication(), |result| {
-                Message::AuthPage(Ma::SessionRestored(result))
-            }),
+            Self::restore_session(),
         )
     }
+
+    fn restore_session() -> Task<Message> {
+        Task::perform(auth::try_cached_authentication(), |result| {
+            Message::AuthPage(Ma::SessionRestored(result))
+        })
+    }
+
+    fn credentials_form<'a>(
+        client_id: &'a str,
+        client_secret: &'a str,
+        redirect_url: &'a str,
+        error: Option<&'a str>,
+    ) -> iced::Element<'a, Message> {
+        let field = |label: &'a str, input: iced::widget::TextInput<'a, Message>| {
+            column![text(label).size(13), input.padding(8)].spacing(4)
+        };
+
+        let mut form = column![
+            text("Connect a SoundCloud app").size(18),
+            text(
+                "Rustwave talks to SoundCloud through an app you register yourself. \
+                 Create one on the SoundCloud developer page, add the redirect URL \
+                 below to it, then paste its client ID and secret here."
+            )
+            .size(13)
+            .style(text::secondary),
+            button(text("Open SoundCloud apps page").size(13))
+                .style(button::text)
+                .padding(0)
+                .on_press(Message::AuthPage(Ma::OpenAppsPage)),
+            field(
+                "Client ID",
+                text_input("Client ID", client_id)
+                    .on_input(|value| Message::AuthPage(Ma::ClientIdChanged(value)))
+            ),
+            field(
+                "Client secret",
+                text_input("Client secret", client_secret)
+                    .secure(true)
+                    .on_input(|value| Message::AuthPage(Ma::ClientSecretChanged(value)))
+            ),
+            field(
+                "Redirect URL",
+                text_input(constants::DEFAULT_REDIRECT_URL, redirect_url)
+                    .on_input(|value| Message::AuthPage(Ma::RedirectUrlChanged(value)))
+                    .on_submit(Message::AuthPage(Ma::SaveCredentials))
+            ),
+        ]
+        .spacing(12)
+        .width(420);
+
+        if let Some(error) = error {
+            form = form.push(text(error).size(13).style(text::danger));
+        }
+
+        form.push(
+            button(text("Save and continue").size(16))
+                .padding([12, 24])
+                .on_press(Message::AuthPage(Ma::SaveCredentials)),
+        )
+        .into()
+    }
 }
 
 impl Page for AuthPage {
@@ -56,10 +147,47 @@ impl Page for AuthPage {
             return (None, Task::none());
         };
 
+        if let AuthState::NeedsCredentials {
+            client_id,
+            client_secret,
+            redirect_url,
+            error,
+        } = &mut self.state
+        {
+            match msg {
+                Ma::ClientIdChanged(value) => *client_id = value,
+                Ma::ClientSecretChanged(value) => *client_secret = value,
+                Ma::RedirectUrlChanged(value) => *redirect_url = value,
+                Ma::OpenAppsPage => {
+                    if let Err(e) = open::that_detached(SOUNDCLOUD_APPS_URL) {
+                        warn!("Failed to open {}: {}", SOUNDCLOUD_APPS_URL, e);
+                    }
+                }
+                Ma::SaveCredentials => {
+                    let saved = Credentials::new(client_id, client_secret, redirect_url).and_then(
+                        |credentials| {
+                            constants::save_credentials(credentials)
+                                .map_err(|e| format!("Couldn't save the credentials: {}", e))
+                        },
+                    );
+                    match saved {
+                        Ok(()) => {
+                            self.state = AuthState::CheckingSession;
+                            return (None, Self::restore_session());
+                        }
+                        Err(e) => *error = Some(e),
+                    }
+                }
+                _ => {}
+            }
+            return (None, Task::none());
+        }
+
         match msg {
             Ma::SessionRestored(Some(token_manager)) | Ma::AuthCompleted(Ok(token_manager)) => {
-                let (page, task) = LikesPage::new(token_manager);
-                (Some(Box::new(page)), task)
+                let landing = config::load_settings().landing_page();
+                let (page, task) = crate::pages::start_page(landing, token_manager);
+                (Some(page), task)
             }
             Ma::SessionRestored(None) => {
                 self.state = AuthState::SignedOut;
@@ -81,10 +209,15 @@ impl Page for AuthPage {
                 self.state = AuthState::Failed(error);
                 (None, Task::none())
             }
+            Ma::ClientIdChanged(_)
+            | Ma::ClientSecretChanged(_)
+            | Ma::RedirectUrlChanged(_)
+            | Ma::SaveCredentials
+            | Ma::OpenAppsPage => (None, Task::none()),
         }
     }
 
-    fn view(&self) -> iced::Element<'_, Message> {
+    fn view(&self, _now_playing: Option<u64>, _hide_blocked: bool) -> iced::Element<'_, Message> {
         let bold = Font {
             weight: iced::font::Weight::Bold,
             ..Font::DEFAULT
@@ -100,6 +233,12 @@ impl Page for AuthPage {
         .align_x(Alignment::Center);
 
         let status: iced::Element<'_, Message> = match &self.state {
+            AuthState::NeedsCredentials {
+                client_id,
+                client_secret,
+                redirect_url,
+                error,
+            } => Self::credentials_form(client_id, client_secret, redirect_url, error.as_deref()),
             AuthState::CheckingSession => column![
                 spinner(32.0),
                 text("Restoring your session…")
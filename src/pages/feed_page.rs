@@ -2,36 +2,70 @@ use iced::widget::image::Handle;
 
 use crate::Message;
 use crate::Page;
-use crate::managers::TrackListManager;
-use crate::models::{SoundCloudActivityCollection, SoundCloudTrack};
+use crate::config::{self, TrackSort};
+use crate::managers::{TrackActivity, TrackListManager};
+use crate::models::{ActivityKind, SoundCloudActivityCollection, SoundCloudTrack};
 use crate::pages::UserPage;
-use crate::pages::{LikesPage, SearchPage};
+use crate::pages::{LikesPage, SearchPage, SettingsPage};
 use crate::soundcloud::TokenManager;
 use crate::soundcloud::api_helpers;
-use crate::widgets::{loading_state, spinner};
+use crate::widgets::{empty_state, sort_control, spinner, track_list_skeleton};
+use iced::Alignment;
 use iced::Color;
 use iced::Length;
 use iced::Task;
 use iced::Vector;
 use iced::advanced::widget::{Id, operate, operation};
 use iced::widget::scrollable::AbsoluteOffset;
-use iced::widget::{Scrollable, button, container, float, sensor, stack, text};
+use iced::widget::{Scrollable, button, container, float, row, sensor, stack, text};
 use tracing::debug;
 
+/// Which kinds of activity the feed shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFilter {
+    All,
+    Posts,
+    Reposts,
+}
+
+impl FeedFilter {
+    const ALL: [FeedFilter; 3] = [FeedFilter::All, FeedFilter::Posts, FeedFilter::Reposts];
+
+    fn label(self) -> &'static str {
+        match self {
+            FeedFilter::All => "All",
+            FeedFilter::Posts => "Posts",
+            FeedFilter::Reposts => "Reposts",
+        }
+    }
+
+    fn shows(self, kind: Option<ActivityKind>) -> bool {
+        match self {
+            FeedFilter::All => true,
+            FeedFilter::Posts => kind != Some(ActivityKind::Repost),
+            FeedFilter::Reposts => kind == Some(ActivityKind::Repost),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum FeedPageMessage {
     LoadFeed,
     LoadMoreFeed,
+    Refresh,
+    Scrolled(f32), // Distance in pixels from the top of the list
+    FeedRefreshedWithToken(SoundCloudActivityCollection, TokenManager),
+    FeedRefreshFailed(String, TokenManager),
     ScrollToTop,
     RequestImage(u64),
     FeedCollectionLoadedWithToken(SoundCloudActivityCollection, TokenManager),
     PlayTrack(SoundCloudTrack),
     ImageLoaded(u64, Handle),
-    ImageLoadFailed(u64),
     LikeTrack(SoundCloudTrack),
     TrackLikedWithToken(u64, TokenManager),
     ApiErrorWithToken(String, TokenManager),
     LoadUser(String),
+    SetFilter(FeedFilter),
 }
 
 type Mf = FeedPageMessage;
@@ -46,7 +80,12 @@ pub struct FeedPage {
     track_list: TrackListManager,
     track_load_failed: bool,
     next_href: Option<String>,
+    requested_href: Option<String>, // Page last fetched, so repeat triggers for it are dropped
     is_loading: bool,
+    auto_refreshing: bool, // A background reload of the first page is in flight
+    at_top: bool,          // List scrolled to the top, so new items can appear without a jump
+    sort_mode: TrackSort,  // Client-side order of the loaded tracks
+    filter: FeedFilter,
 }
 
 impl FeedPage {
@@ -57,11 +96,29 @@ impl FeedPage {
                 track_list: TrackListManager::new(),
                 track_load_failed: false,
                 next_href: None,
-                is_loading: false,
+                requested_href: None,
+                is_loading: true, // The first page is requested right away
+                auto_refreshing: false,
+                at_top: true,
+                sort_mode: config::load_settings().track_sort,
+                filter: FeedFilter::All,
             },
             Task::done(Message::FeedPage(FeedPageMessage::LoadFeed)),
         )
     }
+
+    /// Narrow the list to the tracks `filter` shows
+    fn apply_filter(&mut self) {
+        let visible = (self.filter != FeedFilter::All).then(|| {
+            self.track_list
+                .tracks()
+                .iter()
+                .filter(|t| self.filter.shows(self.track_list.activity_kind(t.id)))
+                .map(|t| t.id)
+                .collect()
+        });
+        self.track_list.set_visible(visible);
+    }
 }
 
 impl Page for FeedPage {
@@ -91,6 +148,43 @@ impl Page for FeedPage {
                         ),
                     );
                 }
+                FeedPageMessage::Refresh => {
+                    // A page still loading would land in the fresh list
+                    if self.is_loading {
+                        return (None, Task::none());
+                    }
+                    // Start over from the first page; clearing the list drops
+                    // its artwork too and shows the loading spinner
+                    self.track_list.set_tracks(Vec::new());
+                    self.next_href = None;
+                    self.requested_href = None;
+                    self.track_load_failed = false;
+                    return self.update(Message::FeedPage(FeedPageMessage::LoadFeed));
+                }
+                FeedPageMessage::Scrolled(offset_y) => {
+                    self.at_top = offset_y <= 1.0;
+                    return (None, Task::none());
+                }
+                FeedPageMessage::FeedRefreshedWithToken(collection, token_manager) => {
+                    self.token_manager = token_manager;
+                    self.auto_refreshing = false;
+                    // A manual refresh emptied the list meanwhile and reloads it itself
+                    if self.track_list.tracks().is_empty() {
+                        return (None, Task::none());
+                    }
+                    let (tracks, activity) = feed_tracks(collection);
+                    self.track_list.prepend_tracks(tracks);
+                    self.track_list.set_activity(activity);
+                    self.apply_filter();
+                    return (None, Task::none());
+                }
+                FeedPageMessage::FeedRefreshFailed(error, token_manager) => {
+                    // Quiet: the list on screen is still good
+                    debug!("Failed to auto-refresh the feed: {}", error);
+                    self.token_manager = token_manager;
+                    self.auto_refreshing = false;
+                    return (None, Task::none());
+                }
                 FeedPageMessage::ScrollToTop => {
                     return (
                         None,
@@ -104,12 +198,17 @@ impl Page for FeedPage {
                     );
                 }
                 FeedPageMessage::LoadMoreFeed => {
-                    // Don't load if already loading or no next page
-                    if self.is_loading || self.next_href.is_none() {
+                    // Don't load if already loading, no next page, or this
+                    // page was already requested by an earlier scroll trigger
+                    if self.is_loading
+                        || self.next_href.is_none()
+                        || self.requested_href == self.next_href
+                    {
                         return (None, Task::none());
                     }
 
                     self.is_loading = true;
+                    self.requested_href = self.next_href.clone();
                     let token_manager = self.token_manager.clone();
                     let next_href = self.next_href.clone();
                     return (
@@ -135,12 +234,7 @@ impl Page for FeedPage {
                     // Store the next_href for pagination
                     self.next_href = collection.next_href.clone();
 
-                    // Extract tracks from activities
-                    let tracks: Vec<SoundCloudTrack> = collection
-                        .collection
-                        .into_iter()
-                        .map(|activity| activity.origin)
-                        .collect();
+                    let (tracks, activity) = feed_tracks(collection);
 
                     // Determine if this is initial load or pagination
                     let is_initial_load = self.track_list.tracks().is_empty();
@@ -152,17 +246,18 @@ impl Page for FeedPage {
                         // Pagination: append tracks
                         self.track_list.append_tracks(tracks);
                     }
+                    self.track_list.set_activity(activity);
+                    self.apply_filter();
 
                     // Artwork now loads lazily per row via RequestImage; nothing to do here.
                     return (None, Task::none());
                 }
                 FeedPageMessage::PlayTrack(track) => {
-                    self.track_list.set_current_track_id(track.id);
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.track_list.tracks().clone(),
+                            self.track_list.sorted_tracks(self.sort_mode),
                             self.token_manager.clone(),
                         )),
                     );
@@ -170,21 +265,15 @@ impl Page for FeedPage {
                 FeedPageMessage::RequestImage(track_id) => {
                     return (
                         None,
-                        self.track_list.load_image_task(
-                            track_id,
-                            |id, handle| Message::FeedPage(Mf::ImageLoaded(id, handle)),
-                            |id| Message::FeedPage(Mf::ImageLoadFailed(id)),
-                        ),
+                        self.track_list.load_image_task(track_id, |id, handle| {
+                            Message::FeedPage(Mf::ImageLoaded(id, handle))
+                        }),
                     );
                 }
                 FeedPageMessage::ImageLoaded(track_id, handle) => {
                     self.track_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                FeedPageMessage::ImageLoadFailed(track_id) => {
-                    println!("Failed to load image for track {}", track_id);
-                    return (None, Task::none());
-                }
                 FeedPageMessage::LikeTrack(track) => {
                     let token_manager = self.token_manager.clone();
                     return (
@@ -210,9 +299,18 @@ impl Page for FeedPage {
                 FeedPageMessage::ApiErrorWithToken(_error_msg, token_manager) => {
                     self.token_manager = token_manager;
                     self.track_load_failed = true;
+                    // Let a page that failed to load be retried by the next trigger
+                    if self.is_loading {
+                        self.requested_href = None;
+                    }
                     self.is_loading = false;
                     return (None, Task::none());
                 }
+                FeedPageMessage::SetFilter(filter) => {
+                    self.filter = filter;
+                    self.apply_filter();
+                    return (None, Task::none());
+                }
                 FeedPageMessage::LoadUser(user_urn) => {
                     let (user_page, task) = UserPage::new(self.token_manager.clone(), user_urn);
                     return (Some(Box::new(user_page)), task);
@@ -220,11 +318,55 @@ impl Page for FeedPage {
             }
         }
 
+        // Background reload of the first page, only while the user is at the
+        // top so new items don't shift what they're looking at
+        if let Message::FeedAutorefresh = message
+            && self.at_top
+            && !self.is_loading
+            && !self.auto_refreshing
+            && !self.track_list.tracks().is_empty()
+        {
+            self.auto_refreshing = true;
+            return (
+                None,
+                Task::perform(
+                    api_helpers::load_feed_paginated_with_refresh(self.token_manager.clone(), None),
+                    |result| match result {
+                        Ok((collection, token_manager)) => {
+                            Message::FeedPage(Mf::FeedRefreshedWithToken(collection, token_manager))
+                        }
+                        Err((error, token_manager)) => Message::FeedPage(Mf::FeedRefreshFailed(
+                            error.to_string(),
+                            token_manager,
+                        )),
+                    },
+                ),
+            );
+        }
+
         if let Message::NavigateToLikes = message {
             let (page, task) = LikesPage::new(self.token_manager.clone());
             return (Some(Box::new(page)), task);
         }
 
+        if let Message::SetTrackSort(sort) = message {
+            self.sort_mode = sort;
+        }
+
+        if let Message::MoveSelection(step, hide_blocked) = message {
+            return (
+                None,
+                self.track_list
+                    .move_selection(self.sort_mode, hide_blocked, step, SCROLL_ID),
+            );
+        }
+
+        if let Message::PlaySelectedTrack = message
+            && let Some(track) = self.track_list.selected_track().cloned()
+        {
+            return self.update(Message::FeedPage(FeedPageMessage::PlayTrack(track)));
+        }
+
         if let Message::NavigateToSearch = message {
             return (
                 Some(Box::new(SearchPage::new(self.token_manager.clone()))),
@@ -232,19 +374,48 @@ impl Page for FeedPage {
             );
         }
 
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
         (None, Task::none())
     }
 
-    fn view(&self) -> iced::Element<'_, Message> {
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message> {
         use iced::widget::column;
 
-        let mut tracks_column = self.track_list.render_tracks(
+        let mut tracks_column = self.track_list.render_sorted_tracks(
+            self.sort_mode,
+            now_playing,
+            hide_blocked,
             |t| Message::FeedPage(FeedPageMessage::PlayTrack(t)),
             |urn| Message::FeedPage(FeedPageMessage::LoadUser(urn)),
             |t| Message::FeedPage(FeedPageMessage::LikeTrack(t)),
             |id| Message::FeedPage(FeedPageMessage::RequestImage(id)),
         );
 
+        let any_shown = self
+            .track_list
+            .tracks()
+            .iter()
+            .any(|t| self.filter.shows(self.track_list.activity_kind(t.id)));
+        if !any_shown && !self.track_list.tracks().is_empty() {
+            tracks_column = tracks_column.push(
+                container(
+                    text(format!(
+                        "No {} in the feed so far",
+                        self.filter.label().to_lowercase()
+                    ))
+                    .style(text::secondary),
+                )
+                .center_x(Length::Fill)
+                .padding(16),
+            );
+        }
+
         if self.next_href.is_some() {
             // Bottom sentinel: fires LoadMoreFeed when scrolled near the end.
             // Keyed on the track count so it re-triggers after each page is appended.
@@ -256,20 +427,56 @@ impl Page for FeedPage {
             );
         }
 
-        let mut content = column![];
+        let filters = FeedFilter::ALL
+            .into_iter()
+            .fold(row![].spacing(4), |row, filter| {
+                row.push(
+                    button(text(filter.label()).size(14))
+                        .style(if self.filter == filter {
+                            button::primary
+                        } else {
+                            button::secondary
+                        })
+                        .on_press(Message::FeedPage(Mf::SetFilter(filter))),
+                )
+            });
+
+        let mut content = column![
+            row![
+                filters,
+                sort_control(self.sort_mode),
+                button(text("Refresh").size(14))
+                    .on_press_maybe((!self.is_loading).then_some(Message::FeedPage(Mf::Refresh))),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(5);
         if self.track_load_failed {
             content =
                 content.push(text("Error Loading Tracks").color(Color::from_rgb(1.0, 0.0, 0.0)));
         }
 
-        if self.track_list.tracks().is_empty() && self.is_loading {
-            // Initial load: no tracks to show yet, so fill the page with a spinner.
-            return content.push(loading_state()).into();
+        if self.track_list.tracks().is_empty() {
+            // Initial load: placeholder rows until the first page arrives
+            if self.is_loading {
+                return content.push(track_list_skeleton()).into();
+            }
+            if !self.track_load_failed {
+                return content
+                    .push(empty_state(
+                        None,
+                        "Your feed is empty".to_string(),
+                        "New tracks from artists you follow show up here".to_string(),
+                    ))
+                    .into();
+            }
         }
 
         content = content.push(
             Scrollable::new(tracks_column)
                 .id(SCROLL_ID)
+                .on_scroll(|viewport| Message::FeedPage(Mf::Scrolled(viewport.absolute_offset().y)))
                 .style(crate::widgets::scrollbar_style)
                 .height(Length::FillPortion(1))
                 .width(Length::FillPortion(1)),
@@ -305,3 +512,33 @@ impl Page for FeedPage {
         stack![content, fab].into()
     }
 }
+
+/// The tracks of a feed page, and whether and when each was posted or
+/// reposted for the rows' "reposted X ago"
+fn feed_tracks(
+    collection: SoundCloudActivityCollection,
+) -> (Vec<SoundCloudTrack>, Vec<TrackActivity>) {
+    let activity = collection
+        .collection
+        .iter()
+        .map(|activity| {
+            (
+                activity.origin.id,
+                activity.kind(),
+                activity
+                    .created_at
+                    .as_deref()
+                    .and_then(crate::utilities::parse_timestamp),
+            )
+        })
+        .collect();
+
+    // Extract tracks from activities
+    let tracks = collection
+        .collection
+        .into_iter()
+        .map(|activity| activity.origin)
+        .collect();
+
+    (tracks, activity)
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use iced::Task;
+use iced::widget::image::Handle;
+use iced::widget::{Scrollable, column, container, grid, sensor, text};
+use iced::{Color, Length};
+use tracing::debug;
+
+use crate::models::{SoundCloudUser, SoundCloudUsers};
+use crate::pages::{FeedPage, LikesPage, SearchPage, SettingsPage, UserPage};
+use crate::soundcloud::api_helpers;
+use crate::soundcloud::{AuthError, TokenManager};
+use crate::widgets::{empty_state, get_user_widget, loading_state, spinner};
+use crate::{Message, Page};
+
+/// Which side of the follow graph the page lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowList {
+    Followings, // Users the logged-in user follows
+    Followers,  // Users following the page's user
+}
+
+#[derive(Debug, Clone)]
+pub enum FollowsPageMessage {
+    LoadUsers,
+    LoadMoreUsers,
+    UsersLoadedWithToken(SoundCloudUsers, TokenManager),
+    ApiErrorWithToken(String, TokenManager),
+    UserImageLoaded(String, Handle),
+    LoadUser(String),
+}
+
+type Mfl = FollowsPageMessage;
+
+// Start loading the next page when the bottom sentinel is within 500px of the viewport
+const LOAD_MORE_THRESHOLD: f32 = 500.0;
+
+pub struct FollowsPage {
+    token_manager: TokenManager,
+    list: FollowList,
+    user_urn: String, // Whose followers to list; unused for followings
+    users: Vec<SoundCloudUser>,
+    user_images: HashMap<String, Handle>,
+    next_href: Option<String>,
+    is_loading: bool,
+    load_failed: bool,
+}
+
+impl FollowsPage {
+    pub fn new(
+        token_manager: TokenManager,
+        list: FollowList,
+        user_urn: String,
+    ) -> (Self, Task<Message>) {
+        (
+            Self {
+                token_manager,
+                list,
+                user_urn,
+                users: Vec::new(),
+                user_images: HashMap::new(),
+                next_href: None,
+                is_loading: false,
+                load_failed: false,
+            },
+            Task::done(Message::FollowsPage(FollowsPageMessage::LoadUsers)),
+        )
+    }
+
+    /// Fetches a page of users (initial load when `next_href` is `None`,
+    /// pagination otherwise).
+    fn fetch_users_task(&self, next_href: Option<String>) -> Task<Message> {
+        let token_manager = self.token_manager.clone();
+        let on_result = |result: Result<_, (AuthError, TokenManager)>| match result {
+            Ok((users, token_manager)) => {
+                Message::FollowsPage(Mfl::UsersLoadedWithToken(users, token_manager))
+            }
+            Err((error, token_manager)) => {
+                Message::FollowsPage(Mfl::ApiErrorWithToken(error.to_string(), token_manager))
+            }
+        };
+        match self.list {
+            FollowList::Followings => Task::perform(
+                api_helpers::get_followings_with_refresh(token_manager, next_href),
+                on_result,
+            ),
+            FollowList::Followers => Task::perform(
+                api_helpers::get_followers_with_refresh(
+                    token_manager,
+                    self.user_urn.clone(),
+                    next_href,
+                ),
+                on_result,
+            ),
+        }
+    }
+
+    /// Downloads the avatars for a batch of users.
+    fn user_images_task(users: &[SoundCloudUser]) -> Task<Message> {
+        crate::utilities::load_images_batched(
+            users
+                .iter()
+                .map(|user| (user.urn.clone(), user.avatar_url.clone()))
+                .collect(),
+            |user_urn, handle| Message::FollowsPage(Mfl::UserImageLoaded(user_urn, handle)),
+        )
+    }
+
+    fn title(&self) -> &'static str {
+        match self.list {
+            FollowList::Followings => "Following",
+            FollowList::Followers => "Followers",
+        }
+    }
+}
+
+impl Page for FollowsPage {
+    fn is_animating(&self) -> bool {
+        // Keep frames flowing while the loading spinner is on screen.
+        self.is_loading
+    }
+
+    fn update(&mut self, message: Message) -> (Option<Box<dyn Page>>, Task<Message>) {
+        if let Message::FollowsPage(msg) = message {
+            match msg {
+                FollowsPageMessage::LoadUsers => {
+                    self.is_loading = true;
+                    return (None, self.fetch_users_task(None));
+                }
+                FollowsPageMessage::LoadMoreUsers => {
+                    // Don't load if already loading or no next page
+                    if self.is_loading || self.next_href.is_none() {
+                        return (None, Task::none());
+                    }
+                    self.is_loading = true;
+                    return (None, self.fetch_users_task(self.next_href.clone()));
+                }
+                FollowsPageMessage::UsersLoadedWithToken(users, token_manager) => {
+                    self.token_manager = token_manager;
+                    self.is_loading = false;
+                    self.load_failed = false;
+                    self.next_href = users.next_href;
+
+                    let image_task = Self::user_images_task(&users.collection);
+                    self.users.extend(users.collection);
+                    return (None, image_task);
+                }
+                FollowsPageMessage::ApiErrorWithToken(error, token_manager) => {
+                    debug!("Failed to load {}: {}", self.title().to_lowercase(), error);
+                    self.token_manager = token_manager;
+                    self.is_loading = false;
+                    self.load_failed = true;
+                    return (None, Task::none());
+                }
+                FollowsPageMessage::UserImageLoaded(user_urn, handle) => {
+                    self.user_images.insert(user_urn, handle);
+                    return (None, Task::none());
+                }
+                FollowsPageMessage::LoadUser(user_urn) => {
+                    let (user_page, task) = UserPage::new(self.token_manager.clone(), user_urn);
+                    return (Some(Box::new(user_page)), task);
+                }
+            }
+        }
+
+        if let Message::NavigateToFeed = message {
+            let (page, task) = FeedPage::new(self.token_manager.clone());
+            return (Some(Box::new(page)), task);
+        }
+
+        if let Message::NavigateToLikes = message {
+            let (page, task) = LikesPage::new(self.token_manager.clone());
+            return (Some(Box::new(page)), task);
+        }
+
+        if let Message::NavigateToSearch = message {
+            return (
+                Some(Box::new(SearchPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
+        (None, Task::none())
+    }
+
+    fn view(&self, _now_playing: Option<u64>, _hide_blocked: bool) -> iced::Element<'_, Message> {
+        let header = text(self.title()).size(24);
+
+        if self.users.is_empty() {
+            let body = if self.is_loading {
+                loading_state()
+            } else if self.load_failed {
+                empty_state(
+                    None,
+                    format!("Couldn't load {}", self.title().to_lowercase()),
+                    "Something went wrong talking to SoundCloud".to_string(),
+                )
+            } else {
+                match self.list {
+                    FollowList::Followings => empty_state(
+                        None,
+                        "Not following anyone".to_string(),
+                        "Users you follow on SoundCloud show up here".to_string(),
+                    ),
+                    FollowList::Followers => empty_state(
+                        None,
+                        "No followers yet".to_string(),
+                        "Users following you on SoundCloud show up here".to_string(),
+                    ),
+                }
+            };
+            return column![header, body].spacing(10).into();
+        }
+
+        // Responsive grid of user cards: column count adapts to available width.
+        let user_cells = self.users.iter().map(|user| {
+            let image_handle = self.user_images.get(&user.urn).cloned();
+            iced::Element::from(get_user_widget(user, image_handle, |urn| {
+                Message::FollowsPage(FollowsPageMessage::LoadUser(urn))
+            }))
+        });
+        let mut users_column = column![
+            grid(user_cells)
+                .fluid(300)
+                .spacing(10)
+                .height(Length::Shrink)
+        ];
+
+        if self.next_href.is_some() {
+            // Bottom sentinel: fires LoadMoreUsers when scrolled near the end.
+            // Keyed on the user count so it re-triggers after each page is appended.
+            users_column = users_column.push(
+                sensor(container(spinner(24.0)).center_x(Length::Fill).padding(8))
+                    .on_show(|_| Message::FollowsPage(Mfl::LoadMoreUsers))
+                    .anticipate(LOAD_MORE_THRESHOLD)
+                    .key(self.users.len()),
+            );
+        }
+
+        let mut content = column![header].spacing(10);
+        if self.load_failed {
+            content = content.push(
+                text(format!("Error Loading {}", self.title()))
+                    .color(Color::from_rgb(1.0, 0.0, 0.0)),
+            );
+        }
+        content
+            .push(
+                Scrollable::new(users_column)
+                    .style(crate::widgets::scrollbar_style)
+                    .height(Length::Fill)
+                    .width(Length::Fill),
+            )
+            .into()
+    }
+}
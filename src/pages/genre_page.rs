@@ -0,0 +1,351 @@
+use crate::Message;
+use crate::Page;
+use crate::config::TrackSort;
+use crate::managers::TrackListManager;
+use crate::models::SoundCloudTrack;
+use crate::models::SoundCloudTracks;
+use crate::pages::{FeedPage, LikesPage, SearchPage, SettingsPage, UserPage};
+use crate::soundcloud::TokenManager;
+use crate::soundcloud::api_helpers;
+use crate::widgets::{empty_state, spinner, track_list_skeleton};
+use iced::Length;
+use iced::Task;
+use iced::widget::image::Handle;
+use iced::widget::{Scrollable, button, column, container, row, sensor, text};
+use iced::{Color, Font};
+use tracing::debug;
+
+// Start loading the next page when the bottom sentinel is within 500px of the viewport
+const LOAD_MORE_THRESHOLD: f32 = 500.0;
+// Stable id of the track Scrollable, for keeping the keyboard selection in view
+const SCROLL_ID: &str = "genre_scroll";
+
+/// Genres offered for browsing, as SoundCloud spells them
+const GENRES: [&str; 16] = [
+    "Ambient",
+    "Classical",
+    "Country",
+    "Dance & EDM",
+    "Deep House",
+    "Drum & Bass",
+    "Dubstep",
+    "Electronic",
+    "Hip-hop & Rap",
+    "House",
+    "Indie",
+    "Jazz & Blues",
+    "Metal",
+    "Pop",
+    "R&B & Soul",
+    "Techno",
+];
+
+#[derive(Debug, Clone)]
+pub enum GenrePageMessage {
+    SelectGenre(&'static str),
+    LoadMoreTracks,
+    TracksLoadedWithToken(&'static str, SoundCloudTracks, TokenManager), // Genre they're for
+    RequestImage(u64),
+    PlayTrack(SoundCloudTrack),
+    ImageLoaded(u64, Handle),
+    LikeTrack(SoundCloudTrack),
+    TrackLikedWithToken(u64, TokenManager),
+    ApiErrorWithToken(&'static str, String, TokenManager), // Genre the load was for
+    LikeFailedWithToken(String, TokenManager),
+    LoadUser(String),
+}
+
+type Mg = GenrePageMessage;
+
+/// Genre picker with the chosen genre's tracks below it
+pub struct GenrePage {
+    token_manager: TokenManager,
+    genre: Option<&'static str>, // None until one is picked
+    track_list: TrackListManager,
+    tracks_next_href: Option<String>,
+    tracks_loading: bool,
+    track_load_failed: bool,
+}
+
+impl GenrePage {
+    pub fn new(token_manager: TokenManager) -> Self {
+        Self {
+            token_manager,
+            genre: None,
+            track_list: TrackListManager::new(),
+            tracks_next_href: None,
+            tracks_loading: false,
+            track_load_failed: false,
+        }
+    }
+
+    /// Fetches a page of `genre`'s tracks (first page when `next_href` is `None`)
+    fn fetch_tracks_task(&self, genre: &'static str, next_href: Option<String>) -> Task<Message> {
+        Task::perform(
+            api_helpers::browse_genre_with_refresh(
+                self.token_manager.clone(),
+                genre.to_string(),
+                next_href,
+            ),
+            move |result| match result {
+                Ok((tracks, token_manager)) => {
+                    Message::GenrePage(Mg::TracksLoadedWithToken(genre, tracks, token_manager))
+                }
+                Err((error, token_manager)) => Message::GenrePage(Mg::ApiErrorWithToken(
+                    genre,
+                    error.to_string(),
+                    token_manager,
+                )),
+            },
+        )
+    }
+
+    /// A button per genre, the selected one highlighted
+    fn genre_picker(&self) -> iced::Element<'_, Message> {
+        row(GENRES.iter().map(|&genre| {
+            button(text(genre))
+                .style(if self.genre == Some(genre) {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(Message::GenrePage(Mg::SelectGenre(genre)))
+                .into()
+        }))
+        .spacing(6)
+        .wrap()
+        .vertical_spacing(6)
+        .into()
+    }
+}
+
+impl Page for GenrePage {
+    fn is_animating(&self) -> bool {
+        // Keep frames flowing while the loading spinner is on screen.
+        self.track_list.is_animating() || self.tracks_loading
+    }
+
+    fn update(&mut self, message: Message) -> (Option<Box<dyn Page>>, Task<Message>) {
+        if let Message::GenrePage(msg) = message {
+            match msg {
+                GenrePageMessage::SelectGenre(genre) => {
+                    if self.genre == Some(genre) {
+                        return (None, Task::none());
+                    }
+                    self.genre = Some(genre);
+                    self.track_list.set_tracks(Vec::new());
+                    self.tracks_next_href = None;
+                    self.tracks_loading = true;
+                    self.track_load_failed = false;
+                    return (None, self.fetch_tracks_task(genre, None));
+                }
+                GenrePageMessage::LoadMoreTracks => {
+                    let Some(genre) = self.genre else {
+                        return (None, Task::none());
+                    };
+                    if self.tracks_loading || self.tracks_next_href.is_none() {
+                        return (None, Task::none());
+                    }
+                    self.tracks_loading = true;
+                    return (
+                        None,
+                        self.fetch_tracks_task(genre, self.tracks_next_href.clone()),
+                    );
+                }
+                GenrePageMessage::TracksLoadedWithToken(genre, tracks, token_manager) => {
+                    self.token_manager = token_manager;
+                    // A genre that was switched away from while it loaded
+                    if self.genre != Some(genre) {
+                        return (None, Task::none());
+                    }
+                    self.track_load_failed = false;
+                    self.tracks_loading = false;
+                    self.tracks_next_href = tracks.next_href.clone();
+                    if self.track_list.tracks().is_empty() {
+                        self.track_list.set_tracks(tracks.collection);
+                    } else {
+                        self.track_list.append_tracks(tracks.collection);
+                    }
+                    return (None, Task::none());
+                }
+                GenrePageMessage::RequestImage(track_id) => {
+                    return (
+                        None,
+                        self.track_list.load_image_task(track_id, |id, handle| {
+                            Message::GenrePage(Mg::ImageLoaded(id, handle))
+                        }),
+                    );
+                }
+                GenrePageMessage::PlayTrack(track) => {
+                    return (
+                        None,
+                        Task::done(Message::StartQueue(
+                            track.clone(),
+                            self.track_list.shared_tracks(),
+                            self.token_manager.clone(),
+                        )),
+                    );
+                }
+                GenrePageMessage::LikeTrack(track) => {
+                    let token_manager = self.token_manager.clone();
+                    return (
+                        None,
+                        Task::perform(
+                            api_helpers::like_track_with_refresh(token_manager, track.clone()),
+                            move |result| match result {
+                                Ok((track_id, token_manager)) => Message::GenrePage(
+                                    Mg::TrackLikedWithToken(track_id, token_manager),
+                                ),
+                                Err((error, token_manager)) => Message::GenrePage(
+                                    Mg::LikeFailedWithToken(error.to_string(), token_manager),
+                                ),
+                            },
+                        ),
+                    );
+                }
+                GenrePageMessage::TrackLikedWithToken(track_id, token_manager) => {
+                    self.token_manager = token_manager;
+                    debug!("Track liked: {}", track_id);
+                    return (None, Task::none());
+                }
+                GenrePageMessage::ApiErrorWithToken(genre, error_msg, token_manager) => {
+                    self.token_manager = token_manager;
+                    // A genre that was switched away from while it loaded
+                    if self.genre != Some(genre) {
+                        debug!("Ignoring stale genre error: {}", error_msg);
+                        return (None, Task::none());
+                    }
+                    self.track_load_failed = true;
+                    self.tracks_loading = false;
+                    debug!("API Error: {}", error_msg);
+                    return (None, Task::none());
+                }
+                GenrePageMessage::LikeFailedWithToken(error_msg, token_manager) => {
+                    self.token_manager = token_manager;
+                    debug!("Failed to like track: {}", error_msg);
+                    return (None, Task::none());
+                }
+                GenrePageMessage::ImageLoaded(track_id, handle) => {
+                    self.track_list.handle_image_loaded(track_id, handle);
+                    return (None, Task::none());
+                }
+                GenrePageMessage::LoadUser(user_urn) => {
+                    debug!("Loading user {}", user_urn);
+                    let (user_page, task) = UserPage::new(self.token_manager.clone(), user_urn);
+                    return (Some(Box::new(user_page)), task);
+                }
+            }
+        }
+
+        if let Message::MoveSelection(step, hide_blocked) = message {
+            return (
+                None,
+                self.track_list
+                    .move_selection(TrackSort::Unsorted, hide_blocked, step, SCROLL_ID),
+            );
+        }
+
+        if let Message::PlaySelectedTrack = message
+            && let Some(track) = self.track_list.selected_track().cloned()
+        {
+            return self.update(Message::GenrePage(Mg::PlayTrack(track)));
+        }
+
+        if let Message::NavigateToFeed = message {
+            let (page, task) = FeedPage::new(self.token_manager.clone());
+            return (Some(Box::new(page)), task);
+        }
+
+        if let Message::NavigateToLikes = message {
+            let (page, task) = LikesPage::new(self.token_manager.clone());
+            return (Some(Box::new(page)), task);
+        }
+
+        if let Message::NavigateToSearch = message {
+            return (
+                Some(Box::new(SearchPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
+        (None, Task::none())
+    }
+
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message> {
+        let bold = Font {
+            weight: iced::font::Weight::Bold,
+            ..Font::DEFAULT
+        };
+        let mut content = column![
+            text("Browse genres").size(24).font(bold),
+            self.genre_picker()
+        ]
+        .spacing(10);
+
+        let Some(genre) = self.genre else {
+            return content
+                .push(empty_state(
+                    None,
+                    "Pick a genre".to_string(),
+                    "Its tracks will show up here".to_string(),
+                ))
+                .into();
+        };
+
+        if self.track_load_failed {
+            content =
+                content.push(text("Error Loading Tracks").color(Color::from_rgb(1.0, 0.0, 0.0)));
+        }
+
+        if self.track_list.tracks().is_empty() {
+            // Initial load: placeholder rows until the first page arrives
+            if self.tracks_loading {
+                return content.push(track_list_skeleton()).into();
+            }
+            if !self.track_load_failed {
+                return content
+                    .push(empty_state(
+                        None,
+                        "No tracks".to_string(),
+                        format!("Nothing on SoundCloud is tagged {}", genre),
+                    ))
+                    .into();
+            }
+        }
+
+        let mut tracks_column = self.track_list.render_tracks(
+            now_playing,
+            hide_blocked,
+            |t| Message::GenrePage(Mg::PlayTrack(t)),
+            |urn| Message::GenrePage(Mg::LoadUser(urn)),
+            |t| Message::GenrePage(Mg::LikeTrack(t)),
+            |id| Message::GenrePage(Mg::RequestImage(id)),
+        );
+        if self.tracks_next_href.is_some() {
+            // Bottom sentinel: loads the next page of tracks when scrolled near the end.
+            tracks_column = tracks_column.push(
+                sensor(container(spinner(24.0)).center_x(Length::Fill).padding(8))
+                    .on_show(|_| Message::GenrePage(Mg::LoadMoreTracks))
+                    .anticipate(LOAD_MORE_THRESHOLD)
+                    .key(self.track_list.tracks().len()),
+            );
+        }
+
+        content
+            .push(
+                Scrollable::new(tracks_column)
+                    .id(SCROLL_ID)
+                    .style(crate::widgets::scrollbar_style)
+                    .height(Length::FillPortion(1))
+                    .width(Length::FillPortion(1)),
+            )
+            .into()
+    }
+}
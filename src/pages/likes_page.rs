@@ -1,25 +1,30 @@
+use crate::config::{self, TrackSort};
 use crate::managers::TrackListManager;
+use crate::managers::export::{ExportFormat, save_export};
 use crate::models::SoundCloudTrack;
-use crate::pages::{FeedPage, SearchPage, UserPage};
+use crate::pages::{FeedPage, SearchPage, SettingsPage, UserPage};
 use crate::soundcloud::TokenManager;
 use crate::soundcloud::api_helpers;
-use crate::widgets::{loading_state, spinner};
+use crate::widgets::{empty_state, sort_control, spinner, track_list_skeleton};
 use crate::{Message, Page};
 use iced::advanced::widget::{Id, operate, operation};
 use iced::widget::image::Handle;
 use iced::widget::scrollable::AbsoluteOffset;
-use iced::widget::{Scrollable, button, column, container, float, sensor, stack, text};
-use iced::{Color, Length, Task, Vector};
+use iced::widget::{
+    Scrollable, button, column, container, float, pick_list, row, sensor, stack, text,
+};
+use iced::{Alignment, Color, Length, Task, Vector};
+use tracing::debug;
 
 #[derive(Debug, Clone)]
 pub enum LikesPageMessage {
     LoadFavourites,
     LoadMoreFavourites,
+    Refresh,
     ScrollToTop,
     RequestImage(u64),
     PlayTrack(SoundCloudTrack),
     ImageLoaded(u64, Handle),
-    ImageLoadFailed(u64),
     LikeTrack(SoundCloudTrack),
     FavouritesLoadedWithToken(crate::models::SoundCloudTracks, TokenManager),
     TrackLikedWithToken(u64, TokenManager),
@@ -38,7 +43,9 @@ pub struct LikesPage {
     track_list: TrackListManager,
     track_load_failed: bool,
     next_href: Option<String>,
+    requested_href: Option<String>, // Page last fetched, so repeat triggers for it are dropped
     is_loading: bool,
+    sort_mode: TrackSort, // Client-side order of the loaded tracks
 }
 
 impl LikesPage {
@@ -49,7 +56,9 @@ impl LikesPage {
                 track_list: TrackListManager::new(),
                 track_load_failed: false,
                 next_href: None,
-                is_loading: false,
+                requested_href: None,
+                is_loading: true, // The first page is requested right away
+                sort_mode: config::load_settings().track_sort,
             },
             Task::done(Message::LikesPage(LikesPageMessage::LoadFavourites)),
         )
@@ -87,12 +96,17 @@ impl Page for LikesPage {
                     );
                 }
                 LikesPageMessage::LoadMoreFavourites => {
-                    // Don't load if already loading or no next page
-                    if self.is_loading || self.next_href.is_none() {
+                    // Don't load if already loading, no next page, or this
+                    // page was already requested by an earlier scroll trigger
+                    if self.is_loading
+                        || self.next_href.is_none()
+                        || self.requested_href == self.next_href
+                    {
                         return (None, Task::none());
                     }
 
                     self.is_loading = true;
+                    self.requested_href = self.next_href.clone();
                     let token_manager = self.token_manager.clone();
                     let next_href = self.next_href.clone();
                     return (
@@ -113,6 +127,19 @@ impl Page for LikesPage {
                         ),
                     );
                 }
+                LikesPageMessage::Refresh => {
+                    // A page still loading would land in the fresh list
+                    if self.is_loading {
+                        return (None, Task::none());
+                    }
+                    // Start over from the first page; clearing the list drops
+                    // its artwork too and shows the loading spinner
+                    self.track_list.set_tracks(Vec::new());
+                    self.next_href = None;
+                    self.requested_href = None;
+                    self.track_load_failed = false;
+                    return self.update(Message::LikesPage(LikesPageMessage::LoadFavourites));
+                }
                 LikesPageMessage::ScrollToTop => {
                     return (
                         None,
@@ -126,14 +153,12 @@ impl Page for LikesPage {
                     );
                 }
                 LikesPageMessage::PlayTrack(track) => {
-                    self.track_list.set_current_track_id(track.id);
-
                     // Send the StartQueue message to main app with the selected track and all tracks
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.track_list.tracks().clone(),
+                            self.track_list.sorted_tracks(self.sort_mode),
                             self.token_manager.clone(),
                         )),
                     );
@@ -141,21 +166,15 @@ impl Page for LikesPage {
                 LikesPageMessage::RequestImage(track_id) => {
                     return (
                         None,
-                        self.track_list.load_image_task(
-                            track_id,
-                            |id, handle| Message::LikesPage(Ml::ImageLoaded(id, handle)),
-                            |id| Message::LikesPage(Ml::ImageLoadFailed(id)),
-                        ),
+                        self.track_list.load_image_task(track_id, |id, handle| {
+                            Message::LikesPage(Ml::ImageLoaded(id, handle))
+                        }),
                     );
                 }
                 LikesPageMessage::ImageLoaded(track_id, handle) => {
                     self.track_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                LikesPageMessage::ImageLoadFailed(track_id) => {
-                    println!("Failed to load image for track {}", track_id);
-                    return (None, Task::none());
-                }
                 LikesPageMessage::LikeTrack(track) => {
                     let token_manager = self.token_manager.clone();
                     return (
@@ -197,14 +216,18 @@ impl Page for LikesPage {
                 }
                 LikesPageMessage::TrackLikedWithToken(track_id, token_manager) => {
                     self.token_manager = token_manager;
-                    println!("Track liked: {}", track_id);
+                    debug!("Track liked: {}", track_id);
                     return (None, Task::none());
                 }
                 LikesPageMessage::ApiErrorWithToken(error_msg, token_manager) => {
                     self.token_manager = token_manager;
                     self.track_load_failed = true;
+                    // Let a page that failed to load be retried by the next trigger
+                    if self.is_loading {
+                        self.requested_href = None;
+                    }
                     self.is_loading = false;
-                    println!("API Error: {}", error_msg);
+                    debug!("API Error: {}", error_msg);
                     return (None, Task::none());
                 }
                 LikesPageMessage::LoadUser(user_urn) => {
@@ -214,6 +237,38 @@ impl Page for LikesPage {
             }
         }
 
+        // Only what's loaded so far; scroll further to include older likes
+        if let Message::ExportLikes(format) = message
+            && !self.track_list.tracks().is_empty()
+        {
+            let tracks = self.track_list.sorted_tracks(self.sort_mode);
+            return (
+                None,
+                Task::perform(
+                    async move { save_export(tracks, format, "rustwave-likes").await },
+                    Message::LibraryExported,
+                ),
+            );
+        }
+
+        if let Message::SetTrackSort(sort) = message {
+            self.sort_mode = sort;
+        }
+
+        if let Message::MoveSelection(step, hide_blocked) = message {
+            return (
+                None,
+                self.track_list
+                    .move_selection(self.sort_mode, hide_blocked, step, SCROLL_ID),
+            );
+        }
+
+        if let Message::PlaySelectedTrack = message
+            && let Some(track) = self.track_list.selected_track().cloned()
+        {
+            return self.update(Message::LikesPage(Ml::PlayTrack(track)));
+        }
+
         if let Message::NavigateToSearch = message {
             return (
                 Some(Box::new(SearchPage::new(self.token_manager.clone()))),
@@ -226,11 +281,21 @@ impl Page for LikesPage {
             return (Some(Box::new(page)), task);
         }
 
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
         (None, Task::none())
     }
 
-    fn view(&self) -> iced::Element<'_, Message> {
-        let mut tracks_column = self.track_list.render_tracks(
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message> {
+        let mut tracks_column = self.track_list.render_sorted_tracks(
+            self.sort_mode,
+            now_playing,
+            hide_blocked,
             |t| Message::LikesPage(Ml::PlayTrack(t)),
             |urn| Message::LikesPage(Ml::LoadUser(urn)),
             |t| Message::LikesPage(Ml::LikeTrack(t)),
@@ -248,15 +313,42 @@ impl Page for LikesPage {
             );
         }
 
-        let mut content = column![];
+        let mut content = column![
+            row![
+                sort_control(self.sort_mode),
+                button(text("Refresh").size(14))
+                    .on_press_maybe((!self.is_loading).then_some(Message::LikesPage(Ml::Refresh))),
+                pick_list(
+                    ExportFormat::ALL,
+                    None::<ExportFormat>,
+                    Message::ExportLikes
+                )
+                .placeholder("Export")
+                .text_size(14),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(5);
         if self.track_load_failed {
             content =
                 content.push(text("Error Loading Tracks").color(Color::from_rgb(1.0, 0.0, 0.0)));
         }
 
-        if self.track_list.tracks().is_empty() && self.is_loading {
-            // Initial load: no tracks to show yet, so fill the page with a spinner.
-            return content.push(loading_state()).into();
+        if self.track_list.tracks().is_empty() {
+            // Initial load: placeholder rows until the first page arrives
+            if self.is_loading {
+                return content.push(track_list_skeleton()).into();
+            }
+            if !self.track_load_failed {
+                return content
+                    .push(empty_state(
+                        None,
+                        "No liked tracks yet".to_string(),
+                        "Tracks you like on SoundCloud show up here".to_string(),
+                    ))
+                    .into();
+            }
         }
 
         content = content.push(
@@ -1,13 +1,41 @@
 mod auth_page;
 mod feed_page;
+mod follows_page;
+mod genre_page;
 mod likes_page;
 mod playlist_page;
 mod search_page;
+mod settings_page;
+mod track_page;
 mod user_page;
 
 pub use auth_page::{AuthPage, AuthPageMessage};
 pub use feed_page::{FeedPage, FeedPageMessage};
+pub use follows_page::{FollowList, FollowsPage, FollowsPageMessage};
+pub use genre_page::{GenrePage, GenrePageMessage};
 pub use likes_page::{LikesPage, LikesPageMessage};
 pub use playlist_page::{PlaylistPage, PlaylistPageMessage};
 pub use search_page::{SearchPage, SearchPageMessage};
+pub use settings_page::SettingsPage;
+pub use track_page::{TrackPage, TrackPageMessage};
 pub use user_page::{UserPage, UserPageMessage};
+
+use crate::config::StartPage;
+use crate::soundcloud::TokenManager;
+use crate::{Message, Page};
+use iced::Task;
+
+/// Build one of the pages the app can start on
+pub fn start_page(page: StartPage, token_manager: TokenManager) -> (Box<dyn Page>, Task<Message>) {
+    match page {
+        StartPage::Likes => {
+            let (page, task) = LikesPage::new(token_manager);
+            (Box::new(page), task)
+        }
+        StartPage::Feed => {
+            let (page, task) = FeedPage::new(token_manager);
+            (Box::new(page), task)
+        }
+        StartPage::Search => (Box::new(SearchPage::new(token_manager)), Task::none()),
+    }
+}
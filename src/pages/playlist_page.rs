@@ -1,24 +1,29 @@
 use crate::Message;
 use crate::Page;
+use crate::config::TrackSort;
 use crate::managers::TrackListManager;
 use crate::models::SoundCloudPlaylist;
 use crate::models::SoundCloudTrack;
 use crate::models::SoundCloudTracks;
 use crate::pages::LikesPage;
+use crate::pages::SettingsPage;
 use crate::pages::UserPage;
 use crate::pages::{FeedPage, SearchPage};
 use crate::soundcloud::TokenManager;
 use crate::soundcloud::api_helpers;
-use crate::widgets::{loading_state, spinner};
-use iced::Color;
+use crate::utilities::{NumberFormat, placeholder_image};
+use crate::widgets::{empty_state, play_all_buttons, spinner, track_list_skeleton};
 use iced::Length;
 use iced::Task;
 use iced::widget::image::Handle;
-use iced::widget::{Scrollable, container, sensor, text};
+use iced::widget::{Scrollable, button, column, container, image, mouse_area, row, sensor, text};
+use iced::{Alignment, Color, Font};
 use tracing::debug;
 
 // Start loading the next page when the bottom sentinel is within 500px of the viewport
 const LOAD_MORE_THRESHOLD: f32 = 500.0;
+// Stable id of the track Scrollable, for keeping the keyboard selection in view
+const SCROLL_ID: &str = "playlist_scroll";
 
 #[derive(Debug, Clone)]
 pub enum PlaylistPageMessage {
@@ -28,22 +33,26 @@ pub enum PlaylistPageMessage {
     RequestImage(u64),
     PlayTrack(SoundCloudTrack),
     ImageLoaded(u64, Handle),
-    ImageLoadFailed(u64),
     LikeTrack(SoundCloudTrack),
     TrackLikedWithToken(u64, TokenManager),
     ApiErrorWithToken(String, TokenManager),
     LoadUser(String),
+    ArtworkLoaded(Handle),
+    PlayAll(bool), // Shuffled
+    AddToQueue,
 }
 
 type Mp = PlaylistPageMessage;
 
 pub struct PlaylistPage {
     token_manager: TokenManager,
-    playlist_urn: String,
+    playlist: SoundCloudPlaylist, // As listed where it was opened, for the header
+    artwork: Option<Handle>,
     track_list: TrackListManager,
     tracks_next_href: Option<String>,
     tracks_loading: bool,
     track_load_failed: bool,
+    after_all_loaded: Option<PlaylistPageMessage>, // Play all / Add to queue waiting on the remaining pages
 }
 
 impl PlaylistPage {
@@ -51,17 +60,76 @@ impl PlaylistPage {
         (
             Self {
                 token_manager,
-                playlist_urn: playlist.urn,
+                playlist,
+                artwork: None,
                 track_list: TrackListManager::new(),
                 tracks_next_href: None,
-                tracks_loading: false,
+                tracks_loading: true, // The first page is requested right away
                 track_load_failed: false,
+                after_all_loaded: None,
             },
             Task::done(Message::PlaylistPage(PlaylistPageMessage::LoadPlaylist)),
         )
     }
 }
 
+impl PlaylistPage {
+    /// Artwork, title, owner (links to their page) and track count
+    fn header(&self) -> iced::Element<'_, Message> {
+        let playlist = &self.playlist;
+        // Pages not loaded yet may hold playable tracks
+        let can_play = self.track_list.has_playable_tracks() || self.tracks_next_href.is_some();
+        let bold = Font {
+            weight: iced::font::Weight::Bold,
+            ..Font::DEFAULT
+        };
+
+        let artwork = image(self.artwork.clone().unwrap_or_else(placeholder_image))
+            .width(160)
+            .height(160);
+
+        // The loaded count is a floor for playlists the listing didn't count
+        let track_count = playlist
+            .track_count
+            .map(u64::from)
+            .unwrap_or_default()
+            .max(self.track_list.tracks().len() as u64);
+
+        row![
+            artwork,
+            column![
+                text(playlist.title.clone())
+                    .size(24)
+                    .font(bold)
+                    .shaping(text::Shaping::Auto),
+                mouse_area(
+                    text(playlist.user.username.clone())
+                        .size(18)
+                        .shaping(text::Shaping::Auto)
+                )
+                .on_press(Message::PlaylistPage(Mp::LoadUser(
+                    playlist.user.urn.clone()
+                )))
+                .interaction(iced::mouse::Interaction::Pointer),
+                text(format!("{} tracks", track_count.format_compact_number()))
+                    .style(text::secondary),
+                play_all_buttons(can_play, |shuffle| {
+                    Message::PlaylistPage(Mp::PlayAll(shuffle))
+                })
+                .push(
+                    button(text("Add to queue"))
+                        .style(button::secondary)
+                        .on_press_maybe(can_play.then_some(Message::PlaylistPage(Mp::AddToQueue)))
+                ),
+            ]
+            .spacing(8),
+        ]
+        .spacing(16)
+        .align_y(Alignment::Start)
+        .into()
+    }
+}
+
 impl Page for PlaylistPage {
     fn is_animating(&self) -> bool {
         // Keep frames flowing while the loading spinner is on screen.
@@ -72,28 +140,38 @@ impl Page for PlaylistPage {
         if let Message::PlaylistPage(msg) = message {
             match msg {
                 PlaylistPageMessage::LoadPlaylist => {
-                    // Fetch the playlist's tracks (first page) from the API.
+                    // Fetch the playlist's tracks (first page) from the API,
+                    // and the header artwork alongside.
                     self.tracks_loading = true;
                     let token_manager = self.token_manager.clone();
-                    let playlist_urn = self.playlist_urn.clone();
-                    return (
-                        None,
-                        Task::perform(
-                            api_helpers::get_playlist_tracks_with_refresh(
-                                token_manager,
-                                playlist_urn,
-                                None,
-                            ),
-                            |result| match result {
-                                Ok((tracks, token_manager)) => Message::PlaylistPage(
-                                    Mp::TracksLoadedWithToken(tracks, token_manager),
-                                ),
-                                Err((error, token_manager)) => Message::PlaylistPage(
-                                    Mp::ApiErrorWithToken(error.to_string(), token_manager),
-                                ),
-                            },
+                    let playlist_urn = self.playlist.urn.clone();
+                    let tracks_task = Task::perform(
+                        api_helpers::get_playlist_tracks_with_refresh(
+                            token_manager,
+                            playlist_urn,
+                            None,
                         ),
+                        |result| match result {
+                            Ok((tracks, token_manager)) => Message::PlaylistPage(
+                                Mp::TracksLoadedWithToken(tracks, token_manager),
+                            ),
+                            Err((error, token_manager)) => Message::PlaylistPage(
+                                Mp::ApiErrorWithToken(error.to_string(), token_manager),
+                            ),
+                        },
                     );
+
+                    if self.artwork.is_some() {
+                        return (None, tracks_task);
+                    }
+                    let artwork_url = self.playlist.artwork_url.clone();
+                    let artwork_task = Task::perform(
+                        async move {
+                            crate::utilities::download_image_with_fallback(&artwork_url).await
+                        },
+                        |handle| Message::PlaylistPage(Mp::ArtworkLoaded(handle)),
+                    );
+                    return (None, Task::batch([tracks_task, artwork_task]));
                 }
                 PlaylistPageMessage::LoadMoreTracks => {
                     if self.tracks_loading || self.tracks_next_href.is_none() {
@@ -101,7 +179,7 @@ impl Page for PlaylistPage {
                     }
                     self.tracks_loading = true;
                     let token_manager = self.token_manager.clone();
-                    let playlist_urn = self.playlist_urn.clone();
+                    let playlist_urn = self.playlist.urn.clone();
                     let next_href = self.tracks_next_href.clone();
                     return (
                         None,
@@ -132,25 +210,58 @@ impl Page for PlaylistPage {
                     } else {
                         self.track_list.append_tracks(tracks.collection);
                     }
+                    if self.after_all_loaded.is_some() {
+                        if self.tracks_next_href.is_some() {
+                            return self.update(Message::PlaylistPage(Mp::LoadMoreTracks));
+                        }
+                        if let Some(waiting) = self.after_all_loaded.take() {
+                            return self.update(Message::PlaylistPage(waiting));
+                        }
+                    }
                     return (None, Task::none());
                 }
                 PlaylistPageMessage::RequestImage(track_id) => {
                     return (
                         None,
-                        self.track_list.load_image_task(
-                            track_id,
-                            |id, handle| Message::PlaylistPage(Mp::ImageLoaded(id, handle)),
-                            |id| Message::PlaylistPage(Mp::ImageLoadFailed(id)),
-                        ),
+                        self.track_list.load_image_task(track_id, |id, handle| {
+                            Message::PlaylistPage(Mp::ImageLoaded(id, handle))
+                        }),
+                    );
+                }
+                PlaylistPageMessage::PlayAll(shuffle) => {
+                    // Play the whole playlist, not just the pages scrolled into view
+                    if self.tracks_next_href.is_some() {
+                        self.after_all_loaded = Some(Mp::PlayAll(shuffle));
+                        return self.update(Message::PlaylistPage(Mp::LoadMoreTracks));
+                    }
+                    let Some((first, tracks)) = self.track_list.play_all(shuffle) else {
+                        return (None, Task::none());
+                    };
+                    return (
+                        None,
+                        Task::done(Message::StartQueue(
+                            first,
+                            tracks,
+                            self.token_manager.clone(),
+                        )),
+                    );
+                }
+                PlaylistPageMessage::AddToQueue => {
+                    if self.tracks_next_href.is_some() {
+                        self.after_all_loaded = Some(Mp::AddToQueue);
+                        return self.update(Message::PlaylistPage(Mp::LoadMoreTracks));
+                    }
+                    return (
+                        None,
+                        Task::done(Message::EnqueueTracks(self.track_list.tracks().clone())),
                     );
                 }
                 PlaylistPageMessage::PlayTrack(track) => {
-                    self.track_list.set_current_track_id(track.id);
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.track_list.tracks().clone(),
+                            self.track_list.shared_tracks(),
                             self.token_manager.clone(),
                         )),
                     );
@@ -181,6 +292,7 @@ impl Page for PlaylistPage {
                     self.token_manager = token_manager;
                     self.track_load_failed = true;
                     self.tracks_loading = false;
+                    self.after_all_loaded = None;
                     debug!("API Error: {}", error_msg);
                     return (None, Task::none());
                 }
@@ -188,8 +300,8 @@ impl Page for PlaylistPage {
                     self.track_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                PlaylistPageMessage::ImageLoadFailed(track_id) => {
-                    println!("Failed to load image for track {}", track_id);
+                PlaylistPageMessage::ArtworkLoaded(handle) => {
+                    self.artwork = Some(handle);
                     return (None, Task::none());
                 }
                 PlaylistPageMessage::LoadUser(user_urn) => {
@@ -200,6 +312,20 @@ impl Page for PlaylistPage {
             }
         }
 
+        if let Message::MoveSelection(step, hide_blocked) = message {
+            return (
+                None,
+                self.track_list
+                    .move_selection(TrackSort::Unsorted, hide_blocked, step, SCROLL_ID),
+            );
+        }
+
+        if let Message::PlaySelectedTrack = message
+            && let Some(track) = self.track_list.selected_track().cloned()
+        {
+            return self.update(Message::PlaylistPage(Mp::PlayTrack(track)));
+        }
+
         if let Message::NavigateToFeed = message {
             let (page, task) = FeedPage::new(self.token_manager.clone());
             return (Some(Box::new(page)), task);
@@ -217,13 +343,20 @@ impl Page for PlaylistPage {
             );
         }
 
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
         (None, Task::none())
     }
 
-    fn view(&self) -> iced::Element<'_, Message> {
-        use iced::widget::column;
-
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message> {
         let mut tracks_column = self.track_list.render_tracks(
+            now_playing,
+            hide_blocked,
             |t| Message::PlaylistPage(PlaylistPageMessage::PlayTrack(t)),
             |urn| Message::PlaylistPage(PlaylistPageMessage::LoadUser(urn)),
             |t| Message::PlaylistPage(PlaylistPageMessage::LikeTrack(t)),
@@ -239,20 +372,32 @@ impl Page for PlaylistPage {
             );
         }
 
-        let mut content = column![];
+        let mut content = column![self.header()].spacing(10);
         if self.track_load_failed {
             content =
                 content.push(text("Error Loading Tracks").color(Color::from_rgb(1.0, 0.0, 0.0)));
         }
 
-        if self.track_list.tracks().is_empty() && self.tracks_loading {
-            // Initial load: no tracks to show yet, so fill the page with a spinner.
-            return content.push(loading_state()).into();
+        if self.track_list.tracks().is_empty() {
+            // Initial load: placeholder rows until the first page arrives
+            if self.tracks_loading {
+                return content.push(track_list_skeleton()).into();
+            }
+            if !self.track_load_failed {
+                return content
+                    .push(empty_state(
+                        None,
+                        "This playlist is empty".to_string(),
+                        String::new(),
+                    ))
+                    .into();
+            }
         }
 
         content
             .push(
                 Scrollable::new(tracks_column)
+                    .id(SCROLL_ID)
                     .style(crate::widgets::scrollbar_style)
                     .height(Length::FillPortion(1))
                     .width(Length::FillPortion(1)),
@@ -1,16 +1,19 @@
-use crate::managers::TrackListManager;
+use crate::config::TrackSort;
+use crate::managers::{SearchHistory, TrackListManager};
 use crate::models::{
     SearchResults, SoundCloudPlaylist, SoundCloudPlaylists, SoundCloudTrack, SoundCloudTracks,
-    SoundCloudUser,
+    SoundCloudUser, SoundCloudUsers, TrackFilters,
 };
-use crate::pages::{LikesPage, PlaylistPage, UserPage};
+use crate::pages::{GenrePage, LikesPage, PlaylistPage, SettingsPage, UserPage};
 use crate::soundcloud::TokenManager;
 use crate::soundcloud::api_helpers;
-use crate::widgets::{get_playlist_widget, get_user_widget, loading_state, spinner};
+use crate::widgets::{empty_state, get_playlist_widget, get_user_widget, loading_state, spinner};
 use crate::{Message, Page};
 use iced::widget::image::Handle;
-use iced::widget::{Scrollable, column, container, grid, row, sensor, text_input};
-use iced::{Length, Task};
+use iced::widget::{
+    Scrollable, Space, button, column, container, grid, row, sensor, text, text_input,
+};
+use iced::{Alignment, Length, Task};
 use std::collections::HashMap;
 use tracing::debug;
 
@@ -18,43 +21,73 @@ use tracing::debug;
 pub enum SearchPageMessage {
     SearchPressed(String),
     Search(String),
-    SearchCompletedWithToken(SearchResults, TokenManager),
+    // Results carry the search generation they were requested under
+    SearchCompletedWithToken(u64, SearchResults, TokenManager),
     LoadMoreTracks,
     LoadMorePlaylists,
-    MoreTracksLoadedWithToken(SoundCloudTracks, TokenManager),
-    MorePlaylistsLoadedWithToken(SoundCloudPlaylists, TokenManager),
-    ApiErrorWithToken(String, TokenManager),
+    LoadMoreUsers,
+    MoreTracksLoadedWithToken(u64, SoundCloudTracks, TokenManager),
+    MorePlaylistsLoadedWithToken(u64, SoundCloudPlaylists, TokenManager),
+    MoreUsersLoadedWithToken(u64, SoundCloudUsers, TokenManager),
+    ApiErrorWithToken(u64, String, TokenManager), // Search generation, like the results
+    LikeFailedWithToken(String, TokenManager),
     UserImageLoaded(String, Handle),
-    UserImageLoadFailed(String),
+    PlaylistImageLoaded(String, Handle),
     RequestTrackImage(u64),
     TrackImageLoaded(u64, Handle),
-    TrackImageLoadFailed(u64),
     PlayTrack(SoundCloudTrack),
     LikeTrack(SoundCloudTrack),
     TrackLikedWithToken(u64, TokenManager),
     LoadUser(String),
     LoadPlaylist(SoundCloudPlaylist),
+    HistorySelected(String),
+    ClearHistory,
+    BrowseGenres,
+    ToggleFilters,
+    FilterChanged(FilterField, String),
+}
+
+/// The advanced search inputs
+#[derive(Debug, Clone, Copy)]
+pub enum FilterField {
+    MinLength,
+    MaxLength,
+    MinBpm,
+    MaxBpm,
 }
 
 type Ms = SearchPageMessage;
 
 // Start loading the next page when the bottom sentinel is within 500px of the viewport
 const LOAD_MORE_THRESHOLD: f32 = 500.0;
+// Stable id of the track Scrollable, for keeping the keyboard selection in view
+const SCROLL_ID: &str = "search_tracks_scroll";
 
 pub struct SearchPage {
     token_manager: TokenManager,
     search_query: String,
     // A full search (users + tracks + playlists) is in flight.
     searching: bool,
+    search_generation: u64, // Bumped per search so late results for an earlier query are dropped
     user_load_failed: bool,
     user_images: HashMap<String, Handle>,
     users: Vec<SoundCloudUser>,
+    users_next_href: Option<String>,
+    users_loading: bool,
     track_list: TrackListManager,
     tracks_next_href: Option<String>,
     tracks_loading: bool,
     playlists: Vec<SoundCloudPlaylist>,
+    playlist_images: HashMap<String, Handle>, // Keyed by playlist urn
     playlists_next_href: Option<String>,
     playlists_loading: bool,
+    results_query: Option<String>, // Query the shown results are for; None before the first search
+    history: SearchHistory,
+    show_history: bool, // Suggest past queries under the search bar while typing
+    show_filters: bool,
+    filter_inputs: [String; 4], // Raw text of each FilterField, in declaration order
+    filters: TrackFilters,      // Applied to the shown results and their later pages
+    filter_error: Option<String>,
 }
 
 impl SearchPage {
@@ -63,16 +96,121 @@ impl SearchPage {
             token_manager,
             search_query: String::new(),
             searching: false,
+            search_generation: 0,
             user_load_failed: false,
             user_images: HashMap::new(),
             users: Vec::new(),
+            users_next_href: None,
+            users_loading: false,
             track_list: TrackListManager::new(),
             tracks_next_href: None,
             tracks_loading: false,
             playlists: Vec::new(),
+            playlist_images: HashMap::new(),
             playlists_next_href: None,
             playlists_loading: false,
+            results_query: None,
+            history: SearchHistory::load(),
+            show_history: true,
+            show_filters: false,
+            filter_inputs: Default::default(),
+            filters: TrackFilters::default(),
+            filter_error: None,
+        }
+    }
+
+    /// Duration and BPM inputs, with the reason the last search was refused
+    fn filters_panel(&self) -> iced::Element<'_, Message> {
+        if !self.show_filters {
+            return Space::new().into();
+        }
+        let submit = Message::SearchPage(Ms::Search(self.search_query.clone()));
+        let input = |placeholder, field: FilterField| {
+            text_input(placeholder, &self.filter_inputs[field as usize])
+                .on_input(move |s| Message::SearchPage(Ms::FilterChanged(field, s)))
+                .on_submit(submit.clone())
+                .width(120)
+        };
+        let mut panel = column![
+            row![
+                text("Length"),
+                input("Min (m:ss)", FilterField::MinLength),
+                input("Max (m:ss)", FilterField::MaxLength),
+                text("BPM"),
+                input("From", FilterField::MinBpm),
+                input("To", FilterField::MaxBpm),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(4);
+        if let Some(error) = &self.filter_error {
+            panel = panel.push(text(error.as_str()).size(12).style(text::danger));
+        }
+        panel.into()
+    }
+
+    /// Downloads the avatars for a batch of users.
+    fn user_images_task(users: &[SoundCloudUser]) -> Task<Message> {
+        crate::utilities::load_images_batched(
+            users
+                .iter()
+                .map(|user| (user.urn.clone(), user.avatar_url.clone()))
+                .collect(),
+            |user_urn, handle| Message::SearchPage(Ms::UserImageLoaded(user_urn, handle)),
+        )
+    }
+
+    /// Downloads the artwork for a batch of playlists.
+    fn playlist_images_task(playlists: &[SoundCloudPlaylist]) -> Task<Message> {
+        crate::utilities::load_images_batched(
+            playlists
+                .iter()
+                .map(|playlist| (playlist.urn.clone(), playlist.artwork_url.clone()))
+                .collect(),
+            |playlist_urn, handle| {
+                Message::SearchPage(Ms::PlaylistImageLoaded(playlist_urn, handle))
+            },
+        )
+    }
+
+    /// Past queries matching what's typed so far, with a clear action.
+    /// Empty while hidden or when nothing matches.
+    fn history_dropdown(&self) -> iced::Element<'_, Message> {
+        let typed = self.search_query.trim().to_lowercase();
+        let matches: Vec<&String> = self
+            .history
+            .queries()
+            .iter()
+            .filter(|q| q.to_lowercase().contains(&typed) && q.to_lowercase() != typed)
+            .collect();
+        if !self.show_history || matches.is_empty() {
+            return Space::new().into();
         }
+
+        let entries = matches.into_iter().fold(column![], |col, query| {
+            col.push(
+                button(text(query.as_str()).shaping(text::Shaping::Auto))
+                    .style(button::text)
+                    .width(Length::Fill)
+                    .on_press(Message::SearchPage(Ms::HistorySelected(query.clone()))),
+            )
+        });
+        container(column![
+            row![
+                text("Recent searches").size(12).style(text::secondary),
+                Space::new().width(Length::Fill),
+                button(text("Clear history").size(12))
+                    .style(button::text)
+                    .on_press(Message::SearchPage(Ms::ClearHistory)),
+            ]
+            .align_y(Alignment::Center),
+            entries,
+        ])
+        .padding(6)
+        .width(Length::Fill)
+        .style(container::rounded_box)
+        .into()
     }
 }
 
@@ -90,34 +228,77 @@ impl Page for SearchPage {
             match msg {
                 SearchPageMessage::SearchPressed(query) => {
                     self.search_query = query.clone();
+                    self.show_history = true;
+                    return (None, Task::none());
+                }
+                SearchPageMessage::HistorySelected(query) => {
+                    return (None, Task::done(Message::SearchPage(Ms::Search(query))));
+                }
+                SearchPageMessage::ClearHistory => {
+                    self.history.clear();
                     return (None, Task::none());
                 }
                 SearchPageMessage::Search(query) => {
+                    let [min_length, max_length, min_bpm, max_bpm] = &self.filter_inputs;
+                    match TrackFilters::parse(min_length, max_length, min_bpm, max_bpm) {
+                        Ok(filters) => {
+                            self.filters = filters;
+                            self.filter_error = None;
+                        }
+                        Err(error) => {
+                            self.show_filters = true;
+                            self.filter_error = Some(error);
+                            return (None, Task::none());
+                        }
+                    }
                     self.search_query = query.clone();
+                    self.history.record(&query);
+                    self.show_history = false;
                     self.searching = true;
+                    self.search_generation += 1;
+                    let generation = self.search_generation;
                     let token_manager = self.token_manager.clone();
                     let search_query = self.search_query.clone();
 
                     return (
                         None,
                         Task::perform(
-                            api_helpers::search_with_refresh(token_manager, search_query),
-                            |result| match result {
-                                Ok((results, token_manager)) => Message::SearchPage(
-                                    Ms::SearchCompletedWithToken(results, token_manager),
-                                ),
-                                Err((error, token_manager)) => Message::SearchPage(
-                                    Ms::ApiErrorWithToken(error.to_string(), token_manager),
-                                ),
+                            api_helpers::search_with_refresh(
+                                token_manager,
+                                search_query,
+                                self.filters,
+                            ),
+                            move |result| match result {
+                                Ok((results, token_manager)) => {
+                                    Message::SearchPage(Ms::SearchCompletedWithToken(
+                                        generation,
+                                        results,
+                                        token_manager,
+                                    ))
+                                }
+                                Err((error, token_manager)) => {
+                                    Message::SearchPage(Ms::ApiErrorWithToken(
+                                        generation,
+                                        error.to_string(),
+                                        token_manager,
+                                    ))
+                                }
                             },
                         ),
                     );
                 }
-                SearchPageMessage::SearchCompletedWithToken(results, token_manager) => {
+                SearchPageMessage::SearchCompletedWithToken(generation, results, token_manager) => {
                     self.token_manager = token_manager;
+                    // A newer search is in flight
+                    if generation != self.search_generation {
+                        return (None, Task::none());
+                    }
                     self.searching = false;
                     self.user_load_failed = false;
+                    self.results_query = Some(self.search_query.clone());
                     self.users = results.users.clone();
+                    self.users_next_href = results.users_next_href.clone();
+                    self.users_loading = false;
                     self.playlists = results.playlists.clone();
                     self.playlists_next_href = results.playlists_next_href.clone();
                     self.tracks_next_href = results.tracks_next_href.clone();
@@ -125,30 +306,15 @@ impl Page for SearchPage {
                     self.playlists_loading = false;
                     self.track_list.set_tracks(results.tracks);
 
-                    // Create tasks to load images for all users
-                    let image_tasks: Vec<Task<Message>> = self
-                        .users
-                        .iter()
-                        .map(|user| {
-                            let user_urn = user.urn.clone();
-                            let artwork_url = user.avatar_url.clone();
-                            Task::perform(
-                                async move { crate::utilities::download_image(&artwork_url).await },
-                                move |result| match result {
-                                    Ok(handle) => Message::SearchPage(Ms::UserImageLoaded(
-                                        user_urn.clone(),
-                                        handle,
-                                    )),
-                                    Err(_) => Message::SearchPage(Ms::UserImageLoadFailed(
-                                        user_urn.clone(),
-                                    )),
-                                },
-                            )
-                        })
-                        .collect();
-
-                    // Track artwork now loads lazily per row via RequestTrackImage.
-                    return (None, Task::batch(image_tasks));
+                    // Load images for all users and playlists. Track artwork
+                    // loads lazily per row via RequestTrackImage.
+                    return (
+                        None,
+                        Task::batch([
+                            Self::user_images_task(&self.users),
+                            Self::playlist_images_task(&self.playlists),
+                        ]),
+                    );
                 }
                 SearchPageMessage::LoadMoreTracks => {
                     if self.tracks_loading || self.tracks_next_href.is_none() {
@@ -158,27 +324,41 @@ impl Page for SearchPage {
                     let token_manager = self.token_manager.clone();
                     let query = self.search_query.clone();
                     let next_href = self.tracks_next_href.clone();
+                    let generation = self.search_generation;
                     return (
                         None,
                         Task::perform(
                             api_helpers::search_tracks_with_refresh(
                                 token_manager,
                                 query,
+                                self.filters,
                                 next_href,
                             ),
-                            |result| match result {
-                                Ok((tracks, token_manager)) => Message::SearchPage(
-                                    Ms::MoreTracksLoadedWithToken(tracks, token_manager),
-                                ),
-                                Err((error, token_manager)) => Message::SearchPage(
-                                    Ms::ApiErrorWithToken(error.to_string(), token_manager),
-                                ),
+                            move |result| match result {
+                                Ok((tracks, token_manager)) => {
+                                    Message::SearchPage(Ms::MoreTracksLoadedWithToken(
+                                        generation,
+                                        tracks,
+                                        token_manager,
+                                    ))
+                                }
+                                Err((error, token_manager)) => {
+                                    Message::SearchPage(Ms::ApiErrorWithToken(
+                                        generation,
+                                        error.to_string(),
+                                        token_manager,
+                                    ))
+                                }
                             },
                         ),
                     );
                 }
-                SearchPageMessage::MoreTracksLoadedWithToken(tracks, token_manager) => {
+                SearchPageMessage::MoreTracksLoadedWithToken(generation, tracks, token_manager) => {
                     self.token_manager = token_manager;
+                    // A page of an earlier query's results
+                    if generation != self.search_generation {
+                        return (None, Task::none());
+                    }
                     self.tracks_loading = false;
                     self.tracks_next_href = tracks.next_href.clone();
                     self.track_list.append_tracks(tracks.collection);
@@ -192,6 +372,7 @@ impl Page for SearchPage {
                     let token_manager = self.token_manager.clone();
                     let query = self.search_query.clone();
                     let next_href = self.playlists_next_href.clone();
+                    let generation = self.search_generation;
                     return (
                         None,
                         Task::perform(
@@ -200,30 +381,93 @@ impl Page for SearchPage {
                                 query,
                                 next_href,
                             ),
-                            |result| match result {
-                                Ok((playlists, token_manager)) => Message::SearchPage(
-                                    Ms::MorePlaylistsLoadedWithToken(playlists, token_manager),
-                                ),
-                                Err((error, token_manager)) => Message::SearchPage(
-                                    Ms::ApiErrorWithToken(error.to_string(), token_manager),
-                                ),
+                            move |result| match result {
+                                Ok((playlists, token_manager)) => {
+                                    Message::SearchPage(Ms::MorePlaylistsLoadedWithToken(
+                                        generation,
+                                        playlists,
+                                        token_manager,
+                                    ))
+                                }
+                                Err((error, token_manager)) => {
+                                    Message::SearchPage(Ms::ApiErrorWithToken(
+                                        generation,
+                                        error.to_string(),
+                                        token_manager,
+                                    ))
+                                }
                             },
                         ),
                     );
                 }
-                SearchPageMessage::MorePlaylistsLoadedWithToken(playlists, token_manager) => {
+                SearchPageMessage::MorePlaylistsLoadedWithToken(
+                    generation,
+                    playlists,
+                    token_manager,
+                ) => {
                     self.token_manager = token_manager;
+                    // A page of an earlier query's results
+                    if generation != self.search_generation {
+                        return (None, Task::none());
+                    }
                     self.playlists_loading = false;
                     self.playlists_next_href = playlists.next_href.clone();
+                    let image_task = Self::playlist_images_task(&playlists.collection);
                     self.playlists.extend(playlists.collection);
-                    return (None, Task::none());
+                    return (None, image_task);
+                }
+                SearchPageMessage::LoadMoreUsers => {
+                    if self.users_loading || self.users_next_href.is_none() {
+                        return (None, Task::none());
+                    }
+                    self.users_loading = true;
+                    let token_manager = self.token_manager.clone();
+                    let query = self.search_query.clone();
+                    let next_href = self.users_next_href.clone();
+                    let generation = self.search_generation;
+                    return (
+                        None,
+                        Task::perform(
+                            api_helpers::search_users_with_refresh(token_manager, query, next_href),
+                            move |result| match result {
+                                Ok((users, token_manager)) => Message::SearchPage(
+                                    Ms::MoreUsersLoadedWithToken(generation, users, token_manager),
+                                ),
+                                Err((error, token_manager)) => {
+                                    Message::SearchPage(Ms::ApiErrorWithToken(
+                                        generation,
+                                        error.to_string(),
+                                        token_manager,
+                                    ))
+                                }
+                            },
+                        ),
+                    );
+                }
+                SearchPageMessage::MoreUsersLoadedWithToken(generation, users, token_manager) => {
+                    self.token_manager = token_manager;
+                    // A page of an earlier query's results
+                    if generation != self.search_generation {
+                        return (None, Task::none());
+                    }
+                    self.users_loading = false;
+                    self.users_next_href = users.next_href.clone();
+                    let image_task = Self::user_images_task(&users.collection);
+                    self.users.extend(users.collection);
+                    return (None, image_task);
                 }
-                SearchPageMessage::ApiErrorWithToken(error_msg, token_manager) => {
+                SearchPageMessage::ApiErrorWithToken(generation, error_msg, token_manager) => {
                     self.token_manager = token_manager;
+                    // A failure from an earlier query, whose flags were already reset
+                    if generation != self.search_generation {
+                        debug!("Ignoring stale search error: {}", error_msg);
+                        return (None, Task::none());
+                    }
                     self.searching = false;
                     self.user_load_failed = true;
                     self.tracks_loading = false;
                     self.playlists_loading = false;
+                    self.users_loading = false;
                     debug!("API Error: {}", error_msg);
                     return (None, Task::none());
                 }
@@ -231,35 +475,28 @@ impl Page for SearchPage {
                     self.user_images.insert(user_urn, handle);
                     return (None, Task::none());
                 }
-                SearchPageMessage::UserImageLoadFailed(user_urn) => {
-                    debug!("Failed to load image for user {}", user_urn);
+                SearchPageMessage::PlaylistImageLoaded(playlist_urn, handle) => {
+                    self.playlist_images.insert(playlist_urn, handle);
                     return (None, Task::none());
                 }
                 SearchPageMessage::RequestTrackImage(track_id) => {
                     return (
                         None,
-                        self.track_list.load_image_task(
-                            track_id,
-                            |id, handle| Message::SearchPage(Ms::TrackImageLoaded(id, handle)),
-                            |id| Message::SearchPage(Ms::TrackImageLoadFailed(id)),
-                        ),
+                        self.track_list.load_image_task(track_id, |id, handle| {
+                            Message::SearchPage(Ms::TrackImageLoaded(id, handle))
+                        }),
                     );
                 }
                 SearchPageMessage::TrackImageLoaded(track_id, handle) => {
                     self.track_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                SearchPageMessage::TrackImageLoadFailed(track_id) => {
-                    println!("Failed to load image for track {}", track_id);
-                    return (None, Task::none());
-                }
                 SearchPageMessage::PlayTrack(track) => {
-                    self.track_list.set_current_track_id(track.id);
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.track_list.tracks().clone(),
+                            self.track_list.shared_tracks(),
                             self.token_manager.clone(),
                         )),
                     );
@@ -275,12 +512,17 @@ impl Page for SearchPage {
                                     Ms::TrackLikedWithToken(track_id, token_manager),
                                 ),
                                 Err((error, token_manager)) => Message::SearchPage(
-                                    Ms::ApiErrorWithToken(error.to_string(), token_manager),
+                                    Ms::LikeFailedWithToken(error.to_string(), token_manager),
                                 ),
                             },
                         ),
                     );
                 }
+                SearchPageMessage::LikeFailedWithToken(error_msg, token_manager) => {
+                    self.token_manager = token_manager;
+                    debug!("Failed to like track: {}", error_msg);
+                    return (None, Task::none());
+                }
                 SearchPageMessage::TrackLikedWithToken(track_id, token_manager) => {
                     self.token_manager = token_manager;
                     debug!("Track liked: {}", track_id);
@@ -296,18 +538,53 @@ impl Page for SearchPage {
                         PlaylistPage::new(self.token_manager.clone(), playlist);
                     return (Some(Box::new(playlist_page)), task);
                 }
+                SearchPageMessage::ToggleFilters => {
+                    self.show_filters = !self.show_filters;
+                    return (None, Task::none());
+                }
+                SearchPageMessage::FilterChanged(field, value) => {
+                    self.filter_inputs[field as usize] = value;
+                    return (None, Task::none());
+                }
+                SearchPageMessage::BrowseGenres => {
+                    return (
+                        Some(Box::new(GenrePage::new(self.token_manager.clone()))),
+                        Task::none(),
+                    );
+                }
             }
         }
 
+        if let Message::MoveSelection(step, hide_blocked) = message {
+            return (
+                None,
+                self.track_list
+                    .move_selection(TrackSort::Unsorted, hide_blocked, step, SCROLL_ID),
+            );
+        }
+
+        if let Message::PlaySelectedTrack = message
+            && let Some(track) = self.track_list.selected_track().cloned()
+        {
+            return self.update(Message::SearchPage(Ms::PlayTrack(track)));
+        }
+
         if let Message::NavigateToLikes = message {
             let (page, task) = LikesPage::new(self.token_manager.clone());
             return (Some(Box::new(page)), task);
         }
 
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
         (None, Task::none())
     }
 
-    fn view(&self) -> iced::Element<'_, Message> {
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message> {
         let mut indices: Vec<usize> = (0..self.users.len()).collect();
         indices.sort_by(|&a, &b| {
             let count_a = self.users[a].followers_count.unwrap_or(0);
@@ -327,8 +604,20 @@ impl Page for SearchPage {
             .fluid(300)
             .spacing(10)
             .height(Length::Shrink);
+        let mut users_content = column![users_grid].spacing(8);
+        if self.users_next_href.is_some() {
+            // Users sit above the scrolled lists, so they page in on request
+            let more_users = if self.users_loading {
+                button(text("Loading…"))
+            } else {
+                button(text("More users")).on_press(Message::SearchPage(Ms::LoadMoreUsers))
+            };
+            users_content = users_content.push(more_users.style(button::text));
+        }
 
         let mut tracks_column = self.track_list.render_tracks(
+            now_playing,
+            hide_blocked,
             |t| Message::SearchPage(SearchPageMessage::PlayTrack(t)),
             |urn| Message::SearchPage(SearchPageMessage::LoadUser(urn)),
             |t| Message::SearchPage(SearchPageMessage::LikeTrack(t)),
@@ -345,7 +634,7 @@ impl Page for SearchPage {
         }
 
         let playlist_cells = self.playlists.iter().map(|playlist| {
-            let image_handle = self.user_images.get(&playlist.user.urn).cloned();
+            let image_handle = self.playlist_images.get(&playlist.urn).cloned();
             iced::Element::from(get_playlist_widget(playlist, image_handle, |urn| {
                 Message::SearchPage(SearchPageMessage::LoadPlaylist(urn))
             }))
@@ -365,23 +654,56 @@ impl Page for SearchPage {
             );
         }
 
-        let search_bar = row![
+        let search_input = row![
             text_input("Search", self.search_query.as_str())
                 .on_submit(Message::SearchPage(Ms::Search(self.search_query.clone())))
                 .on_input(|s| Message::SearchPage(Ms::SearchPressed(s))),
+            button(text(if self.filters.is_empty() {
+                "Filters"
+            } else {
+                "Filters (on)"
+            }))
+            .style(if self.show_filters {
+                button::primary
+            } else {
+                button::secondary
+            })
+            .on_press(Message::SearchPage(Ms::ToggleFilters)),
+            button(text("Browse genres"))
+                .style(button::secondary)
+                .on_press(Message::SearchPage(Ms::BrowseGenres)),
         ]
         .spacing(10);
+        let search_bar =
+            column![search_input, self.filters_panel(), self.history_dropdown()].spacing(4);
 
         if self.searching {
             // A search is in flight: replace the results area with a spinner.
             return column![search_bar, loading_state()].into();
         }
 
+        if let Some(query) = &self.results_query
+            && self.users.is_empty()
+            && self.track_list.tracks().is_empty()
+            && self.playlists.is_empty()
+        {
+            return column![
+                search_bar,
+                empty_state(
+                    None,
+                    "No results".to_string(),
+                    format!("Nothing on SoundCloud matches \"{}\"", query),
+                ),
+            ]
+            .into();
+        }
+
         column![
             search_bar,
-            row![users_grid].spacing(10),
+            row![users_content].spacing(10),
             row![
                 Scrollable::new(tracks_column)
+                    .id(SCROLL_ID)
                     .style(crate::widgets::scrollbar_style)
                     .height(Length::FillPortion(1))
                     .width(Length::FillPortion(1)),
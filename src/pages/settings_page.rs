@@ -0,0 +1,440 @@
+use crate::config::{self, AppSettings, SeekbarType, StartPage, VolumeStep};
+use crate::constants;
+use crate::managers::audio::list_output_devices;
+use crate::managers::equalizer::{EQ_FREQUENCIES, EQ_MAX_GAIN, EqPreset};
+use crate::pages::{FeedPage, LikesPage, SearchPage};
+use crate::soundcloud::TokenManager;
+use crate::utilities::DurationFormat;
+use crate::widgets::section;
+use crate::{Message, Page};
+use iced::widget::{
+    Scrollable, button, column, pick_list, radio, row, slider, text, toggler, vertical_slider,
+};
+use iced::{Alignment, Element, Length, Task};
+
+/// Picker entry standing in for "no saved device"
+const DEFAULT_DEVICE_LABEL: &str = "System default";
+
+/// Feed auto-refresh picker entry, in minutes; None is off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AutorefreshChoice(Option<u32>);
+
+impl AutorefreshChoice {
+    const ALL: [AutorefreshChoice; 5] = [
+        AutorefreshChoice(None),
+        AutorefreshChoice(Some(5)),
+        AutorefreshChoice(Some(15)),
+        AutorefreshChoice(Some(30)),
+        AutorefreshChoice(Some(60)),
+    ];
+}
+
+impl std::fmt::Display for AutorefreshChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            None => write!(f, "Off"),
+            Some(minutes) => write!(f, "Every {} min", minutes),
+        }
+    }
+}
+
+/// App preferences. Changes are sent as app-level messages that MyApp applies
+/// and persists; the page mirrors them from the same messages to stay in sync.
+pub struct SettingsPage {
+    token_manager: TokenManager,
+    settings: AppSettings,
+    output_devices: Vec<String>, // Picker options, the system default first
+}
+
+impl SettingsPage {
+    pub fn new(token_manager: TokenManager) -> Self {
+        // MyApp saves on every change, so the file is current
+        let settings = config::load_settings();
+
+        let mut output_devices = vec![DEFAULT_DEVICE_LABEL.to_string()];
+        output_devices.extend(list_output_devices());
+        // Keep a saved device that's currently unplugged selectable
+        if let Some(saved) = &settings.output_device
+            && !output_devices.contains(saved)
+        {
+            output_devices.push(saved.clone());
+        }
+
+        Self {
+            token_manager,
+            settings,
+            output_devices,
+        }
+    }
+
+    fn startup_section(&self) -> Element<'_, Message> {
+        section(
+            "Startup",
+            None,
+            column![
+                row![
+                    text("Start on"),
+                    pick_list(
+                        StartPage::ALL,
+                        Some(self.settings.default_page),
+                        Message::SetDefaultPage,
+                    ),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                toggler(self.settings.remember_last_page)
+                    .label("Start on the last page visited instead")
+                    .on_toggle(|_| Message::ToggleRememberLastPage),
+            ]
+            .spacing(8),
+        )
+        .height(Length::Shrink)
+        .into()
+    }
+
+    fn appearance_section(&self) -> Element<'_, Message> {
+        let scale = self.settings.ui_scale();
+        section(
+            "Appearance",
+            None,
+            column![
+                row![
+                    text(format!("Zoom: {:.0}%", scale * 100.0)),
+                    button(text("-").center().width(16)).on_press_maybe(
+                        (scale > *config::UI_SCALE_RANGE.start()).then_some(Message::ZoomOut)
+                    ),
+                    button(text("+").center().width(16)).on_press_maybe(
+                        (scale < *config::UI_SCALE_RANGE.end()).then_some(Message::ZoomIn)
+                    ),
+                    button(text("Reset"))
+                        .style(button::secondary)
+                        .on_press_maybe((scale != 1.0).then_some(Message::ResetZoom)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                text("Ctrl + and Ctrl - zoom from anywhere, Ctrl 0 resets")
+                    .size(13)
+                    .style(text::secondary),
+                toggler(self.settings.theme == config::AppTheme::Light)
+                    .label("Light theme")
+                    .on_toggle(|_| Message::ToggleTheme),
+            ]
+            .spacing(8),
+        )
+        .height(Length::Shrink)
+        .into()
+    }
+
+    fn playback_section(&self) -> Element<'_, Message> {
+        section(
+            "Playback",
+            None,
+            column![
+                toggler(self.settings.autoplay)
+                    .label("Autoplay related tracks when the queue ends (repeat off)")
+                    .on_toggle(|_| Message::ToggleAutoplay),
+                toggler(self.settings.wrap_queue)
+                    .label("Wrap next/previous around the ends of the queue")
+                    .on_toggle(|_| Message::ToggleWrapQueue),
+                toggler(self.settings.normalize_volume)
+                    .label("Normalize volume across tracks")
+                    .on_toggle(|_| Message::ToggleNormalizeVolume),
+                row![
+                    text("Output device"),
+                    pick_list(
+                        self.output_devices.as_slice(),
+                        Some(
+                            self.settings
+                                .output_device
+                                .clone()
+                                .unwrap_or_else(|| DEFAULT_DEVICE_LABEL.to_string()),
+                        ),
+                        |device| Message::SetOutputDevice(
+                            (device != DEFAULT_DEVICE_LABEL).then_some(device)
+                        ),
+                    ),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                row![
+                    text("Volume step when scrolling over the artwork"),
+                    pick_list(
+                        VolumeStep::ALL,
+                        Some(self.settings.volume_step),
+                        Message::SetVolumeStep,
+                    ),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(8),
+        )
+        .height(Length::Shrink)
+        .into()
+    }
+
+    fn integrations_section(&self) -> Element<'_, Message> {
+        let mut discord = toggler(self.settings.discord_presence)
+            .label("Show what you're listening to on Discord");
+        // Without an app id there's nothing to publish under
+        if constants::DISCORD_APP_ID.is_some() {
+            discord = discord.on_toggle(|_| Message::ToggleDiscordPresence);
+        }
+
+        let mut content = column![discord].spacing(8);
+        if constants::DISCORD_APP_ID.is_none() {
+            content = content.push(
+                text("Set DISCORD_APP_ID in your .env file to enable this")
+                    .size(13)
+                    .style(text::secondary),
+            );
+        }
+
+        section("Integrations", None, content)
+            .height(Length::Shrink)
+            .into()
+    }
+
+    fn advanced_section(&self) -> Element<'_, Message> {
+        section(
+            "Advanced",
+            None,
+            column![
+                row![
+                    text("Refresh the feed while it's open and scrolled to the top"),
+                    pick_list(
+                        AutorefreshChoice::ALL,
+                        Some(AutorefreshChoice(self.settings.feed_autorefresh_mins)),
+                        |choice| Message::SetFeedAutorefresh(choice.0),
+                    ),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center),
+                toggler(self.settings.show_rate_limit)
+                    .label("Warn when few SoundCloud API requests are left")
+                    .on_toggle(|_| Message::ToggleRateLimitIndicator),
+                toggler(self.settings.hide_blocked)
+                    .label("Hide tracks that are blocked in your region")
+                    .on_toggle(|_| Message::ToggleHideBlocked),
+                column![
+                    text(format!(
+                        "UI update interval while playing: {} ms",
+                        self.settings.ui_tick_interval().as_millis()
+                    )),
+                    text(
+                        "Longer intervals use less CPU, but the seekbar, visualizer and \
+                         media keys respond more slowly"
+                    )
+                    .size(13)
+                    .style(text::secondary),
+                    slider(
+                        *config::UI_TICK_MS_RANGE.start() as f64
+                            ..=*config::UI_TICK_MS_RANGE.end() as f64,
+                        self.settings.ui_tick_interval().as_millis() as f64,
+                        |ms| Message::SetUiTickMs(ms as u64),
+                    )
+                    .step(10.0)
+                    .width(300)
+                    .on_release(Message::SaveSettings),
+                ]
+                .spacing(4),
+                column![
+                    text(match self.settings.repeat_cache_limit() {
+                        Some(limit) =>
+                            format!("Instant repeat for tracks up to {}", limit.format_as_mmss()),
+                        None => "Instant repeat: off".to_string(),
+                    }),
+                    text(
+                        "Repeat one keeps short tracks decoded in memory (about 21 MB per \
+                         minute of audio) instead of decoding them again each time"
+                    )
+                    .size(13)
+                    .style(text::secondary),
+                    slider(
+                        0.0..=config::MAX_REPEAT_CACHE_SECS as f64,
+                        self.settings
+                            .repeat_cache_limit()
+                            .map_or(0.0, |limit| limit.as_secs() as f64),
+                        |secs| Message::SetRepeatCacheSecs(secs as u32),
+                    )
+                    .step(30.0)
+                    .width(300),
+                ]
+                .spacing(4),
+            ]
+            .spacing(8),
+        )
+        .height(Length::Shrink)
+        .into()
+    }
+
+    fn seekbar_section(&self) -> Element<'_, Message> {
+        let options = SeekbarType::ALL
+            .iter()
+            .fold(column![].spacing(8), |col, &seekbar_type| {
+                col.push(radio(
+                    seekbar_type.label(),
+                    seekbar_type,
+                    Some(self.settings.seekbar_type),
+                    Message::SetSeekbarType,
+                ))
+            });
+
+        section("Seekbar", None, options)
+            .height(Length::Shrink)
+            .into()
+    }
+
+    fn equalizer_section(&self) -> Element<'_, Message> {
+        let gains = self.settings.eq_gains;
+        let active_preset = EqPreset::ALL.into_iter().find(|p| p.gains() == gains);
+
+        let presets = EqPreset::ALL
+            .iter()
+            .fold(row![].spacing(6), |row, &preset| {
+                let style = if active_preset == Some(preset) {
+                    button::primary
+                } else {
+                    button::secondary
+                };
+                row.push(
+                    button(text(preset.label()))
+                        .style(style)
+                        .on_press(Message::ApplyEqPreset(preset)),
+                )
+            });
+
+        let bands = EQ_FREQUENCIES.iter().enumerate().fold(
+            row![].spacing(18).align_y(Alignment::End),
+            |row, (band, &freq)| {
+                let label = if freq >= 1_000.0 {
+                    format!("{}k", freq / 1_000.0)
+                } else {
+                    format!("{}", freq)
+                };
+                row.push(
+                    column![
+                        text(format!("{:+.0} dB", gains[band])).size(12),
+                        vertical_slider(-EQ_MAX_GAIN..=EQ_MAX_GAIN, gains[band], move |gain| {
+                            Message::SetEqBand { band, gain }
+                        })
+                        .step(0.5)
+                        .height(140)
+                        .on_release(Message::SaveSettings),
+                        text(label).size(12),
+                    ]
+                    .spacing(6)
+                    .align_x(Alignment::Center),
+                )
+            },
+        );
+
+        section(
+            "Equalizer",
+            active_preset.map(|p| p.label().to_string()),
+            column![presets, bands].spacing(16),
+        )
+        .height(Length::Shrink)
+        .into()
+    }
+}
+
+impl Page for SettingsPage {
+    fn update(&mut self, message: Message) -> (Option<Box<dyn Page>>, Task<Message>) {
+        match message {
+            Message::SetEqBand { band, gain } => {
+                if let Some(slot) = self.settings.eq_gains.get_mut(band) {
+                    *slot = gain;
+                }
+            }
+            Message::ApplyEqPreset(preset) => {
+                self.settings.eq_gains = preset.gains();
+            }
+            Message::SetSeekbarType(seekbar_type) => {
+                self.settings.seekbar_type = seekbar_type;
+            }
+            Message::ToggleAutoplay => {
+                self.settings.autoplay = !self.settings.autoplay;
+            }
+            Message::ToggleWrapQueue => {
+                self.settings.wrap_queue = !self.settings.wrap_queue;
+            }
+            Message::ToggleDiscordPresence => {
+                self.settings.discord_presence = !self.settings.discord_presence;
+            }
+            Message::ToggleRateLimitIndicator => {
+                self.settings.show_rate_limit = !self.settings.show_rate_limit;
+            }
+            Message::ToggleHideBlocked => {
+                self.settings.hide_blocked = !self.settings.hide_blocked;
+            }
+            Message::ToggleTheme => {
+                self.settings.theme = self.settings.theme.toggle();
+            }
+            Message::ZoomIn => self.settings.zoom(1),
+            Message::ZoomOut => self.settings.zoom(-1),
+            Message::ResetZoom => self.settings.ui_scale = None,
+            Message::SetUiTickMs(ms) => {
+                self.settings.ui_tick_ms = Some(ms);
+            }
+            Message::SetDefaultPage(page) => {
+                self.settings.default_page = page;
+            }
+            Message::ToggleRememberLastPage => {
+                self.settings.remember_last_page = !self.settings.remember_last_page;
+            }
+            Message::SetRepeatCacheSecs(secs) => {
+                self.settings.repeat_cache_secs = Some(secs);
+            }
+            Message::SetFeedAutorefresh(minutes) => {
+                self.settings.feed_autorefresh_mins = minutes;
+            }
+            Message::SetVolumeStep(step) => {
+                self.settings.volume_step = step;
+            }
+            Message::ToggleNormalizeVolume => {
+                self.settings.normalize_volume = !self.settings.normalize_volume;
+            }
+            Message::SetOutputDevice(device) => {
+                self.settings.output_device = device;
+            }
+            Message::NavigateToFeed => {
+                let (page, task) = FeedPage::new(self.token_manager.clone());
+                return (Some(Box::new(page)), task);
+            }
+            Message::NavigateToLikes => {
+                let (page, task) = LikesPage::new(self.token_manager.clone());
+                return (Some(Box::new(page)), task);
+            }
+            Message::NavigateToSearch => {
+                return (
+                    Some(Box::new(SearchPage::new(self.token_manager.clone()))),
+                    Task::none(),
+                );
+            }
+            _ => {}
+        }
+
+        (None, Task::none())
+    }
+
+    fn view(&self, _now_playing: Option<u64>, _hide_blocked: bool) -> iced::Element<'_, Message> {
+        Scrollable::new(
+            column![
+                self.startup_section(),
+                self.appearance_section(),
+                self.playback_section(),
+                self.seekbar_section(),
+                self.equalizer_section(),
+                self.integrations_section(),
+                self.advanced_section(),
+            ]
+            .spacing(10)
+            .padding(5),
+        )
+        .style(crate::widgets::scrollbar_style)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+    }
+}
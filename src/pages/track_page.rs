@@ -0,0 +1,525 @@
+use iced::Task;
+use iced::widget::image::Handle;
+use iced::widget::{
+    Scrollable, Space, button, column, container, image, mouse_area, row, sensor, text, text_input,
+};
+use iced::{Alignment, Font, Length, Theme, border};
+use tracing::debug;
+
+use crate::models::{SoundCloudComment, SoundCloudComments, SoundCloudTrack};
+use crate::pages::{FeedPage, LikesPage, SearchPage, SettingsPage, UserPage};
+use crate::soundcloud::TokenManager;
+use crate::soundcloud::api_helpers;
+use crate::utilities::{DurationFormat, NumberFormat, placeholder_image};
+use crate::widgets::{loading_state, section, spinner};
+use crate::{Message, Page};
+
+#[derive(Debug, Clone)]
+pub enum TrackPageMessage {
+    LoadDetails,
+    DetailsLoadedWithToken(Box<SoundCloudTrack>, TokenManager),
+    ApiErrorWithToken(String, TokenManager),
+    ArtworkLoaded(Handle),
+    LoadMoreComments,
+    CommentsLoadedWithToken(SoundCloudComments, TokenManager),
+    CommentsErrorWithToken(String, TokenManager),
+    CommentDraftChanged(String),
+    SubmitComment(u64, String), // MyApp stamps it with the author and playhead as a PostComment
+    PostComment(SoundCloudComment),
+    CommentPostedWithToken(SoundCloudComment, TokenManager),
+    CommentFailedWithToken(String, TokenManager),
+    PlayTrack,
+    LoadUser(String),
+}
+
+type Mt = TrackPageMessage;
+
+// Id of the optimistic copy of a comment that's still being posted
+const PENDING_COMMENT_ID: u64 = 0;
+
+// Start loading the next page when the bottom sentinel is within 500px of the viewport
+const LOAD_MORE_THRESHOLD: f32 = 500.0;
+
+/// Full details for one track. Opens with the list's copy of the track and
+/// fetches the description and tags, which list responses don't carry.
+pub struct TrackPage {
+    token_manager: TokenManager,
+    track: SoundCloudTrack,
+    artwork: Option<Handle>,
+    details_loading: bool,
+    details_failed: bool,
+    comments: Vec<SoundCloudComment>,
+    comments_next_href: Option<String>,
+    comments_loading: bool,
+    comments_failed: bool,
+    comment_draft: String,
+    posting_comment: bool,
+}
+
+impl TrackPage {
+    pub fn new(token_manager: TokenManager, track: SoundCloudTrack) -> (Self, Task<Message>) {
+        let artwork_url = track.artwork_url.clone();
+        let (token_manager_for_comments, track_id) = (token_manager.clone(), track.id);
+        (
+            Self {
+                token_manager,
+                track,
+                artwork: None,
+                details_loading: false,
+                details_failed: false,
+                comments: Vec::new(),
+                comments_next_href: None,
+                comments_loading: true,
+                comments_failed: false,
+                comment_draft: String::new(),
+                posting_comment: false,
+            },
+            Task::batch([
+                Task::done(Message::TrackPage(TrackPageMessage::LoadDetails)),
+                Self::fetch_comments_task(token_manager_for_comments, track_id, None),
+                Task::perform(
+                    async move { crate::utilities::download_image_with_fallback(&artwork_url).await },
+                    |handle| Message::TrackPage(Mt::ArtworkLoaded(handle)),
+                ),
+            ]),
+        )
+    }
+}
+
+impl TrackPage {
+    /// Fetches a page of comments (first page when `next_href` is `None`)
+    fn fetch_comments_task(
+        token_manager: TokenManager,
+        track_id: u64,
+        next_href: Option<String>,
+    ) -> Task<Message> {
+        Task::perform(
+            api_helpers::get_track_comments_with_refresh(token_manager, track_id, next_href),
+            |result| match result {
+                Ok((comments, token_manager)) => {
+                    Message::TrackPage(Mt::CommentsLoadedWithToken(comments, token_manager))
+                }
+                Err((error, token_manager)) => {
+                    Message::TrackPage(Mt::CommentsErrorWithToken(error.to_string(), token_manager))
+                }
+            },
+        )
+    }
+
+    /// Commenter, the point in the track it was left at, its age, and the text
+    fn comment_widget(comment: &SoundCloudComment, now: i64) -> iced::Element<'_, Message> {
+        let bold = Font {
+            weight: iced::font::Weight::Bold,
+            ..Font::DEFAULT
+        };
+        let mut heading = row![
+            mouse_area(
+                text(comment.user.username.clone())
+                    .font(bold)
+                    .shaping(text::Shaping::Auto)
+            )
+            .on_press(Message::TrackPage(Mt::LoadUser(comment.user.urn.clone())))
+            .interaction(iced::mouse::Interaction::Pointer)
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center);
+        if let Some(position) = comment.timestamp {
+            heading = heading.push(
+                text(format!(
+                    "at {}",
+                    std::time::Duration::from_millis(position).format_as_mmss()
+                ))
+                .size(12)
+                .style(text::secondary),
+            );
+        }
+        if comment.id == PENDING_COMMENT_ID {
+            heading = heading.push(text("posting…").size(12).style(text::secondary));
+        } else if let Some(created) = crate::utilities::parse_timestamp(&comment.created_at) {
+            heading = heading.push(
+                text(crate::utilities::format_time_ago(created, now))
+                    .size(12)
+                    .style(text::secondary),
+            );
+        }
+        column![
+            heading,
+            text(comment.body.clone()).shaping(text::Shaping::Auto)
+        ]
+        .spacing(2)
+        .into()
+    }
+
+    /// Comment input; the comment is pinned to the playhead when this track
+    /// is the one playing
+    fn comment_composer(&self, now_playing: Option<u64>) -> iced::Element<'_, Message> {
+        let submit = (!self.posting_comment && !self.comment_draft.trim().is_empty()).then(|| {
+            Message::TrackPage(Mt::SubmitComment(self.track.id, self.comment_draft.clone()))
+        });
+        let mut input = text_input("Add a comment", &self.comment_draft)
+            .on_input(|s| Message::TrackPage(Mt::CommentDraftChanged(s)));
+        if let Some(submit) = submit.clone() {
+            input = input.on_submit(submit);
+        }
+        let mut composer = column![
+            row![input, button(text("Post")).on_press_maybe(submit)]
+                .spacing(8)
+                .align_y(Alignment::Center)
+        ]
+        .spacing(4);
+        if now_playing == Some(self.track.id) {
+            composer = composer.push(
+                text("Posts at the current playback position")
+                    .size(12)
+                    .style(text::secondary),
+            );
+        }
+        composer.into()
+    }
+
+    fn comments_view(&self) -> iced::Element<'_, Message> {
+        if self.comments.is_empty() {
+            return if self.comments_loading {
+                loading_state()
+            } else if self.comments_failed {
+                text("Couldn't load the comments")
+                    .style(text::secondary)
+                    .into()
+            } else {
+                text("No comments yet").style(text::secondary).into()
+            };
+        }
+
+        let now = crate::utilities::unix_now();
+        let mut list = self
+            .comments
+            .iter()
+            .fold(column![].spacing(12), |list, comment| {
+                list.push(Self::comment_widget(comment, now))
+            });
+        if self.comments_failed {
+            list = list.push(text("Couldn't load more comments").style(text::secondary));
+        } else if self.comments_next_href.is_some() {
+            // Bottom sentinel: loads the next page of comments when scrolled near the end.
+            list = list.push(
+                sensor(container(spinner(24.0)).center_x(Length::Fill).padding(8))
+                    .on_show(|_| Message::TrackPage(Mt::LoadMoreComments))
+                    .anticipate(LOAD_MORE_THRESHOLD)
+                    .key(self.comments.len()),
+            );
+        }
+        list.into()
+    }
+}
+
+impl Page for TrackPage {
+    fn is_animating(&self) -> bool {
+        // Keep frames flowing while the loading spinner is on screen.
+        self.details_loading || self.comments_loading
+    }
+
+    fn update(&mut self, message: Message) -> (Option<Box<dyn Page>>, Task<Message>) {
+        if let Message::TrackPage(msg) = message {
+            match msg {
+                TrackPageMessage::LoadDetails => {
+                    self.details_loading = true;
+                    return (
+                        None,
+                        Task::perform(
+                            api_helpers::get_track_with_refresh(
+                                self.token_manager.clone(),
+                                self.track.id,
+                            ),
+                            |result| match result {
+                                Ok((track, token_manager)) => Message::TrackPage(
+                                    Mt::DetailsLoadedWithToken(Box::new(track), token_manager),
+                                ),
+                                Err((error, token_manager)) => Message::TrackPage(
+                                    Mt::ApiErrorWithToken(error.to_string(), token_manager),
+                                ),
+                            },
+                        ),
+                    );
+                }
+                TrackPageMessage::DetailsLoadedWithToken(track, token_manager) => {
+                    self.token_manager = token_manager;
+                    self.details_loading = false;
+                    self.track = *track;
+                    return (None, Task::none());
+                }
+                TrackPageMessage::ApiErrorWithToken(error, token_manager) => {
+                    debug!(
+                        "Failed to load details for track {}: {}",
+                        self.track.id, error
+                    );
+                    self.token_manager = token_manager;
+                    self.details_loading = false;
+                    self.details_failed = true;
+                    return (None, Task::none());
+                }
+                TrackPageMessage::ArtworkLoaded(handle) => {
+                    self.artwork = Some(handle);
+                    return (None, Task::none());
+                }
+                TrackPageMessage::LoadMoreComments => {
+                    if self.comments_loading || self.comments_next_href.is_none() {
+                        return (None, Task::none());
+                    }
+                    self.comments_loading = true;
+                    return (
+                        None,
+                        Self::fetch_comments_task(
+                            self.token_manager.clone(),
+                            self.track.id,
+                            self.comments_next_href.clone(),
+                        ),
+                    );
+                }
+                TrackPageMessage::CommentsLoadedWithToken(comments, token_manager) => {
+                    self.token_manager = token_manager;
+                    self.comments_loading = false;
+                    self.comments_next_href = comments.next_href;
+                    self.comments.extend(comments.collection);
+                    return (None, Task::none());
+                }
+                TrackPageMessage::CommentsErrorWithToken(error, token_manager) => {
+                    debug!(
+                        "Failed to load comments for track {}: {}",
+                        self.track.id, error
+                    );
+                    self.token_manager = token_manager;
+                    self.comments_loading = false;
+                    self.comments_failed = true;
+                    return (None, Task::none());
+                }
+                TrackPageMessage::CommentDraftChanged(draft) => {
+                    self.comment_draft = draft;
+                    return (None, Task::none());
+                }
+                TrackPageMessage::SubmitComment(..) => {
+                    // Handled by MyApp, which knows who's posting and the playhead
+                    return (None, Task::none());
+                }
+                TrackPageMessage::PostComment(mut comment) => {
+                    if self.posting_comment {
+                        return (None, Task::none());
+                    }
+                    comment.id = PENDING_COMMENT_ID;
+                    self.posting_comment = true;
+                    self.comment_draft.clear();
+                    let (body, timestamp) = (comment.body.clone(), comment.timestamp);
+                    // Shown straight away, and dropped again if posting fails
+                    self.comments.insert(0, comment);
+                    return (
+                        None,
+                        Task::perform(
+                            api_helpers::post_comment_with_refresh(
+                                self.token_manager.clone(),
+                                self.track.id,
+                                body,
+                                timestamp,
+                            ),
+                            |result| match result {
+                                Ok((comment, token_manager)) => Message::TrackPage(
+                                    Mt::CommentPostedWithToken(comment, token_manager),
+                                ),
+                                Err((error, token_manager)) => Message::TrackPage(
+                                    Mt::CommentFailedWithToken(error.to_string(), token_manager),
+                                ),
+                            },
+                        ),
+                    );
+                }
+                TrackPageMessage::CommentPostedWithToken(comment, token_manager) => {
+                    self.token_manager = token_manager;
+                    self.posting_comment = false;
+                    if let Some(pending) = self
+                        .comments
+                        .iter_mut()
+                        .find(|c| c.id == PENDING_COMMENT_ID)
+                    {
+                        *pending = comment;
+                    }
+                    return (None, Task::none());
+                }
+                TrackPageMessage::CommentFailedWithToken(error, token_manager) => {
+                    debug!(
+                        "Failed to post comment on track {}: {}",
+                        self.track.id, error
+                    );
+                    self.token_manager = token_manager;
+                    self.posting_comment = false;
+                    if let Some(index) = self
+                        .comments
+                        .iter()
+                        .position(|c| c.id == PENDING_COMMENT_ID)
+                    {
+                        let pending = self.comments.remove(index);
+                        // Hand the text back so it can be retried
+                        if self.comment_draft.is_empty() {
+                            self.comment_draft = pending.body;
+                        }
+                    }
+                    return (None, Task::none());
+                }
+                TrackPageMessage::PlayTrack => {
+                    return (
+                        None,
+                        Task::done(Message::StartQueue(
+                            self.track.clone(),
+                            std::sync::Arc::new(vec![self.track.clone()]),
+                            self.token_manager.clone(),
+                        )),
+                    );
+                }
+                TrackPageMessage::LoadUser(user_urn) => {
+                    let (user_page, task) = UserPage::new(self.token_manager.clone(), user_urn);
+                    return (Some(Box::new(user_page)), task);
+                }
+            }
+        }
+
+        if let Message::NavigateToFeed = message {
+            let (page, task) = FeedPage::new(self.token_manager.clone());
+            return (Some(Box::new(page)), task);
+        }
+
+        if let Message::NavigateToLikes = message {
+            let (page, task) = LikesPage::new(self.token_manager.clone());
+            return (Some(Box::new(page)), task);
+        }
+
+        if let Message::NavigateToSearch = message {
+            return (
+                Some(Box::new(SearchPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
+        (None, Task::none())
+    }
+
+    fn view(&self, now_playing: Option<u64>, _hide_blocked: bool) -> iced::Element<'_, Message> {
+        let track = &self.track;
+        let bold = Font {
+            weight: iced::font::Weight::Bold,
+            ..Font::DEFAULT
+        };
+
+        let artwork = image(self.artwork.clone().unwrap_or_else(placeholder_image))
+            .width(160)
+            .height(160);
+
+        let stats = row![
+            text(format!(
+                "{} plays",
+                track.playback_count.unwrap_or(0).format_compact_number()
+            )),
+            text(format!(
+                "{} likes",
+                track.favoritings_count.unwrap_or(0).format_compact_number()
+            )),
+            text(format!(
+                "{} reposts",
+                track.reposts_count.unwrap_or(0).format_compact_number()
+            )),
+        ]
+        .spacing(16);
+
+        let header = row![
+            artwork,
+            column![
+                text(track.title.clone())
+                    .size(24)
+                    .font(bold)
+                    .shaping(text::Shaping::Auto),
+                mouse_area(
+                    text(track.user.username.clone())
+                        .size(18)
+                        .shaping(text::Shaping::Auto)
+                )
+                .on_press(Message::TrackPage(Mt::LoadUser(track.user.urn.clone())))
+                .interaction(iced::mouse::Interaction::Pointer),
+                text(std::time::Duration::from_millis(track.duration).format_as_mmss())
+                    .style(text::secondary),
+                stats,
+                row![
+                    button(text("Play")).on_press_maybe(
+                        track
+                            .stream_url
+                            .is_some()
+                            .then_some(Message::TrackPage(Mt::PlayTrack))
+                    ),
+                    button(text("Copy link"))
+                        .on_press(Message::CopyLink(track.permalink_url.clone())),
+                ]
+                .spacing(8),
+            ]
+            .spacing(8),
+        ]
+        .spacing(16)
+        .align_y(Alignment::Start);
+
+        // Genre first, then the tags, as pills
+        let tags =
+            std::iter::once(track.genre.clone())
+                .chain(track.tags())
+                .filter(|tag| !tag.is_empty())
+                .fold(row![].spacing(6), |row, tag| {
+                    row.push(container(text(tag).size(12)).padding([2, 8]).style(
+                        |theme: &Theme| {
+                            let palette = theme.extended_palette();
+                            container::Style {
+                                background: Some(palette.primary.weak.color.into()),
+                                text_color: Some(palette.primary.weak.text),
+                                border: border::rounded(999),
+                                ..container::Style::default()
+                            }
+                        },
+                    ))
+                })
+                .wrap();
+
+        let description: iced::Element<'_, Message> = if self.details_loading {
+            loading_state()
+        } else if self.details_failed {
+            text("Couldn't load the track details")
+                .style(text::secondary)
+                .into()
+        } else if track.description.is_empty() {
+            text("No description").style(text::secondary).into()
+        } else {
+            text(track.description.clone())
+                .shaping(text::Shaping::Auto)
+                .into()
+        };
+
+        Scrollable::new(
+            column![
+                header,
+                tags,
+                section("Description", None, description).height(Length::Shrink),
+                section(
+                    "Comments",
+                    None,
+                    column![self.comment_composer(now_playing), self.comments_view()].spacing(12)
+                )
+                .height(Length::Shrink),
+                Space::new().height(10),
+            ]
+            .spacing(16)
+            .padding(10),
+        )
+        .style(crate::widgets::scrollbar_style)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+    }
+}
@@ -8,14 +8,21 @@ use crate::models::{
     SoundCloudPlaylist, SoundCloudPlaylists, SoundCloudTrack, SoundCloudTracks, SoundCloudUser,
     SoundCloudUserProfile,
 };
-use crate::pages::{FeedPage, LikesPage, PlaylistPage, SearchPage};
+use crate::pages::{
+    FeedPage, FollowList, FollowsPage, LikesPage, PlaylistPage, SearchPage, SettingsPage,
+};
 use crate::soundcloud::TokenManager;
 use crate::soundcloud::api_helpers;
-use crate::utilities::{NumberFormat, get_asset_path};
-use crate::widgets::{empty_state, get_playlist_widget, loading_state, section, spinner};
+use crate::utilities::NumberFormat;
+use crate::widgets::{
+    empty_state, get_playlist_widget, loading_state, play_all_buttons, section, spinner,
+    user_badges,
+};
 use crate::{Message, Page};
-use iced::widget::image::{self, Handle};
-use iced::widget::{Container, Scrollable, column, container, grid, row, sensor, text};
+use iced::widget::image::Handle;
+use iced::widget::{
+    Container, Scrollable, Space, button, column, container, grid, row, sensor, text,
+};
 use iced::{Alignment, Font, Length};
 
 // Start loading the next page when the bottom sentinel is within 500px of the viewport
@@ -26,17 +33,15 @@ pub enum UserPageMessage {
     LoadUser,
     UserProfileLoaded(SoundCloudUserProfile, TokenManager),
     LoadMoreTracks,
+    PlayAll(bool), // The user's own tracks, shuffled or not
     LoadMorePlaylists,
     MoreTracksLoadedWithToken(SoundCloudTracks, TokenManager),
     MorePlaylistsLoadedWithToken(SoundCloudPlaylists, TokenManager),
     PlaylistImageLoaded(String, Handle),
-    PlaylistImageLoadFailed(String),
     AvatarImageLoaded(Handle),
-    AvatarImageLoadFailed,
     ApiErrorWithToken(String, TokenManager),
     RequestTrackImage(u64),
     TrackImageLoaded(u64, Handle),
-    TrackImageLoadFailed(u64),
     PlayTrack(SoundCloudTrack),
     NavigateToUser(String),
     LoadPlaylist(SoundCloudPlaylist),
@@ -47,15 +52,15 @@ pub enum UserPageMessage {
     LikedTracksLoadFailedWithToken(String, TokenManager),
     RequestLikedTrackImage(u64),
     LikedTrackImageLoaded(u64, Handle),
-    LikedTrackImageLoadFailed(u64),
     PlayLikedTrack(SoundCloudTrack),
     LoadMoreRepostedTracks,
     MoreRepostedTracksLoadedWithToken(SoundCloudTracks, TokenManager),
     RepostedTracksLoadFailedWithToken(String, TokenManager),
     RequestRepostedTrackImage(u64),
     RepostedTrackImageLoaded(u64, Handle),
-    RepostedTrackImageLoadFailed(u64),
     PlayRepostedTrack(SoundCloudTrack),
+    ShowFollowings,
+    ShowFollowers,
 }
 
 type Mu = UserPageMessage;
@@ -73,6 +78,7 @@ pub struct UserPage {
     tracks_next_href: Option<String>,
     tracks_loading: bool,
     track_load_failed: bool,
+    play_all_when_loaded: Option<bool>, // Shuffled; a Play all waiting on the remaining pages
     liked_list: TrackListManager,
     liked_next_href: Option<String>,
     liked_loading: bool,
@@ -81,6 +87,7 @@ pub struct UserPage {
     reposted_next_href: Option<String>,
     reposted_loading: bool,
     reposted_load_failed: bool,
+    is_me: bool, // The logged-in user's own profile, with links to its follow lists
 }
 
 impl UserPage {
@@ -99,6 +106,7 @@ impl UserPage {
                 tracks_next_href: None,
                 tracks_loading: false,
                 track_load_failed: false,
+                play_all_when_loaded: None,
                 liked_list: TrackListManager::new(),
                 liked_next_href: None,
                 liked_loading: false,
@@ -107,31 +115,33 @@ impl UserPage {
                 reposted_next_href: None,
                 reposted_loading: false,
                 reposted_load_failed: false,
+                is_me: false,
             },
             Task::done(Message::UserPage(UserPageMessage::LoadUser)),
         )
     }
 
-    /// Builds the artwork-download tasks for a batch of playlists.
-    fn playlist_image_tasks(playlists: &[SoundCloudPlaylist]) -> Vec<Task<Message>> {
-        playlists
-            .iter()
-            .map(|playlist| {
-                let playlist_urn = playlist.urn.clone();
-                let artwork_url = playlist.artwork_url.clone();
-                Task::perform(
-                    async move { crate::utilities::download_image(&artwork_url).await },
-                    move |result| match result {
-                        Ok(handle) => {
-                            Message::UserPage(Mu::PlaylistImageLoaded(playlist_urn.clone(), handle))
-                        }
-                        Err(_) => {
-                            Message::UserPage(Mu::PlaylistImageLoadFailed(playlist_urn.clone()))
-                        }
-                    },
-                )
-            })
-            .collect()
+    /// The logged-in user's own profile
+    pub fn new_me(token_manager: TokenManager, user_urn: String) -> (Self, Task<Message>) {
+        let (page, task) = Self::new(token_manager, user_urn);
+        (
+            Self {
+                is_me: true,
+                ..page
+            },
+            task,
+        )
+    }
+
+    /// Downloads the artwork for a batch of playlists.
+    fn playlist_images_task(playlists: &[SoundCloudPlaylist]) -> Task<Message> {
+        crate::utilities::load_images_batched(
+            playlists
+                .iter()
+                .map(|playlist| (playlist.urn.clone(), playlist.artwork_url.clone()))
+                .collect(),
+            |playlist_urn, handle| Message::UserPage(Mu::PlaylistImageLoaded(playlist_urn, handle)),
+        )
     }
 
     /// Fetches a page of the user's liked tracks (initial load when
@@ -182,6 +192,8 @@ impl UserPage {
         &'a self,
         title: &'a str,
         list: &'a TrackListManager,
+        now_playing: Option<u64>,
+        hide_blocked: bool,
         has_more: bool,
         loading: bool,
         load_failed: bool,
@@ -205,6 +217,8 @@ impl UserPage {
             }
         } else {
             let mut tracks_column = list.render_tracks(
+                now_playing,
+                hide_blocked,
                 move |t| Message::UserPage(on_play(t)),
                 |urn| Message::UserPage(UserPageMessage::NavigateToUser(urn)),
                 |t| Message::UserPage(UserPageMessage::LikeTrack(t)),
@@ -278,14 +292,11 @@ impl Page for UserPage {
 
                     // Track artwork loads lazily per row via RequestTrackImage; the
                     // playlist thumbnails and the header avatar are fetched eagerly here.
-                    let mut tasks = Self::playlist_image_tasks(&self.playlists);
+                    let mut tasks = vec![Self::playlist_images_task(&self.playlists)];
                     let avatar_url = self.user.avatar_url.clone();
                     tasks.push(Task::perform(
-                        async move { crate::utilities::download_image(&avatar_url).await },
-                        |result| match result {
-                            Ok(handle) => Message::UserPage(Mu::AvatarImageLoaded(handle)),
-                            Err(_) => Message::UserPage(Mu::AvatarImageLoadFailed),
-                        },
+                        async move { crate::utilities::download_image_with_fallback(&avatar_url).await },
+                        |handle| Message::UserPage(Mu::AvatarImageLoaded(handle)),
                     ));
 
                     // The liked/reposted panels load after the profile so they
@@ -328,6 +339,14 @@ impl Page for UserPage {
                     self.tracks_loading = false;
                     self.tracks_next_href = tracks.next_href.clone();
                     self.track_list.append_tracks(tracks.collection);
+                    if self.play_all_when_loaded.is_some() {
+                        if self.tracks_next_href.is_some() {
+                            return self.update(Message::UserPage(Mu::LoadMoreTracks));
+                        }
+                        if let Some(shuffle) = self.play_all_when_loaded.take() {
+                            return self.update(Message::UserPage(Mu::PlayAll(shuffle)));
+                        }
+                    }
                     return (None, Task::none());
                 }
                 UserPageMessage::LoadMorePlaylists => {
@@ -361,61 +380,62 @@ impl Page for UserPage {
                     self.token_manager = token_manager;
                     self.playlists_loading = false;
                     self.playlists_next_href = playlists.next_href.clone();
-                    let image_tasks = Self::playlist_image_tasks(&playlists.collection);
+                    let image_task = Self::playlist_images_task(&playlists.collection);
                     self.playlists.extend(playlists.collection);
-                    return (None, Task::batch(image_tasks));
+                    return (None, image_task);
                 }
                 UserPageMessage::RequestTrackImage(track_id) => {
                     return (
                         None,
-                        self.track_list.load_image_task(
-                            track_id,
-                            |id, handle| Message::UserPage(Mu::TrackImageLoaded(id, handle)),
-                            |id| Message::UserPage(Mu::TrackImageLoadFailed(id)),
-                        ),
+                        self.track_list.load_image_task(track_id, |id, handle| {
+                            Message::UserPage(Mu::TrackImageLoaded(id, handle))
+                        }),
                     );
                 }
                 UserPageMessage::TrackImageLoaded(track_id, handle) => {
                     self.track_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                UserPageMessage::TrackImageLoadFailed(track_id) => {
-                    debug!("Failed to load image for track {}", track_id);
-                    return (None, Task::none());
-                }
                 UserPageMessage::AvatarImageLoaded(handle) => {
                     self.avatar_image = Some(handle);
                     return (None, Task::none());
                 }
-                UserPageMessage::AvatarImageLoadFailed => {
-                    // Header simply renders without an avatar.
-                    debug!("Failed to load avatar for user {}", self.user.urn);
-                    return (None, Task::none());
-                }
                 UserPageMessage::PlaylistImageLoaded(urn, handle) => {
                     self.playlist_images.insert(urn, handle);
                     return (None, Task::none());
                 }
-                UserPageMessage::PlaylistImageLoadFailed(urn) => {
-                    debug!("Failed to load image for playlist {}", urn);
-                    let handle = image::Handle::from_path(get_asset_path("assets/icon/png"));
-                    self.playlist_images.insert(urn, handle);
-                    return (None, Task::none());
-                }
                 UserPageMessage::ApiErrorWithToken(_error_msg, token_manager) => {
                     self.token_manager = token_manager;
                     self.track_load_failed = true;
                     self.tracks_loading = false;
                     self.playlists_loading = false;
+                    self.play_all_when_loaded = None;
                     return (None, Task::none());
                 }
+                UserPageMessage::PlayAll(shuffle) => {
+                    // Play all of the user's tracks, not just the pages scrolled into view
+                    if self.tracks_next_href.is_some() {
+                        self.play_all_when_loaded = Some(shuffle);
+                        return self.update(Message::UserPage(Mu::LoadMoreTracks));
+                    }
+                    let Some((first, tracks)) = self.track_list.play_all(shuffle) else {
+                        return (None, Task::none());
+                    };
+                    return (
+                        None,
+                        Task::done(Message::StartQueue(
+                            first,
+                            tracks,
+                            self.token_manager.clone(),
+                        )),
+                    );
+                }
                 UserPageMessage::PlayTrack(track) => {
-                    self.track_list.set_current_track_id(track.id);
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.track_list.tracks().clone(),
+                            self.track_list.shared_tracks(),
                             self.token_manager.clone(),
                         )),
                     );
@@ -424,6 +444,16 @@ impl Page for UserPage {
                     debug!("Loading user {}", user_urn);
                     return (None, Task::none());
                 }
+                UserPageMessage::ShowFollowings | UserPageMessage::ShowFollowers => {
+                    let list = if matches!(msg, UserPageMessage::ShowFollowings) {
+                        FollowList::Followings
+                    } else {
+                        FollowList::Followers
+                    };
+                    let (page, task) =
+                        FollowsPage::new(self.token_manager.clone(), list, self.user_urn.clone());
+                    return (Some(Box::new(page)), task);
+                }
                 UserPageMessage::LoadPlaylist(playlist) => {
                     let (playlist_page, task) =
                         PlaylistPage::new(self.token_manager.clone(), playlist);
@@ -476,28 +506,21 @@ impl Page for UserPage {
                 UserPageMessage::RequestLikedTrackImage(track_id) => {
                     return (
                         None,
-                        self.liked_list.load_image_task(
-                            track_id,
-                            |id, handle| Message::UserPage(Mu::LikedTrackImageLoaded(id, handle)),
-                            |id| Message::UserPage(Mu::LikedTrackImageLoadFailed(id)),
-                        ),
+                        self.liked_list.load_image_task(track_id, |id, handle| {
+                            Message::UserPage(Mu::LikedTrackImageLoaded(id, handle))
+                        }),
                     );
                 }
                 UserPageMessage::LikedTrackImageLoaded(track_id, handle) => {
                     self.liked_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                UserPageMessage::LikedTrackImageLoadFailed(track_id) => {
-                    debug!("Failed to load image for liked track {}", track_id);
-                    return (None, Task::none());
-                }
                 UserPageMessage::PlayLikedTrack(track) => {
-                    self.liked_list.set_current_track_id(track.id);
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.liked_list.tracks().clone(),
+                            self.liked_list.shared_tracks(),
                             self.token_manager.clone(),
                         )),
                     );
@@ -527,30 +550,21 @@ impl Page for UserPage {
                 UserPageMessage::RequestRepostedTrackImage(track_id) => {
                     return (
                         None,
-                        self.reposted_list.load_image_task(
-                            track_id,
-                            |id, handle| {
-                                Message::UserPage(Mu::RepostedTrackImageLoaded(id, handle))
-                            },
-                            |id| Message::UserPage(Mu::RepostedTrackImageLoadFailed(id)),
-                        ),
+                        self.reposted_list.load_image_task(track_id, |id, handle| {
+                            Message::UserPage(Mu::RepostedTrackImageLoaded(id, handle))
+                        }),
                     );
                 }
                 UserPageMessage::RepostedTrackImageLoaded(track_id, handle) => {
                     self.reposted_list.handle_image_loaded(track_id, handle);
                     return (None, Task::none());
                 }
-                UserPageMessage::RepostedTrackImageLoadFailed(track_id) => {
-                    debug!("Failed to load image for reposted track {}", track_id);
-                    return (None, Task::none());
-                }
                 UserPageMessage::PlayRepostedTrack(track) => {
-                    self.reposted_list.set_current_track_id(track.id);
                     return (
                         None,
                         Task::done(Message::StartQueue(
                             track.clone(),
-                            self.reposted_list.tracks().clone(),
+                            self.reposted_list.shared_tracks(),
                             self.token_manager.clone(),
                         )),
                     );
@@ -575,14 +589,21 @@ impl Page for UserPage {
             );
         }
 
+        if let Message::NavigateToSettings = message {
+            return (
+                Some(Box::new(SettingsPage::new(self.token_manager.clone()))),
+                Task::none(),
+            );
+        }
+
         (None, Task::none())
     }
 
-    fn view(&self) -> iced::Element<'_, Message> {
+    fn view(&self, now_playing: Option<u64>, hide_blocked: bool) -> iced::Element<'_, Message> {
         // An empty urn means the profile request hasn't completed yet.
         let profile_loading = self.user.urn.is_empty();
 
-        // Header strip: avatar, username, follower count.
+        // Header strip: avatar, username, follower count, badges and location.
         let bold = Font {
             weight: iced::font::Weight::Bold,
             ..Font::DEFAULT
@@ -605,12 +626,36 @@ impl Page for UserPage {
             ))
             .size(14)
             .style(text::secondary),
+            user_badges(&self.user),
         ]);
+        // Plays the user's own tracks
+        header = header
+            .push(Space::new().width(Length::Fill))
+            .push(play_all_buttons(
+                // Pages not loaded yet may hold playable tracks
+                self.track_list.has_playable_tracks() || self.tracks_next_href.is_some(),
+                |shuffle| Message::UserPage(UserPageMessage::PlayAll(shuffle)),
+            ));
+        if self.is_me {
+            header = header.push(
+                row![
+                    button(text("Following"))
+                        .style(button::secondary)
+                        .on_press(Message::UserPage(UserPageMessage::ShowFollowings)),
+                    button(text("Followers"))
+                        .style(button::secondary)
+                        .on_press(Message::UserPage(UserPageMessage::ShowFollowers)),
+                ]
+                .spacing(6),
+            );
+        }
 
         // Top-left: the user's own tracks.
         let tracks_panel = self.track_list_panel(
             "Tracks",
             &self.track_list,
+            now_playing,
+            hide_blocked,
             self.tracks_next_href.is_some(),
             profile_loading,
             self.track_load_failed,
@@ -635,7 +680,7 @@ impl Page for UserPage {
         } else {
             // Responsive grid of playlist cards: column count adapts to available width.
             let playlist_cells = self.playlists.iter().map(|playlist| {
-                let image_handle = self.playlist_images.get(&playlist.user.urn).cloned();
+                let image_handle = self.playlist_images.get(&playlist.urn).cloned();
                 iced::Element::from(get_playlist_widget(playlist, image_handle, |urn| {
                     Message::UserPage(UserPageMessage::LoadPlaylist(urn))
                 }))
@@ -671,6 +716,8 @@ impl Page for UserPage {
         let likes_panel = self.track_list_panel(
             "Likes",
             &self.liked_list,
+            now_playing,
+            hide_blocked,
             self.liked_next_href.is_some(),
             profile_loading || self.liked_loading,
             self.liked_load_failed,
@@ -685,6 +732,8 @@ impl Page for UserPage {
         let reposts_panel = self.track_list_panel(
             "Reposts",
             &self.reposted_list,
+            now_playing,
+            hide_blocked,
             self.reposted_next_href.is_some(),
             profile_loading || self.reposted_loading,
             self.reposted_load_failed,
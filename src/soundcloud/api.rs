@@ -1,13 +1,15 @@
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
 use oauth2::AccessToken;
+use serde::Deserialize;
 use tokio::try_join;
 use tokio_util::bytes::Bytes;
 
 use crate::models::{
-    SearchResults, SoundCloudActivityCollection, SoundCloudPlaylists, SoundCloudStreams,
-    SoundCloudTrack, SoundCloudTracks, SoundCloudUser, SoundCloudUserProfile, SoundCloudUsers,
+    SearchResults, SoundCloudActivityCollection, SoundCloudComment, SoundCloudComments,
+    SoundCloudPlaylist, SoundCloudPlaylists, SoundCloudStreams, SoundCloudTrack, SoundCloudTracks,
+    SoundCloudUser, SoundCloudUserProfile, SoundCloudUsers, TrackFilters,
 };
 
 /// Shared HTTP client so TLS handshakes and connections are reused across all
@@ -23,10 +25,161 @@ fn http_client() -> &'static reqwest::Client {
     })
 }
 
+/// Broad category of an API failure, so callers can react (re-auth, back off)
+/// without parsing messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    Auth,        // 401/403: the token was rejected
+    RateLimited, // 429
+    NotFound,    // 404
+    Network,     // Connection failures, timeouts, other HTTP errors
+    Parse,       // The response body didn't match the expected shape
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub kind: ApiErrorKind,
+    pub status: Option<u16>, // HTTP status, when the server answered
+    pub message: String,
+}
+
+impl ApiError {
+    fn from_status(status: reqwest::StatusCode, message: String) -> Self {
+        let kind = match status.as_u16() {
+            401 | 403 => ApiErrorKind::Auth,
+            404 => ApiErrorKind::NotFound,
+            429 => ApiErrorKind::RateLimited,
+            _ => ApiErrorKind::Network,
+        };
+        Self {
+            kind,
+            status: Some(status.as_u16()),
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "HTTP {} error: {}", status, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            Self {
+                kind: ApiErrorKind::Parse,
+                status: None,
+                message: err.to_string(),
+            }
+        } else if let Some(status) = err.status() {
+            Self::from_status(status, err.to_string())
+        } else {
+            Self {
+                kind: ApiErrorKind::Network,
+                status: None,
+                message: err.to_string(),
+            }
+        }
+    }
+}
+
+/// SoundCloud's request quota, from the `x-ratelimit-*` response headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    pub remaining: u32,
+    pub limit: u32,
+}
+
+impl RateLimitInfo {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name| headers.get(name)?.to_str().ok()?.trim().parse().ok();
+        Some(Self {
+            remaining: header("x-ratelimit-remaining")?,
+            limit: header("x-ratelimit-limit")?,
+        })
+    }
+
+    /// Whether at most a tenth of the quota is left
+    pub fn is_low(&self) -> bool {
+        self.limit > 0 && self.remaining.saturating_mul(10) <= self.limit
+    }
+}
+
+/// Quota reported by the most recent API response
+static RATE_LIMIT: Mutex<Option<RateLimitInfo>> = Mutex::new(None);
+
+/// The last rate-limit status SoundCloud reported, if it has sent one
+pub fn rate_limit() -> Option<RateLimitInfo> {
+    *RATE_LIMIT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// SoundCloud's JSON error body, e.g.
+/// `{"errors":[{"error_message":"404 - Not Found"}]}`
+#[derive(Debug, Deserialize)]
+struct SoundCloudErrorBody {
+    #[serde(default)]
+    errors: Vec<SoundCloudErrorEntry>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SoundCloudErrorEntry {
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+/// The readable part of an error body: the `error_message`s joined, or the
+/// top-level `message`, falling back to the raw text when it isn't JSON
+fn error_message_from_body(body: &str) -> String {
+    let Ok(parsed) = serde_json::from_str::<SoundCloudErrorBody>(body) else {
+        return body.trim().to_string();
+    };
+    let messages: Vec<&str> = parsed
+        .errors
+        .iter()
+        .filter_map(|e| e.error_message.as_deref())
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .collect();
+    if !messages.is_empty() {
+        messages.join("; ")
+    } else if let Some(message) = parsed.message.filter(|m| !m.trim().is_empty()) {
+        message.trim().to_string()
+    } else {
+        body.trim().to_string()
+    }
+}
+
+/// Pass a successful response through, or turn an error status into an
+/// `ApiError` carrying the response body's error message
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+    if let Some(info) = RateLimitInfo::from_headers(response.headers()) {
+        *RATE_LIMIT.lock().unwrap_or_else(|e| e.into_inner()) = Some(info);
+    }
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let error_text = response
+        .text()
+        .await
+        .map(|body| error_message_from_body(&body))
+        .unwrap_or_else(|_| "Failed to read error body".to_string());
+    Err(ApiError::from_status(status, error_text))
+}
+
 pub async fn get_liked_tracks_paginated(
     access_token: AccessToken,
     next_href: Option<String>,
-) -> Result<SoundCloudTracks, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudTracks, ApiError> {
     let c = http_client();
 
     let url = next_href.unwrap_or_else(|| "https://api.soundcloud.com/me/likes/tracks".to_string());
@@ -44,14 +197,7 @@ pub async fn get_liked_tracks_paginated(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudTracks>().await?;
     Ok(body)
@@ -60,7 +206,7 @@ pub async fn get_liked_tracks_paginated(
 pub async fn get_activity_feed_paginated(
     access_token: AccessToken,
     next_href: Option<String>,
-) -> Result<SoundCloudActivityCollection, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudActivityCollection, ApiError> {
     let c = http_client();
 
     let url =
@@ -79,24 +225,21 @@ pub async fn get_activity_feed_paginated(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudActivityCollection>().await?;
     Ok(body)
 }
 
+/// Tracks matching `query`, narrowed to `genre` and `filters` where set.
+/// An empty `query` lists the genre's tracks, newest SoundCloud matches first.
 pub async fn search_tracks(
     access_token: AccessToken,
     query: &str,
+    genre: Option<&str>,
+    filters: &TrackFilters,
     next_href: Option<String>,
-) -> Result<SoundCloudTracks, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudTracks, ApiError> {
     let c = http_client();
 
     let url = next_href.unwrap_or_else(|| "https://api.soundcloud.com/tracks".to_string());
@@ -105,34 +248,50 @@ pub async fn search_tracks(
 
     // Only add query parameters if using the default URL (not a pagination URL)
     if !url.contains("?") {
-        request = request.query(&[
-            ("q", query),
-            ("access", "playable,blocked"),
-            ("limit", "50"),
-            ("linked_partitioning", "true"),
-        ]);
+        if !query.is_empty() {
+            request = request.query(&[("q", query)]);
+        }
+        if let Some(genre) = genre {
+            request = request.query(&[("genres", genre)]);
+        }
+        request = request
+            .query(&[
+                ("access", "playable,blocked"),
+                ("limit", "50"),
+                ("linked_partitioning", "true"),
+            ])
+            .query(&filters.query_params());
     }
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudTracks>().await?;
     Ok(body)
 }
 
+/// Tracks in a genre, newest SoundCloud matches first
+pub async fn browse_genre(
+    access_token: AccessToken,
+    genre: &str,
+    next_href: Option<String>,
+) -> Result<SoundCloudTracks, ApiError> {
+    search_tracks(
+        access_token,
+        "",
+        Some(genre),
+        &TrackFilters::default(),
+        next_href,
+    )
+    .await
+}
+
 pub async fn search_playlists(
     access_token: AccessToken,
     query: &str,
     next_href: Option<String>,
-) -> Result<SoundCloudPlaylists, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudPlaylists, ApiError> {
     let c = http_client();
 
     let url = next_href.unwrap_or_else(|| "https://api.soundcloud.com/playlists".to_string());
@@ -151,14 +310,7 @@ pub async fn search_playlists(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudPlaylists>().await?;
     Ok(body)
@@ -167,66 +319,269 @@ pub async fn search_playlists(
 pub async fn search_user(
     access_token: AccessToken,
     query: &str,
-) -> Result<Vec<SoundCloudUser>, Box<dyn std::error::Error + Send + Sync>> {
+    next_href: Option<String>,
+) -> Result<SoundCloudUsers, ApiError> {
     let c = http_client();
-    let response = c
-        .get("https://api.soundcloud.com/users")
-        .query(&[
+
+    let url = next_href.unwrap_or_else(|| "https://api.soundcloud.com/users".to_string());
+
+    let mut request = c.get(&url).bearer_auth(access_token.secret());
+
+    // Only add query parameters if using the default URL (not a pagination URL)
+    if !url.contains("?") {
+        request = request.query(&[
             ("q", query),
             ("access", "playable,blocked"),
             ("limit", "5"),
             ("linked_partitioning", "true"),
-        ])
-        .bearer_auth(access_token.secret())
-        .send()
-        .await?;
-
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
+        ]);
     }
 
+    let response = request.send().await?;
+
+    let response = check_status(response).await?;
+
     let body = response.json::<SoundCloudUsers>().await?;
-    Ok(body.collection)
+    Ok(body)
 }
 
 pub async fn search(
     access_token: AccessToken,
     query: &str,
-) -> Result<SearchResults, Box<dyn std::error::Error + Send + Sync>> {
+    filters: &TrackFilters,
+) -> Result<SearchResults, ApiError> {
     let (tracks, users, playlists) = try_join!(
-        search_tracks(access_token.clone(), query, None),
-        search_user(access_token.clone(), query),
+        search_tracks(access_token.clone(), query, None, filters, None),
+        search_user(access_token.clone(), query, None),
         search_playlists(access_token.clone(), query, None)
     )?;
     Ok(SearchResults {
         tracks: tracks.collection,
         tracks_next_href: tracks.next_href,
-        users,
+        users: users.collection,
+        users_next_href: users.next_href,
         playlists: playlists.collection,
         playlists_next_href: playlists.next_href,
     })
 }
 
-pub async fn like_track(
-    access_token: AccessToken,
-    track: SoundCloudTrack,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub async fn like_track(access_token: AccessToken, track: SoundCloudTrack) -> Result<(), ApiError> {
     let u = format!("https://api.soundcloud.com/likes/tracks/{}", track.id);
     let c = http_client();
-    c.post(u).bearer_auth(access_token.secret()).send().await?;
+    let response = c.post(u).bearer_auth(access_token.secret()).send().await?;
+    check_status(response).await?;
+
+    Ok(())
+}
+
+pub async fn unlike_track(access_token: AccessToken, track_id: u64) -> Result<(), ApiError> {
+    let u = format!("https://api.soundcloud.com/likes/tracks/{}", track_id);
+    let c = http_client();
+    let response = c
+        .delete(u)
+        .bearer_auth(access_token.secret())
+        .send()
+        .await?;
+    check_status(response).await?;
+
+    Ok(())
+}
+
+/// Creates a private playlist on the user's account holding the given tracks
+pub async fn create_playlist(
+    access_token: AccessToken,
+    title: String,
+    track_ids: Vec<u64>,
+) -> Result<SoundCloudPlaylist, ApiError> {
+    let tracks: Vec<_> = track_ids
+        .iter()
+        .map(|id| serde_json::json!({ "id": id }))
+        .collect();
+    let c = http_client();
+    let response = c
+        .post("https://api.soundcloud.com/playlists")
+        .bearer_auth(access_token.secret())
+        .json(&serde_json::json!({
+            "playlist": {
+                "title": title,
+                "sharing": "private",
+                "tracks": tracks,
+            }
+        }))
+        .send()
+        .await?;
+
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudPlaylist>().await?;
+    Ok(body)
+}
+
+/// Fetches the logged-in user's playlists, every page of them, without
+/// their track lists
+pub async fn get_my_playlists(access_token: AccessToken) -> Result<SoundCloudPlaylists, ApiError> {
+    let c = http_client();
+
+    let mut playlists = Vec::new();
+    let mut url = "https://api.soundcloud.com/me/playlists".to_string();
+    loop {
+        let mut request = c.get(&url).bearer_auth(access_token.secret());
+        if !url.contains("?") {
+            request = request.query(&[
+                ("show_tracks", "false"),
+                ("limit", "200"),
+                ("linked_partitioning", "true"),
+            ]);
+        }
+        let response = check_status(request.send().await?).await?;
+        let page = response.json::<SoundCloudPlaylists>().await?;
+        playlists.extend(page.collection);
+        match page.next_href {
+            Some(next_href) => url = next_href,
+            None => break,
+        }
+    }
+
+    Ok(SoundCloudPlaylists {
+        collection: playlists,
+        next_href: None,
+    })
+}
+
+/// Appends a track to one of the user's playlists. SoundCloud replaces the
+/// whole track list on update, so the current list is fetched first, every
+/// page and every access level, so nothing gets dropped. A track that's
+/// already in the playlist is left alone.
+pub async fn add_track_to_playlist(
+    access_token: AccessToken,
+    playlist_urn: String,
+    track_id: u64,
+) -> Result<(), ApiError> {
+    let c = http_client();
+
+    let mut track_ids = Vec::new();
+    let mut url = format!(
+        "https://api.soundcloud.com/playlists/{}/tracks",
+        playlist_urn
+    );
+    loop {
+        let mut request = c.get(&url).bearer_auth(access_token.secret());
+        if !url.contains("?") {
+            request = request.query(&[
+                ("access", "playable,preview,blocked"),
+                ("limit", "200"),
+                ("linked_partitioning", "true"),
+            ]);
+        }
+        let response = check_status(request.send().await?).await?;
+        let page = response.json::<SoundCloudTracks>().await?;
+        track_ids.extend(page.collection.iter().map(|t| t.id));
+        match page.next_href {
+            Some(next_href) => url = next_href,
+            None => break,
+        }
+    }
+
+    if track_ids.contains(&track_id) {
+        return Ok(());
+    }
+    track_ids.push(track_id);
+
+    let tracks: Vec<_> = track_ids
+        .iter()
+        .map(|id| serde_json::json!({ "id": id }))
+        .collect();
+    let response = c
+        .put(format!(
+            "https://api.soundcloud.com/playlists/{}",
+            playlist_urn
+        ))
+        .bearer_auth(access_token.secret())
+        .json(&serde_json::json!({ "playlist": { "tracks": tracks } }))
+        .send()
+        .await?;
+    check_status(response).await?;
 
     Ok(())
 }
 
+/// Fetches a track's full details, including its description and tags
+pub async fn get_track(
+    access_token: AccessToken,
+    track_id: u64,
+) -> Result<SoundCloudTrack, ApiError> {
+    let c = http_client();
+    let response = c
+        .get(format!("https://api.soundcloud.com/tracks/{}", track_id))
+        .bearer_auth(access_token.secret())
+        .send()
+        .await?;
+
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudTrack>().await?;
+    Ok(body)
+}
+
+/// A page of the comments left on a track, newest first
+pub async fn get_track_comments(
+    access_token: AccessToken,
+    track_id: u64,
+    next_href: Option<String>,
+) -> Result<SoundCloudComments, ApiError> {
+    let c = http_client();
+
+    let url = next_href
+        .unwrap_or_else(|| format!("https://api.soundcloud.com/tracks/{}/comments", track_id));
+
+    let mut request = c.get(&url).bearer_auth(access_token.secret());
+
+    // Only add query parameters if using the default URL (not a pagination URL)
+    if !url.contains("?") {
+        request = request.query(&[("limit", "50"), ("linked_partitioning", "true")]);
+    }
+
+    let response = request.send().await?;
+
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudComments>().await?;
+    Ok(body)
+}
+
+/// Comments on a track, pinned to `timestamp` ms into it when given
+pub async fn post_comment(
+    access_token: AccessToken,
+    track_id: u64,
+    body: String,
+    timestamp: Option<u64>,
+) -> Result<SoundCloudComment, ApiError> {
+    let c = http_client();
+    let response = c
+        .post(format!(
+            "https://api.soundcloud.com/tracks/{}/comments",
+            track_id
+        ))
+        .bearer_auth(access_token.secret())
+        .json(&serde_json::json!({
+            "comment": {
+                "body": body,
+                "timestamp": timestamp,
+            }
+        }))
+        .send()
+        .await?;
+
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudComment>().await?;
+    Ok(body)
+}
+
 pub async fn get_user(
     access_token: AccessToken,
     user_urn: String,
-) -> Result<SoundCloudUser, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudUser, ApiError> {
     let c = http_client();
     let response = c
         .get(format!("https://api.soundcloud.com/users/{}", user_urn))
@@ -234,24 +589,73 @@ pub async fn get_user(
         .send()
         .await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudUser>().await?;
+    Ok(body)
+}
+
+/// Fetches the logged-in user
+pub async fn get_me(access_token: AccessToken) -> Result<SoundCloudUser, ApiError> {
+    let c = http_client();
+    let response = c
+        .get("https://api.soundcloud.com/me")
+        .bearer_auth(access_token.secret())
+        .send()
+        .await?;
+
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudUser>().await?;
     Ok(body)
 }
 
+/// Fetches a page of the users the logged-in user follows
+pub async fn get_followings(
+    access_token: AccessToken,
+    next_href: Option<String>,
+) -> Result<SoundCloudUsers, ApiError> {
+    let url = next_href.unwrap_or_else(|| "https://api.soundcloud.com/me/followings".to_string());
+    get_users_page(access_token, url).await
+}
+
+/// Fetches a page of a user's followers
+pub async fn get_followers(
+    access_token: AccessToken,
+    user_urn: String,
+    next_href: Option<String>,
+) -> Result<SoundCloudUsers, ApiError> {
+    let url = next_href
+        .unwrap_or_else(|| format!("https://api.soundcloud.com/users/{}/followers", user_urn));
+    get_users_page(access_token, url).await
+}
+
+async fn get_users_page(
+    access_token: AccessToken,
+    url: String,
+) -> Result<SoundCloudUsers, ApiError> {
+    let c = http_client();
+
+    let mut request = c.get(&url).bearer_auth(access_token.secret());
+
+    // Only add query parameters if using the default URL (not a pagination URL)
+    if !url.contains("?") {
+        request = request.query(&[("limit", "50"), ("linked_partitioning", "true")]);
+    }
+
+    let response = request.send().await?;
+
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudUsers>().await?;
+    Ok(body)
+}
+
 pub async fn get_user_tracks(
     access_token: AccessToken,
     user_urn: String,
     next_href: Option<String>,
-) -> Result<SoundCloudTracks, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudTracks, ApiError> {
     let c = http_client();
 
     let url = next_href
@@ -270,14 +674,7 @@ pub async fn get_user_tracks(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudTracks>().await?;
     Ok(body)
@@ -287,7 +684,7 @@ pub async fn get_user_playlists(
     access_token: AccessToken,
     user_urn: String,
     next_href: Option<String>,
-) -> Result<SoundCloudPlaylists, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudPlaylists, ApiError> {
     let c = http_client();
 
     let url = next_href
@@ -306,14 +703,7 @@ pub async fn get_user_playlists(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudPlaylists>().await?;
     Ok(body)
@@ -322,7 +712,7 @@ pub async fn get_user_playlists(
 pub async fn get_user_profile(
     access_token: AccessToken,
     user_urn: String,
-) -> Result<SoundCloudUserProfile, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudUserProfile, ApiError> {
     let (user, tracks, playlists) = try_join!(
         get_user(access_token.clone(), user_urn.clone()),
         get_user_tracks(access_token.clone(), user_urn.clone(), None),
@@ -341,7 +731,7 @@ pub async fn get_playlist_tracks(
     access_token: AccessToken,
     playlist_urn: String,
     next_href: Option<String>,
-) -> Result<SoundCloudTracks, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudTracks, ApiError> {
     let c = http_client();
 
     let url = next_href.unwrap_or_else(|| {
@@ -364,14 +754,7 @@ pub async fn get_playlist_tracks(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudTracks>().await?;
     Ok(body)
@@ -381,7 +764,7 @@ pub async fn get_user_liked_tracks(
     access_token: AccessToken,
     user_urn: String,
     next_href: Option<String>,
-) -> Result<SoundCloudTracks, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudTracks, ApiError> {
     let c = http_client();
 
     let url = next_href
@@ -400,14 +783,7 @@ pub async fn get_user_liked_tracks(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudTracks>().await?;
     Ok(body)
@@ -417,7 +793,7 @@ pub async fn get_user_reposted_tracks(
     access_token: AccessToken,
     user_urn: String,
     next_href: Option<String>,
-) -> Result<SoundCloudTracks, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudTracks, ApiError> {
     let c = http_client();
 
     let url = next_href.unwrap_or_else(|| {
@@ -440,14 +816,33 @@ pub async fn get_user_reposted_tracks(
 
     let response = request.send().await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
+
+    let body = response.json::<SoundCloudTracks>().await?;
+    Ok(body)
+}
+
+/// Fetches tracks related to the given one, used to keep playing after the queue ends
+pub async fn get_related_tracks(
+    access_token: AccessToken,
+    track_id: u64,
+) -> Result<SoundCloudTracks, ApiError> {
+    let c = http_client();
+    let response = c
+        .get(format!(
+            "https://api.soundcloud.com/tracks/{}/related",
+            track_id
+        ))
+        .bearer_auth(access_token.secret())
+        .query(&[
+            ("access", "playable"),
+            ("limit", "20"),
+            ("linked_partitioning", "true"),
+        ])
+        .send()
+        .await?;
+
+    let response = check_status(response).await?;
 
     let body = response.json::<SoundCloudTracks>().await?;
     Ok(body)
@@ -457,7 +852,7 @@ pub async fn get_user_reposted_tracks(
 pub async fn get_track_streams(
     access_token: AccessToken,
     track_id: u64,
-) -> Result<SoundCloudStreams, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SoundCloudStreams, ApiError> {
     let client = http_client();
     let url = format!("https://api.soundcloud.com/tracks/{}/streams", track_id);
 
@@ -467,14 +862,7 @@ pub async fn get_track_streams(
         .send()
         .await?;
 
-    let status = response.status();
-    if !status.is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Failed to read error body".to_string());
-        return Err(format!("HTTP {} error fetching streams: {}", status, error_text).into());
-    }
+    let response = check_status(response).await?;
 
     let streams = response.json::<SoundCloudStreams>().await?;
     Ok(streams)
@@ -1177,6 +1565,59 @@ mod tests {
         out
     }
 
+    #[test]
+    fn error_statuses_map_to_kinds() {
+        let kind = |code| {
+            ApiError::from_status(reqwest::StatusCode::from_u16(code).unwrap(), String::new()).kind
+        };
+        assert_eq!(kind(401), ApiErrorKind::Auth);
+        assert_eq!(kind(403), ApiErrorKind::Auth);
+        assert_eq!(kind(404), ApiErrorKind::NotFound);
+        assert_eq!(kind(429), ApiErrorKind::RateLimited);
+        assert_eq!(kind(503), ApiErrorKind::Network);
+    }
+
+    #[test]
+    fn rate_limit_headers_parse_and_flag_a_low_quota() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(RateLimitInfo::from_headers(&headers), None);
+
+        headers.insert("x-ratelimit-remaining", "120".parse().unwrap());
+        headers.insert("x-ratelimit-limit", "1500".parse().unwrap());
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+        assert_eq!((info.remaining, info.limit), (120, 1500));
+        assert!(info.is_low());
+        assert!(
+            !RateLimitInfo {
+                remaining: 151,
+                limit: 1500
+            }
+            .is_low()
+        );
+    }
+
+    #[test]
+    fn error_bodies_yield_their_messages() {
+        assert_eq!(
+            error_message_from_body(r#"{"errors":[{"error_message":"404 - Not Found"}]}"#),
+            "404 - Not Found"
+        );
+        assert_eq!(
+            error_message_from_body(
+                r#"{"errors":[{"error_message":"bad limit"},{"error_message":"bad offset"}]}"#
+            ),
+            "bad limit; bad offset"
+        );
+        assert_eq!(
+            error_message_from_body(r#"{"code":401,"message":"invalid_token","errors":[]}"#),
+            "invalid_token"
+        );
+        assert_eq!(
+            error_message_from_body("<html>Bad Gateway</html>\n"),
+            "<html>Bad Gateway</html>"
+        );
+    }
+
     #[test]
     fn ts_demux_reassembles_frames_across_segment_split() {
         let mut adts_stream = Vec::new();
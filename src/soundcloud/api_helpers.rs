@@ -1,38 +1,57 @@
 use crate::models::{
-    SearchResults, SoundCloudActivityCollection, SoundCloudPlaylists, SoundCloudStreams,
-    SoundCloudTrack, SoundCloudTracks, SoundCloudUserProfile,
+    SearchResults, SoundCloudActivityCollection, SoundCloudComment, SoundCloudComments,
+    SoundCloudPlaylist, SoundCloudPlaylists, SoundCloudStreams, SoundCloudTrack, SoundCloudTracks,
+    SoundCloudUser, SoundCloudUserProfile, SoundCloudUsers, TrackFilters,
 };
-use crate::soundcloud::api;
+use crate::soundcloud::api::{self, ApiError, ApiErrorKind};
 use crate::soundcloud::auth::{AuthError, TokenManager};
+use oauth2::AccessToken;
+
+/// Turn an API failure into the error the pages see. Rejected tokens (401/403)
+/// become `AuthError::OAuth` so they take the re-auth path; `action` says what
+/// was being done, e.g. "loading liked tracks".
+fn api_error(action: &str, e: ApiError) -> AuthError {
+    match e.kind {
+        ApiErrorKind::Auth => AuthError::OAuth(format!("Authentication failed while {}", action)),
+        ApiErrorKind::RateLimited => {
+            AuthError::Other(format!("Rate limited while {}: {}", action, e))
+        }
+        _ => AuthError::Other(format!("Error while {}: {}", action, e)),
+    }
+}
+
+/// Run an API call with a fresh token. If the API rejects it anyway (revoked,
+/// or expired early), force a refresh and retry once before giving up.
+async fn with_token<T, F, Fut>(
+    token_manager: &mut TokenManager,
+    action: &str,
+    call: F,
+) -> Result<T, AuthError>
+where
+    F: Fn(AccessToken) -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    let token = token_manager.get_fresh_token().await?;
+    match call(token).await {
+        Err(e) if e.kind == ApiErrorKind::Auth => {
+            let token = token_manager.handle_auth_error().await?;
+            call(token).await.map_err(|e| api_error(action, e))
+        }
+        result => result.map_err(|e| api_error(action, e)),
+    }
+}
 
 /// Helper functions that combine token refresh with API calls for use with Iced Tasks
 pub async fn load_feed_paginated_with_refresh(
     mut token_manager: TokenManager,
     next_href: Option<String>,
 ) -> Result<(SoundCloudActivityCollection, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_activity_feed_paginated(token, next_href).await {
-            Ok(collection) => Ok((collection, token_manager)),
-            Err(e) => {
-                let error_msg = format!("{}", e);
-                if error_msg.contains("401")
-                    || error_msg.contains("403")
-                    || error_msg.contains("Unauthorized")
-                {
-                    Err((
-                        AuthError::OAuth(
-                            "Authentication failed while loading activity feed".to_string(),
-                        ),
-                        token_manager,
-                    ))
-                } else {
-                    Err((
-                        AuthError::Other(format!("Failed to load activity feed: {}", e)),
-                        token_manager,
-                    ))
-                }
-            }
-        },
+    match with_token(&mut token_manager, "loading the activity feed", |token| {
+        api::get_activity_feed_paginated(token, next_href.clone())
+    })
+    .await
+    {
+        Ok(collection) => Ok((collection, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -41,14 +60,12 @@ pub async fn load_favourites_paginated_with_refresh(
     mut token_manager: TokenManager,
     next_href: Option<String>,
 ) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_liked_tracks_paginated(token, next_href).await {
-            Ok(tracks) => Ok((tracks, token_manager)),
-            Err(_) => Err((
-                AuthError::Other("Failed to load liked tracks".to_string()),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading liked tracks", |token| {
+        api::get_liked_tracks_paginated(token, next_href.clone())
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -56,33 +73,14 @@ pub async fn load_favourites_paginated_with_refresh(
 pub async fn search_with_refresh(
     mut token_manager: TokenManager,
     query: String,
+    filters: TrackFilters,
 ) -> Result<(SearchResults, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::search(token, &query).await {
-            Ok(results) => Ok((results, token_manager)),
-            Err(e) => {
-                let error_msg = format!("{}", e);
-                if error_msg.contains("401")
-                    || error_msg.contains("403")
-                    || error_msg.contains("Unauthorized")
-                {
-                    Err((
-                        AuthError::OAuth("Authentication failed while searching".to_string()),
-                        token_manager,
-                    ))
-                } else if error_msg.contains("429") || error_msg.contains("Rate") {
-                    Err((
-                        AuthError::Other(format!("Rate limited while searching: {}", e)),
-                        token_manager,
-                    ))
-                } else {
-                    Err((
-                        AuthError::Other(format!("Failed to search: {}", e)),
-                        token_manager,
-                    ))
-                }
-            }
-        },
+    match with_token(&mut token_manager, "searching", |token| {
+        api::search(token, &query, &filters)
+    })
+    .await
+    {
+        Ok(results) => Ok((results, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -90,16 +88,30 @@ pub async fn search_with_refresh(
 pub async fn search_tracks_with_refresh(
     mut token_manager: TokenManager,
     query: String,
+    filters: TrackFilters,
     next_href: Option<String>,
 ) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::search_tracks(token, &query, next_href).await {
-            Ok(tracks) => Ok((tracks, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load more tracks: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading more tracks", |token| {
+        api::search_tracks(token, &query, None, &filters, next_href.clone())
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn browse_genre_with_refresh(
+    mut token_manager: TokenManager,
+    genre: String,
+    next_href: Option<String>,
+) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading the genre", |token| {
+        api::browse_genre(token, &genre, next_href.clone())
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -109,14 +121,27 @@ pub async fn search_playlists_with_refresh(
     query: String,
     next_href: Option<String>,
 ) -> Result<(SoundCloudPlaylists, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::search_playlists(token, &query, next_href).await {
-            Ok(playlists) => Ok((playlists, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load more playlists: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading more playlists", |token| {
+        api::search_playlists(token, &query, next_href.clone())
+    })
+    .await
+    {
+        Ok(playlists) => Ok((playlists, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn search_users_with_refresh(
+    mut token_manager: TokenManager,
+    query: String,
+    next_href: Option<String>,
+) -> Result<(SoundCloudUsers, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading more users", |token| {
+        api::search_user(token, &query, next_href.clone())
+    })
+    .await
+    {
+        Ok(users) => Ok((users, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -125,34 +150,50 @@ pub async fn load_user_profile_with_refresh(
     mut token_manager: TokenManager,
     user_urn: String,
 ) -> Result<(SoundCloudUserProfile, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_user_profile(token, user_urn).await {
-            Ok(results) => Ok((results, token_manager)),
-            Err(e) => {
-                let error_msg = format!("{}", e);
-                if error_msg.contains("401")
-                    || error_msg.contains("403")
-                    || error_msg.contains("Unauthorized")
-                {
-                    Err((
-                        AuthError::OAuth(
-                            "Authentication failed while loading user profile".to_string(),
-                        ),
-                        token_manager,
-                    ))
-                } else if error_msg.contains("429") || error_msg.contains("Rate") {
-                    Err((
-                        AuthError::Other(format!("Rate limited while loading user profile: {}", e)),
-                        token_manager,
-                    ))
-                } else {
-                    Err((
-                        AuthError::Other(format!("Failed to load user profile: {}", e)),
-                        token_manager,
-                    ))
-                }
-            }
-        },
+    match with_token(&mut token_manager, "loading the user profile", |token| {
+        api::get_user_profile(token, user_urn.clone())
+    })
+    .await
+    {
+        Ok(results) => Ok((results, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_me_with_refresh(
+    mut token_manager: TokenManager,
+) -> Result<(SoundCloudUser, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading your profile", api::get_me).await {
+        Ok(user) => Ok((user, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_followings_with_refresh(
+    mut token_manager: TokenManager,
+    next_href: Option<String>,
+) -> Result<(SoundCloudUsers, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading followings", |token| {
+        api::get_followings(token, next_href.clone())
+    })
+    .await
+    {
+        Ok(users) => Ok((users, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_followers_with_refresh(
+    mut token_manager: TokenManager,
+    user_urn: String,
+    next_href: Option<String>,
+) -> Result<(SoundCloudUsers, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading followers", |token| {
+        api::get_followers(token, user_urn.clone(), next_href.clone())
+    })
+    .await
+    {
+        Ok(users) => Ok((users, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -162,14 +203,12 @@ pub async fn get_playlist_tracks_with_refresh(
     playlist_urn: String,
     next_href: Option<String>,
 ) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_playlist_tracks(token, playlist_urn, next_href).await {
-            Ok(tracks) => Ok((tracks, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load playlist tracks: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading playlist tracks", |token| {
+        api::get_playlist_tracks(token, playlist_urn.clone(), next_href.clone())
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -179,14 +218,12 @@ pub async fn get_user_tracks_with_refresh(
     user_urn: String,
     next_href: Option<String>,
 ) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_user_tracks(token, user_urn, next_href).await {
-            Ok(tracks) => Ok((tracks, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load more user tracks: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading more user tracks", |token| {
+        api::get_user_tracks(token, user_urn.clone(), next_href.clone())
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -196,14 +233,12 @@ pub async fn get_user_liked_tracks_with_refresh(
     user_urn: String,
     next_href: Option<String>,
 ) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_user_liked_tracks(token, user_urn, next_href).await {
-            Ok(tracks) => Ok((tracks, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load user liked tracks: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading user liked tracks", |token| {
+        api::get_user_liked_tracks(token, user_urn.clone(), next_href.clone())
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -213,14 +248,14 @@ pub async fn get_user_reposted_tracks_with_refresh(
     user_urn: String,
     next_href: Option<String>,
 ) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_user_reposted_tracks(token, user_urn, next_href).await {
-            Ok(tracks) => Ok((tracks, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load user reposted tracks: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(
+        &mut token_manager,
+        "loading user reposted tracks",
+        |token| api::get_user_reposted_tracks(token, user_urn.clone(), next_href.clone()),
+    )
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -230,14 +265,71 @@ pub async fn get_user_playlists_with_refresh(
     user_urn: String,
     next_href: Option<String>,
 ) -> Result<(SoundCloudPlaylists, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_user_playlists(token, user_urn, next_href).await {
-            Ok(playlists) => Ok((playlists, token_manager)),
-            Err(e) => Err((
-                AuthError::Other(format!("Failed to load more user playlists: {}", e)),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "loading more user playlists", |token| {
+        api::get_user_playlists(token, user_urn.clone(), next_href.clone())
+    })
+    .await
+    {
+        Ok(playlists) => Ok((playlists, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_related_tracks_with_refresh(
+    mut token_manager: TokenManager,
+    track_id: u64,
+) -> Result<(SoundCloudTracks, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading related tracks", |token| {
+        api::get_related_tracks(token, track_id)
+    })
+    .await
+    {
+        Ok(tracks) => Ok((tracks, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_track_with_refresh(
+    mut token_manager: TokenManager,
+    track_id: u64,
+) -> Result<(SoundCloudTrack, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading track details", |token| {
+        api::get_track(token, track_id)
+    })
+    .await
+    {
+        Ok(track) => Ok((track, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_track_comments_with_refresh(
+    mut token_manager: TokenManager,
+    track_id: u64,
+    next_href: Option<String>,
+) -> Result<(SoundCloudComments, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "loading comments", |token| {
+        api::get_track_comments(token, track_id, next_href.clone())
+    })
+    .await
+    {
+        Ok(comments) => Ok((comments, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn post_comment_with_refresh(
+    mut token_manager: TokenManager,
+    track_id: u64,
+    body: String,
+    timestamp: Option<u64>,
+) -> Result<(SoundCloudComment, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "posting the comment", |token| {
+        api::post_comment(token, track_id, body.clone(), timestamp)
+    })
+    .await
+    {
+        Ok(comment) => Ok((comment, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -247,14 +339,71 @@ pub async fn like_track_with_refresh(
     track: SoundCloudTrack,
 ) -> Result<(u64, TokenManager), (AuthError, TokenManager)> {
     let track_id = track.id;
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::like_track(token, track).await {
-            Ok(_) => Ok((track_id, token_manager)),
-            Err(_) => Err((
-                AuthError::Other("Failed to like track".to_string()),
-                token_manager,
-            )),
-        },
+    match with_token(&mut token_manager, "liking the track", |token| {
+        api::like_track(token, track.clone())
+    })
+    .await
+    {
+        Ok(_) => Ok((track_id, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn unlike_track_with_refresh(
+    mut token_manager: TokenManager,
+    track_id: u64,
+) -> Result<(u64, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "unliking the track", |token| {
+        api::unlike_track(token, track_id)
+    })
+    .await
+    {
+        Ok(_) => Ok((track_id, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn create_playlist_with_refresh(
+    mut token_manager: TokenManager,
+    title: String,
+    track_ids: Vec<u64>,
+) -> Result<(SoundCloudPlaylist, TokenManager), (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "creating the playlist", |token| {
+        api::create_playlist(token, title.clone(), track_ids.clone())
+    })
+    .await
+    {
+        Ok(playlist) => Ok((playlist, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn get_my_playlists_with_refresh(
+    mut token_manager: TokenManager,
+) -> Result<(SoundCloudPlaylists, TokenManager), (AuthError, TokenManager)> {
+    match with_token(
+        &mut token_manager,
+        "loading your playlists",
+        api::get_my_playlists,
+    )
+    .await
+    {
+        Ok(playlists) => Ok((playlists, token_manager)),
+        Err(e) => Err((e, token_manager)),
+    }
+}
+
+pub async fn add_track_to_playlist_with_refresh(
+    mut token_manager: TokenManager,
+    playlist_urn: String,
+    track_id: u64,
+) -> Result<TokenManager, (AuthError, TokenManager)> {
+    match with_token(&mut token_manager, "adding to the playlist", |token| {
+        api::add_track_to_playlist(token, playlist_urn.clone(), track_id)
+    })
+    .await
+    {
+        Ok(_) => Ok(token_manager),
         Err(e) => Err((e, token_manager)),
     }
 }
@@ -263,29 +412,12 @@ pub async fn get_track_streams_with_refresh(
     mut token_manager: TokenManager,
     track_id: u64,
 ) -> Result<(SoundCloudStreams, TokenManager), (AuthError, TokenManager)> {
-    match token_manager.get_fresh_token().await {
-        Ok(token) => match api::get_track_streams(token, track_id).await {
-            Ok(streams) => Ok((streams, token_manager)),
-            Err(e) => {
-                let error_msg = format!("{}", e);
-                if error_msg.contains("401")
-                    || error_msg.contains("403")
-                    || error_msg.contains("Unauthorized")
-                {
-                    Err((
-                        AuthError::OAuth(
-                            "Authentication failed while fetching streams".to_string(),
-                        ),
-                        token_manager,
-                    ))
-                } else {
-                    Err((
-                        AuthError::Other(format!("Failed to get track streams: {}", e)),
-                        token_manager,
-                    ))
-                }
-            }
-        },
+    match with_token(&mut token_manager, "fetching streams", |token| {
+        api::get_track_streams(token, track_id)
+    })
+    .await
+    {
+        Ok(streams) => Ok((streams, token_manager)),
         Err(e) => Err((e, token_manager)),
     }
 }
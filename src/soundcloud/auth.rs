@@ -55,7 +55,6 @@ impl StoredToken {
     }
 }
 
-#[derive(Clone)]
 struct TokenStorage {
     file_path: PathBuf,
 }
@@ -124,7 +123,7 @@ impl TokenState {
 /// one clone is visible to all of them.
 #[derive(Clone)]
 pub struct TokenManager {
-    storage: TokenStorage,
+    storage: Arc<TokenStorage>, // Shared so clones don't copy the path
     state: Arc<Mutex<TokenState>>,
 }
 
@@ -142,7 +141,7 @@ impl std::fmt::Debug for TokenManager {
 impl TokenManager {
     fn from_stored(stored: StoredToken, storage: TokenStorage) -> Self {
         Self {
-            storage,
+            storage: Arc::new(storage),
             state: Arc::new(Mutex::new(TokenState {
                 access_token: AccessToken::new(stored.access_token),
                 refresh_token: stored.refresh_token.map(RefreshToken::new),
@@ -153,7 +152,7 @@ impl TokenManager {
 
     fn from_token_response(token: &TokenResp, storage: TokenStorage) -> Self {
         Self {
-            storage,
+            storage: Arc::new(storage),
             state: Arc::new(Mutex::new(TokenState {
                 access_token: token.access_token().clone(),
                 refresh_token: token.refresh_token().cloned(),
@@ -177,7 +176,29 @@ impl TokenManager {
             })?
         };
 
-        let new_token = refresh_access_token(&refresh_token).await?;
+        self.refresh(&refresh_token).await
+    }
+
+    /// Force a refresh after the API rejected the current token, e.g. when it
+    /// was revoked or expired earlier than `expires_at` said.
+    pub async fn handle_auth_error(&mut self) -> Result<AccessToken, AuthError> {
+        let refresh_token = self
+            .state
+            .lock()
+            .unwrap()
+            .refresh_token
+            .clone()
+            .ok_or_else(|| {
+                AuthError::OAuth(
+                    "Access token was rejected and no refresh token is available".to_string(),
+                )
+            })?;
+        warn!("Access token rejected by the API, refreshing");
+        self.refresh(&refresh_token).await
+    }
+
+    async fn refresh(&mut self, refresh_token: &RefreshToken) -> Result<AccessToken, AuthError> {
+        let new_token = refresh_access_token(refresh_token).await?;
         info!("Refreshed OAuth token");
         self.storage
             .save_token(&StoredToken::from_token_response(&new_token))?;
@@ -215,14 +236,15 @@ pub async fn try_cached_authentication() -> Option<TokenManager> {
 /// browser on the SoundCloud consent page and wait for the redirect back to a
 /// local listener.
 pub async fn authenticate_in_browser() -> Result<TokenManager, AuthError> {
+    let credentials = app_credentials()?;
     let storage = TokenStorage::new()?;
     info!("Starting OAuth2 authentication flow");
 
-    let client = BasicClient::new(ClientId::new(constants::CLIENT_ID.to_string()))
-        .set_client_secret(ClientSecret::new(constants::CLIENT_SECRET.to_string()))
+    let client = BasicClient::new(ClientId::new(credentials.client_id.clone()))
+        .set_client_secret(ClientSecret::new(credentials.client_secret.clone()))
         .set_auth_uri(AuthUrl::new(constants::SOUNDCLOUD_AUTH_URL.to_string())?)
         .set_token_uri(TokenUrl::new(constants::SOUNDCLOUD_TOKEN_URL.to_string())?)
-        .set_redirect_uri(RedirectUrl::new(constants::REDIRECT_URL.to_string())?);
+        .set_redirect_uri(RedirectUrl::new(credentials.redirect_url.clone())?);
 
     let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
 
@@ -233,7 +255,7 @@ pub async fn authenticate_in_browser() -> Result<TokenManager, AuthError> {
 
     // Bind before opening the browser so a busy port fails fast instead of
     // leaving the user on a dead consent page.
-    let listener = TcpListener::bind(redirect_listen_addr()?).await?;
+    let listener = TcpListener::bind(redirect_listen_addr(&credentials.redirect_url)?).await?;
 
     if let Err(e) = open::that_detached(auth_url.as_str()) {
         return Err(AuthError::Other(format!(
@@ -260,8 +282,8 @@ pub async fn authenticate_in_browser() -> Result<TokenManager, AuthError> {
 
     let token = client
         .exchange_code(code)
-        .add_extra_param("client_id", constants::CLIENT_ID.as_str())
-        .add_extra_param("client_secret", constants::CLIENT_SECRET.as_str())
+        .add_extra_param("client_id", credentials.client_id.as_str())
+        .add_extra_param("client_secret", credentials.client_secret.as_str())
         .set_pkce_verifier(pkce_verifier)
         .request_async(&http_client)
         .await
@@ -273,10 +295,17 @@ pub async fn authenticate_in_browser() -> Result<TokenManager, AuthError> {
     Ok(TokenManager::from_token_response(&token, storage))
 }
 
+/// The configured SoundCloud app credentials; AuthPage onboards the user
+/// before any sign-in is attempted, so missing ones are only an error here
+fn app_credentials() -> Result<constants::Credentials, AuthError> {
+    constants::credentials()
+        .ok_or_else(|| AuthError::Other("SoundCloud app credentials aren't set up".to_string()))
+}
+
 /// The local address the OAuth redirect listener binds to, derived from the
 /// configured redirect URL so the two can never disagree on the port.
-fn redirect_listen_addr() -> Result<String, AuthError> {
-    let url = Url::parse(constants::REDIRECT_URL.as_str())?;
+fn redirect_listen_addr(redirect_url: &str) -> Result<String, AuthError> {
+    let url = Url::parse(redirect_url)?;
     let port = url.port_or_known_default().unwrap_or(32857);
     Ok(format!("127.0.0.1:{}", port))
 }
@@ -374,8 +403,9 @@ async fn respond_html(stream: &mut TcpStream, heading: &str, message: &str) {
 }
 
 async fn refresh_access_token(refresh_token: &RefreshToken) -> Result<TokenResp, AuthError> {
-    let client = BasicClient::new(ClientId::new(constants::CLIENT_ID.to_string()))
-        .set_client_secret(ClientSecret::new(constants::CLIENT_SECRET.to_string()))
+    let credentials = app_credentials()?;
+    let client = BasicClient::new(ClientId::new(credentials.client_id.clone()))
+        .set_client_secret(ClientSecret::new(credentials.client_secret.clone()))
         .set_token_uri(TokenUrl::new(constants::SOUNDCLOUD_TOKEN_URL.to_string())?);
 
     let http_client = reqwest::ClientBuilder::new()
@@ -385,8 +415,8 @@ async fn refresh_access_token(refresh_token: &RefreshToken) -> Result<TokenResp,
 
     let token_result = client
         .exchange_refresh_token(refresh_token)
-        .add_extra_param("client_id", constants::CLIENT_ID.as_str())
-        .add_extra_param("client_secret", constants::CLIENT_SECRET.as_str())
+        .add_extra_param("client_id", credentials.client_id.as_str())
+        .add_extra_param("client_secret", credentials.client_secret.as_str())
         .request_async(&http_client)
         .await;
 
@@ -1,8 +1,15 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::Message;
 use crate::models::SoundCloudTrack;
 use ::image::load_from_memory;
+use futures::StreamExt;
+use iced::Task;
 use iced::widget::image::Handle;
+use iced::widget::svg;
 
 pub trait DurationFormat {
     fn format_as_mmss(&self) -> String;
@@ -30,12 +37,54 @@ pub fn truncate_string(s: impl AsRef<str>, max_len: usize) -> String {
     }
 }
 
+/// Artwork shown in place of an image that is missing or failed to download
+static PLACEHOLDER_IMAGE: LazyLock<Handle> = LazyLock::new(|| image_asset("assets/icon.png"));
+
+pub fn placeholder_image() -> Handle {
+    PLACEHOLDER_IMAGE.clone()
+}
+
 pub async fn download_image(url: &str) -> Result<Handle, Box<dyn std::error::Error + Send + Sync>> {
-    let response = reqwest::get(url).await?;
+    let response = reqwest::get(url).await?.error_for_status()?;
     let bytes = response.bytes().await?;
     Ok(Handle::from_bytes(bytes))
 }
 
+/// Downloads an image, retrying once, and falls back to the shared
+/// placeholder so a missing image still takes up its space
+pub async fn download_image_with_fallback(url: &str) -> Handle {
+    if url.is_empty() {
+        return placeholder_image();
+    }
+    match download_image(url).await {
+        Ok(handle) => handle,
+        Err(_) => download_image(url).await.unwrap_or_else(|e| {
+            tracing::debug!("Failed to load image {}: {}", url, e);
+            placeholder_image()
+        }),
+    }
+}
+
+/// Images a batch from `load_images_batched` downloads at once
+const IMAGE_BATCH_CONCURRENCY: usize = 6;
+
+/// Downloads a batch of images (key and URL pairs) in a single task, at most
+/// `IMAGE_BATCH_CONCURRENCY` at a time, handing each to `on_loaded` as soon
+/// as it arrives rather than in order
+pub fn load_images_batched<K, F>(images: Vec<(K, String)>, on_loaded: F) -> Task<Message>
+where
+    K: Send + 'static,
+    F: Fn(K, Handle) -> Message + Send + 'static,
+{
+    if images.is_empty() {
+        return Task::none();
+    }
+    let downloads = futures::stream::iter(images)
+        .map(|(key, url)| async move { (key, download_image_with_fallback(&url).await) })
+        .buffer_unordered(IMAGE_BATCH_CONCURRENCY);
+    Task::run(downloads, move |(key, handle)| on_loaded(key, handle))
+}
+
 /// Downloads waveform image and returns raw bytes for peak extraction
 pub async fn download_waveform_bytes(
     url: &str,
@@ -45,7 +94,8 @@ pub async fn download_waveform_bytes(
     Ok(bytes.to_vec())
 }
 
-/// Extract peak data from waveform PNG for canvas rendering
+/// Extract peak data from a waveform image (PNG, or WebP on some tracks) for
+/// canvas rendering
 pub fn extract_waveform_peaks(
     waveform_bytes: &[u8],
     target_width: usize,
@@ -99,15 +149,13 @@ pub fn extract_waveform_peaks(
     Ok(peaks)
 }
 
-pub fn get_track_queue(track_id: u64, tracks: Vec<SoundCloudTrack>) -> Vec<SoundCloudTrack> {
-    // We own `tracks`, so we can split it efficiently without extra allocations.
-    let mut tracks = tracks;
-    if let Some(pos) = tracks.iter().position(|t| t.id == track_id) {
+/// The tracks from `track_id` to the end of the list, or the whole list if
+/// the track isn't in it
+pub fn get_track_queue(track_id: u64, tracks: &[SoundCloudTrack]) -> &[SoundCloudTrack] {
+    match tracks.iter().position(|t| t.id == track_id) {
         // Keep from `pos` to the end (inclusive of the found track)
-        tracks.split_off(pos)
-    } else {
-        // If the track is not found, return an empty queue
-        Vec::new()
+        Some(pos) => &tracks[pos..],
+        None => tracks,
     }
 }
 
@@ -115,42 +163,34 @@ pub trait NumberFormat {
     fn format_compact_number(&self) -> String;
 }
 
+/// Compact form of `num` with one decimal at most, e.g. 1.2K or 3M. Picks the
+/// unit after rounding, so values just under a threshold roll over to the
+/// next unit (999,950 is 1M, not 1000K).
+fn format_compact(num: u64) -> String {
+    const UNITS: [(f64, &str); 3] = [(1_000.0, "K"), (1_000_000.0, "M"), (1_000_000_000.0, "B")];
+
+    if num < 1_000 {
+        return num.to_string();
+    }
+    let mut unit = 0;
+    let mut rounded = (num as f64 / UNITS[0].0 * 10.0).round() / 10.0;
+    while rounded >= 1_000.0 && unit + 1 < UNITS.len() {
+        unit += 1;
+        rounded = (num as f64 / UNITS[unit].0 * 10.0).round() / 10.0;
+    }
+    let formatted = format!("{:.1}", rounded)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string();
+    format!("{}{}", formatted, UNITS[unit].1)
+}
+
 macro_rules! impl_number_format {
     ($($t:ty),*) => {
         $(
             impl NumberFormat for $t {
                 fn format_compact_number(&self) -> String {
-                    let num = *self as u64;
-                    match num {
-                        n if n < 1_000 => n.to_string(),
-                        n if n < 1_000_000 => {
-                            let val = n as f64 / 1_000.0;
-                            if val.fract() == 0.0 {
-                                format!("{}K", val as u64)
-                            } else {
-                                let formatted = format!("{:.1}", val).trim_end_matches('0').trim_end_matches('.').to_string();
-                                format!("{}K", formatted)
-                            }
-                        }
-                        n if n < 1_000_000_000 => {
-                            let val = n as f64 / 1_000_000.0;
-                            if val.fract() == 0.0 {
-                                format!("{}M", val as u64)
-                            } else {
-                                let formatted = format!("{:.1}", val).trim_end_matches('0').trim_end_matches('.').to_string();
-                                format!("{}M", formatted)
-                            }
-                        }
-                        n => {
-                            let val = n as f64 / 1_000_000_000.0;
-                            if val.fract() == 0.0 {
-                                format!("{}B", val as u64)
-                            } else {
-                                let formatted = format!("{:.1}", val).trim_end_matches('0').trim_end_matches('.').to_string();
-                                format!("{}B", formatted)
-                            }
-                        }
-                    }
+                    format_compact(*self as u64)
                 }
             }
         )*
@@ -159,9 +199,169 @@ macro_rules! impl_number_format {
 
 impl_number_format!(u32, u64);
 
-/// Get the path to an asset file relative to the executable location
-/// This works both in development (cargo run) and in the app bundle
-pub fn get_asset_path(relative_path: &str) -> String {
+/// Parse a SoundCloud timestamp into Unix seconds. The API uses
+/// `2024/01/15 12:34:56 +0000`, and some endpoints ISO 8601
+/// (`2024-01-15T12:34:56Z`). Returns None for anything else.
+pub fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let s = timestamp.trim();
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !matches!(s.get(10..11)?, " " | "T") || !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+
+    // Zone suffix: "Z", "+0000", "-05:00", or nothing (UTC); fractional
+    // seconds before it are ignored
+    let zone = s[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match zone.trim() {
+        "" | "Z" => 0,
+        zone => {
+            let sign = match zone.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let digits = zone[1..].replace(':', "");
+            if digits.len() != 4 {
+                return None;
+            }
+            let hours = digits[..2].parse::<i64>().ok()?;
+            let minutes = digits[2..].parse::<i64>().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    // Days since the epoch for a proleptic Gregorian date (Howard Hinnant's
+    // days_from_civil)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Some(days * 86_400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Compact age of a Unix timestamp relative to `now`, e.g. "3h ago"
+pub fn format_time_ago(timestamp: i64, now: i64) -> String {
+    let seconds = (now - timestamp).max(0);
+    match seconds {
+        0..60 => "just now".to_string(),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        86_400..604_800 => format!("{}d ago", seconds / 86_400),
+        604_800..2_592_000 => format!("{}w ago", seconds / 604_800),
+        2_592_000..31_536_000 => format!("{}mo ago", seconds / 2_592_000),
+        _ => format!("{}y ago", seconds / 31_536_000),
+    }
+}
+
+/// Shuffle in place (Fisher-Yates) with a clock-seeded xorshift; plenty
+/// random for a play order
+pub fn shuffle<T>(items: &mut [T]) {
+    let mut state = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+        | 1;
+    for i in (1..items.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        items.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+/// Current time in Unix seconds
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Copies of the UI assets built into the binary, for packages that lay the
+/// assets dir out somewhere `find_asset` doesn't look
+fn bundled_asset(relative_path: &str) -> Option<&'static [u8]> {
+    Some(match relative_path {
+        "assets/feed.svg" => include_bytes!("../assets/feed.svg"),
+        "assets/heart.svg" => include_bytes!("../assets/heart.svg"),
+        "assets/icon.png" => include_bytes!("../assets/icon.png"),
+        "assets/link.svg" => include_bytes!("../assets/link.svg"),
+        "assets/moon.svg" => include_bytes!("../assets/moon.svg"),
+        "assets/next.svg" => include_bytes!("../assets/next.svg"),
+        "assets/pause.svg" => include_bytes!("../assets/pause.svg"),
+        "assets/play.svg" => include_bytes!("../assets/play.svg"),
+        "assets/playing.svg" => include_bytes!("../assets/playing.svg"),
+        "assets/previous.svg" => include_bytes!("../assets/previous.svg"),
+        "assets/repeat.svg" => include_bytes!("../assets/repeat.svg"),
+        "assets/repeat_one.svg" => include_bytes!("../assets/repeat_one.svg"),
+        "assets/repost.svg" => include_bytes!("../assets/repost.svg"),
+        "assets/search.svg" => include_bytes!("../assets/search.svg"),
+        "assets/settings.svg" => include_bytes!("../assets/settings.svg"),
+        "assets/sun.svg" => include_bytes!("../assets/sun.svg"),
+        _ => return None,
+    })
+}
+
+/// Bounds of the connected monitors in logical pixels, for checking a
+/// restored window position. Only Windows lets a window open where no
+/// monitor shows it; elsewhere this is empty and the window system decides
+/// (X11 window managers and macOS keep title bars reachable, Wayland ignores
+/// requested positions).
+pub fn monitor_bounds() -> Vec<iced::Rectangle> {
+    #[cfg(windows)]
+    return win32_monitor_bounds();
+    #[cfg(not(windows))]
+    Vec::new()
+}
+
+#[cfg(windows)]
+fn win32_monitor_bounds() -> Vec<iced::Rectangle> {
+    use windows_sys::Win32::Foundation::{BOOL, LPARAM, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{EnumDisplayMonitors, HDC, HMONITOR};
+    use windows_sys::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+
+    unsafe extern "system" fn collect(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        rect: *mut RECT,
+        bounds: LPARAM,
+    ) -> BOOL {
+        // SAFETY: `bounds` is the Vec passed to EnumDisplayMonitors below, and
+        // `rect` points at this monitor's bounds for the duration of the call
+        let (bounds, rect) = unsafe { (&mut *(bounds as *mut Vec<iced::Rectangle>), &*rect) };
+        let (mut dpi_x, mut dpi_y) = (96, 96);
+        // SAFETY: `monitor` comes from the enumeration; the out-params are locals
+        unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) };
+        // Monitor bounds are physical pixels; iced positions are logical
+        let scale = dpi_x.max(1) as f32 / 96.0;
+        bounds.push(iced::Rectangle::new(
+            iced::Point::new(rect.left as f32 / scale, rect.top as f32 / scale),
+            iced::Size::new(
+                (rect.right - rect.left) as f32 / scale,
+                (rect.bottom - rect.top) as f32 / scale,
+            ),
+        ));
+        1 // Keep enumerating
+    }
+
+    let mut bounds: Vec<iced::Rectangle> = Vec::new();
+    // SAFETY: `bounds` outlives the call, which invokes `collect` synchronously
+    unsafe {
+        EnumDisplayMonitors(
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            Some(collect),
+            &mut bounds as *mut _ as LPARAM,
+        )
+    };
+    bounds
+}
+
+/// Find an asset file on disk, relative to the executable location. This
+/// works both in development (cargo run) and in the app bundle.
+fn find_asset(relative_path: &str) -> Option<PathBuf> {
     if let Ok(exe_path) = std::env::current_exe()
         && let Some(exe_dir) = exe_path.parent()
     {
@@ -170,17 +370,153 @@ pub fn get_asset_path(relative_path: &str) -> String {
         if let Some(contents_dir) = exe_dir.parent() {
             let resources_path = contents_dir.join("Resources").join(relative_path);
             if resources_path.exists() {
-                return resources_path.to_string_lossy().to_string();
+                return Some(resources_path);
             }
         }
 
         // For other platforms or non-bundled macOS builds
         let asset_path = exe_dir.join(relative_path);
         if asset_path.exists() {
-            return asset_path.to_string_lossy().to_string();
+            return Some(asset_path);
         }
     }
 
-    // Fallback to relative path for development
-    relative_path.to_string()
+    // Relative to the working directory for development
+    let asset_path = PathBuf::from(relative_path);
+    asset_path.exists().then_some(asset_path)
+}
+
+/// Where an asset is loaded from, resolved once per path
+#[derive(Clone)]
+enum AssetSource {
+    File(PathBuf),
+    Bundled(&'static [u8]),
+    Missing,
+}
+
+static ASSET_SOURCES: LazyLock<Mutex<HashMap<String, AssetSource>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn asset_source(relative_path: &str) -> AssetSource {
+    ASSET_SOURCES
+        .lock()
+        .unwrap()
+        .entry(relative_path.to_string())
+        .or_insert_with(|| {
+            if let Some(path) = find_asset(relative_path) {
+                AssetSource::File(path)
+            } else if let Some(bytes) = bundled_asset(relative_path) {
+                AssetSource::Bundled(bytes)
+            } else {
+                // Cached, so this is only reported the first time
+                tracing::warn!("Asset {} not found, it won't be shown", relative_path);
+                AssetSource::Missing
+            }
+        })
+        .clone()
+}
+
+/// An SVG from the assets dir, falling back to the copy built into the binary
+pub fn svg_asset(relative_path: &str) -> svg::Handle {
+    match asset_source(relative_path) {
+        AssetSource::File(path) => svg::Handle::from_path(path),
+        AssetSource::Bundled(bytes) => svg::Handle::from_memory(bytes),
+        AssetSource::Missing => svg::Handle::from_memory(&[]),
+    }
+}
+
+/// An image from the assets dir, falling back to the copy built into the binary
+pub fn image_asset(relative_path: &str) -> Handle {
+    match asset_source(relative_path) {
+        AssetSource::File(path) => Handle::from_path(path),
+        AssetSource::Bundled(bytes) => Handle::from_bytes(bytes),
+        AssetSource::Missing => Handle::from_bytes(&[][..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shuffle_keeps_every_item() {
+        let mut items: Vec<u32> = (0..50).collect();
+        shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn assets_fall_back_to_bundled_copies() {
+        assert!(matches!(
+            asset_source("assets/play.svg"),
+            AssetSource::File(_) | AssetSource::Bundled(_)
+        ));
+        assert!(bundled_asset("assets/repeat_one.svg").is_some_and(|b| !b.is_empty()));
+        assert!(matches!(
+            asset_source("assets/does_not_exist.svg"),
+            AssetSource::Missing
+        ));
+    }
+
+    #[test]
+    fn decodes_webp_images() {
+        use ::image::{ExtendedColorType, ImageEncoder, codecs::webp::WebPEncoder};
+
+        let (width, height) = (8u32, 4u32);
+        let pixels: Vec<u8> = (0..width * height)
+            .flat_map(|i| [(i * 8) as u8, 64, 128, 255])
+            .collect();
+        let mut webp = Vec::new();
+        WebPEncoder::new_lossless(&mut webp)
+            .write_image(&pixels, width, height, ExtendedColorType::Rgba8)
+            .unwrap();
+
+        let decoded = load_from_memory(&webp).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), (width, height));
+        assert_eq!(decoded.as_raw(), &pixels);
+        assert_eq!(extract_waveform_peaks(&webp, 4).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn compact_numbers_roll_over_at_unit_boundaries() {
+        assert_eq!(999u64.format_compact_number(), "999");
+        assert_eq!(1_000u64.format_compact_number(), "1K");
+        assert_eq!(1_250u32.format_compact_number(), "1.3K");
+        assert_eq!(999_949u64.format_compact_number(), "999.9K");
+        assert_eq!(999_950u64.format_compact_number(), "1M");
+        assert_eq!(999_999u64.format_compact_number(), "1M");
+        assert_eq!(1_000_000u64.format_compact_number(), "1M");
+        assert_eq!(999_950_000u64.format_compact_number(), "1B");
+        assert_eq!(2_500_000_000u64.format_compact_number(), "2.5B");
+    }
+
+    #[test]
+    fn parses_soundcloud_and_iso_timestamps() {
+        assert_eq!(parse_timestamp("1970/01/01 00:00:00 +0000"), Some(0));
+        assert_eq!(
+            parse_timestamp("2024/03/01 12:00:00 +0000"),
+            Some(1_709_294_400)
+        );
+        assert_eq!(
+            parse_timestamp("2024-03-01T12:00:00Z"),
+            parse_timestamp("2024/03/01 12:00:00 +0000")
+        );
+        // 07:00 at -05:00 is noon UTC
+        assert_eq!(
+            parse_timestamp("2024-03-01T07:00:00.123-05:00"),
+            Some(1_709_294_400)
+        );
+        assert_eq!(parse_timestamp(""), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn time_ago_picks_the_largest_unit() {
+        let now = 1_000_000_000;
+        assert_eq!(format_time_ago(now - 5, now), "just now");
+        assert_eq!(format_time_ago(now - 3 * 3_600 - 59, now), "3h ago");
+        assert_eq!(format_time_ago(now - 2 * 86_400, now), "2d ago");
+        assert_eq!(format_time_ago(now + 60, now), "just now", "clock skew");
+    }
 }
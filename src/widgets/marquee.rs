@@ -0,0 +1,46 @@
+/// Longest text, in characters, the playback bar shows without scrolling
+pub const MARQUEE_MAX_CHARS: usize = 48;
+
+/// Gap shown between the end of a scrolling text and its next pass
+const MARQUEE_GAP: &str = "   •   ";
+
+/// A `max_chars` window into `text` that has scrolled `offset` characters,
+/// wrapping around with a gap. Text that fits is returned unchanged.
+pub fn marquee_text(text: &str, max_chars: usize, offset: usize) -> String {
+    let len = text.chars().count();
+    if len <= max_chars {
+        return text.to_string();
+    }
+
+    let cycle = len + MARQUEE_GAP.chars().count();
+    text.chars()
+        .chain(MARQUEE_GAP.chars())
+        .cycle()
+        .skip(offset % cycle)
+        .take(max_chars)
+        .collect()
+}
+
+/// Whether `text` is too long to show in full and needs to scroll
+pub fn marquee_overflows(text: &str, max_chars: usize) -> bool {
+    text.chars().count() > max_chars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_stays_put_and_long_text_wraps_around() {
+        assert_eq!(marquee_text("Short", 10, 3), "Short");
+
+        assert_eq!(marquee_text("abcdefghijkl", 5, 0), "abcde");
+        assert_eq!(marquee_text("abcdefghijkl", 5, 10), "kl   ");
+        assert_eq!(marquee_text("abcdefghijkl", 5, 15), "•   a");
+        // One full pass is the text plus the gap
+        assert_eq!(
+            marquee_text("abcdefghijkl", 5, 19),
+            marquee_text("abcdefghijkl", 5, 0)
+        );
+    }
+}
@@ -1,21 +1,43 @@
+mod marquee;
 mod playback_bar;
+mod playlist_picker;
 mod playlist_widget;
+mod restore_queue_prompt;
 mod section;
+mod skeleton;
+mod sort_control;
 mod spinner;
+mod toast;
 mod track_widget;
 mod user_widget;
+mod visualizer;
 mod waveform_widget;
 
+pub use marquee::{MARQUEE_MAX_CHARS, marquee_overflows, marquee_text};
 pub use playback_bar::get_playback_bar;
+pub use playlist_picker::playlist_picker;
 pub use playlist_widget::get_playlist_widget;
+pub use restore_queue_prompt::restore_queue_prompt;
 pub use section::{empty_state, loading_state, section};
+pub use skeleton::track_list_skeleton;
+pub use sort_control::sort_control;
 pub use spinner::spinner;
-pub use track_widget::get_track_widget;
-pub use user_widget::get_user_widget;
+pub use toast::toast;
+pub use track_widget::{copy_link_button, get_track_widget, play_all_buttons};
+pub use user_widget::{get_user_widget, user_badges};
+pub use visualizer::get_visualizer_widget;
 pub use waveform_widget::get_waveform_widget;
 
 use iced::Theme;
-use iced::widget::scrollable;
+use iced::widget::{scrollable, svg};
+
+/// Icon colour for SVGs on primary buttons, matching the button's text so
+/// they stay legible in both light and dark themes
+pub fn button_icon_style(theme: &Theme, _status: svg::Status) -> svg::Style {
+    svg::Style {
+        color: Some(theme.extended_palette().primary.base.text),
+    }
+}
 
 /// Shared scrollbar style: an accent-coloured scroller that pops against the
 /// dark theme, reusing the framework defaults for everything else.
@@ -1,15 +1,25 @@
 use crate::Message;
 use crate::config;
-use crate::utilities::{DurationFormat, get_asset_path};
+use crate::managers::PlayerState;
+use crate::models::SoundCloudTrack;
+use crate::utilities::{DurationFormat, NumberFormat, svg_asset};
 use crate::widgets;
+use iced::mouse::ScrollDelta;
 use iced::widget::image::Handle;
 use iced::{
-    Color, Element, Length,
+    Element, Length,
     alignment::Vertical,
-    widget::{Space, Svg, button, column, container, image, row, rule, slider, svg, text, tooltip},
+    widget::{
+        Space, Svg, Text, button, column, container, image, mouse_area, row, rule, slider, svg,
+        text, text_input, tooltip,
+    },
 };
 use std::time::Duration;
 
+// Trackpads report scrolling in pixels, many small events per gesture; this
+// many pixels count as one wheel notch
+const PIXELS_PER_NOTCH: f32 = 50.0;
+
 /// Wraps a control in a labelled tooltip shown after a short hover delay.
 fn tip<'a>(content: impl Into<Element<'a, Message>>, label: &'a str) -> Element<'a, Message> {
     tooltip(content, text(label), tooltip::Position::Top)
@@ -20,6 +30,65 @@ fn tip<'a>(content: impl Into<Element<'a, Message>>, label: &'a str) -> Element<
         .into()
 }
 
+/// Makes `content` clickable when there's somewhere for it to lead
+fn link<'a>(content: Text<'a>, on_press: Option<Message>) -> Element<'a, Message> {
+    match on_press {
+        Some(message) => mouse_area(content)
+            .on_press(message)
+            .interaction(iced::mouse::Interaction::Pointer)
+            .into(),
+        None => content.into(),
+    }
+}
+
+/// One compact line of plays, likes (a button that likes or unlikes the
+/// track, red while liked) and when it was posted
+fn track_stats<'a>(track: Option<&SoundCloudTrack>, liked: bool) -> Element<'a, Message> {
+    let Some(track) = track else {
+        return Space::new().into();
+    };
+    let posted = crate::utilities::parse_timestamp(&track.created_at)
+        .map(|time| crate::utilities::format_time_ago(time, crate::utilities::unix_now()));
+
+    let mut stats = row![
+        text(format!(
+            "{} plays",
+            track.playback_count.unwrap_or(0).format_compact_number()
+        ))
+        .size(13)
+        .style(text::secondary),
+        tip(
+            button(
+                row![
+                    Svg::new(svg_asset("assets/heart.svg"))
+                        .width(13)
+                        .height(13)
+                        .style(move |theme: &iced::Theme, _status| svg::Style {
+                            color: Some(if liked {
+                                theme.palette().danger
+                            } else {
+                                theme.extended_palette().secondary.base.text
+                            }),
+                        }),
+                    text(track.favoritings_count.unwrap_or(0).format_compact_number()).size(13),
+                ]
+                .spacing(4)
+                .align_y(Vertical::Center),
+            )
+            .padding([1, 6])
+            .style(button::secondary)
+            .on_press(Message::LikeCurrentTrack),
+            if liked { "Unlike" } else { "Like" },
+        ),
+    ]
+    .spacing(8)
+    .align_y(Vertical::Center);
+    if let Some(posted) = posted {
+        stats = stats.push(text(posted).size(13).style(text::secondary));
+    }
+    stats.into()
+}
+
 /// Renders the playback control bar with album art, track info, and controls
 #[allow(clippy::too_many_arguments)]
 pub fn get_playback_bar<'a>(
@@ -27,23 +96,39 @@ pub fn get_playback_bar<'a>(
     artwork_opacity: f32,
     title: &'a str,
     user: &'a str,
+    track: Option<&'a SoundCloudTrack>, // Current queue track, for its stats and liking
+    liked: bool,
     track_position: Duration,
     track_duration: Duration,
     progress_bar_value: f32,
-    stream_loading: bool,
-    is_playing: bool,
+    player_state: PlayerState,
     current_position: Option<usize>,
     queue_length: usize,
-    waveform_peaks: Option<Vec<f32>>,
+    waveform_peaks: Option<&'a [f32]>,
+    spectrum_bars: &[f32],
+    playlist_title: Option<&'a str>,
+    me_avatar: Option<Handle>,
+    volume: f32,
+    marquee_offset: usize,
     settings: &config::AppSettings,
 ) -> iced::Element<'a, Message> {
-    let album_image = if let Some(handle) = artwork {
-        image(handle).width(100).height(100)
-    } else {
-        image("placeholder.png").width(100).height(100)
-    }
-    .opacity(artwork_opacity)
-    .scale(0.85 + 0.15 * artwork_opacity);
+    let album_image = image(artwork.unwrap_or_else(crate::utilities::placeholder_image))
+        .width(100)
+        .height(100)
+        .opacity(artwork_opacity)
+        .scale(0.85 + 0.15 * artwork_opacity);
+
+    // Wheel over the artwork nudges the volume one step per notch
+    let volume_step = settings.volume_step.amount();
+    let album_image = mouse_area(album_image).on_scroll(move |delta| {
+        let notches = match delta {
+            ScrollDelta::Lines { y, .. } => y,
+            ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_NOTCH,
+        };
+        Message::SetVolume(volume + notches * volume_step)
+    });
+
+    let repeat_off = settings.repeat_mode == config::RepeatMode::Off;
 
     let queue_text = if let Some(current_pos) = current_position {
         text(format!("Queue: {} of {}", current_pos + 1, queue_length))
@@ -51,22 +136,90 @@ pub fn get_playback_bar<'a>(
         text("Queue: Empty")
     };
 
+    // Profile button: the logged-in user's avatar, or a placeholder until it loads
+    let me_icon: Element<'a, Message> = match me_avatar {
+        Some(handle) => image(handle).width(22).height(22).border_radius(11).into(),
+        None => text("Me").size(12).into(),
+    };
+
+    // "Save queue" opens an inline prompt for the new playlist's title
+    let save_queue: Element<'a, Message> = match playlist_title {
+        Some(title) => row![
+            text_input("Playlist title", title)
+                .on_input(Message::PlaylistTitleChanged)
+                .on_submit(Message::SaveQueueAsPlaylist(title.to_string()))
+                .width(180),
+            button(text("Save")).on_press(Message::SaveQueueAsPlaylist(title.to_string())),
+            button(text("Cancel"))
+                .style(button::secondary)
+                .on_press(Message::CancelSaveQueue),
+        ]
+        .spacing(5)
+        .into(),
+        None if queue_length > 0 => button(text("Save queue").size(14))
+            .style(button::text)
+            .padding(0)
+            .on_press(Message::PromptSaveQueue)
+            .into(),
+        None => Space::new().into(),
+    };
+
     column![
         container(row![
             album_image,
             column![
                 text("Playback").size(24),
-                if stream_loading {
-                    text("Loading stream...")
-                } else {
-                    text(format!("Now Playing: {}", title)).shaping(text::Shaping::Auto)
-                },
-                text(format!("User: {}", user)).shaping(text::Shaping::Auto),
-                text(format!(
+                // Hovering pauses long titles that scroll
+                mouse_area(column![
+                    if player_state == PlayerState::Loading {
+                        text("Loading stream...").into()
+                    } else {
+                        link(
+                            text(format!(
+                                "{}: {}",
+                                if player_state == PlayerState::Paused {
+                                    "Paused"
+                                } else {
+                                    "Now Playing"
+                                },
+                                widgets::marquee_text(
+                                    title,
+                                    widgets::MARQUEE_MAX_CHARS,
+                                    marquee_offset
+                                )
+                            ))
+                            .shaping(text::Shaping::Auto),
+                            track.map(|track| Message::ShowTrackDetails(track.clone())),
+                        )
+                    },
+                    link(
+                        text(format!(
+                            "User: {}",
+                            widgets::marquee_text(user, widgets::MARQUEE_MAX_CHARS, marquee_offset)
+                        ))
+                        .shaping(text::Shaping::Auto),
+                        track.map(|_| Message::NavigateToCurrentArtist),
+                    ),
+                ])
+                .on_enter(Message::MarqueeHovered(true))
+                .on_exit(Message::MarqueeHovered(false)),
+                mouse_area(text(format!(
                     "{} / {}",
                     track_position.format_as_mmss(),
-                    track_duration.format_as_mmss()
-                )),
+                    if settings.show_remaining {
+                        format!(
+                            "-{}",
+                            track_duration
+                                .saturating_sub(track_position)
+                                .format_as_mmss()
+                        )
+                    } else {
+                        track_duration.format_as_mmss()
+                    }
+                )))
+                .on_press(Message::ToggleTimeDisplay)
+                .interaction(iced::mouse::Interaction::Pointer),
+                track_stats(track, liked),
             ]
             .padding(5),
             Space::new().width(Length::Fill),
@@ -75,102 +228,149 @@ pub fn get_playback_bar<'a>(
                     row![
                         tip(
                             button(
-                                Svg::new(get_asset_path("assets/previous.svg"))
+                                Svg::new(svg_asset("assets/previous.svg"))
                                     .width(22)
                                     .height(22)
-                                    .style(|_theme, _status| svg::Style {
-                                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                    }),
+                                    .style(widgets::button_icon_style),
                             )
                             .on_press(Message::PreviousTrack),
                             "Previous track",
                         ),
                         tip(
                             button(
-                                Svg::new(get_asset_path(if is_playing {
-                                    "assets/pause.svg"
-                                } else {
-                                    "assets/play.svg"
+                                Svg::new(svg_asset(match player_state {
+                                    PlayerState::Playing | PlayerState::Loading => {
+                                        "assets/pause.svg"
+                                    }
+                                    PlayerState::Idle | PlayerState::Paused => "assets/play.svg",
                                 }))
                                 .width(22)
                                 .height(22)
-                                .style(|_theme, _status| svg::Style {
-                                    color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                }),
+                                .style(widgets::button_icon_style),
                             )
                             .on_press(Message::PlayPausePlayback),
-                            if is_playing { "Pause" } else { "Play" },
+                            match player_state {
+                                PlayerState::Playing => "Pause",
+                                PlayerState::Loading => "Loading...",
+                                PlayerState::Idle | PlayerState::Paused => "Play",
+                            },
                         ),
                         tip(
                             button(
-                                Svg::new(get_asset_path("assets/next.svg"))
+                                Svg::new(svg_asset("assets/next.svg"))
                                     .width(22)
                                     .height(22)
-                                    .style(|_theme, _status| svg::Style {
-                                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                    }),
+                                    .style(widgets::button_icon_style),
                             )
                             .on_press(Message::NextTrack),
                             "Next track",
                         ),
                         tip(
                             button(
-                                Svg::new(get_asset_path(match settings.repeat_mode {
-                                    config::RepeatMode::All => "assets/repeat.svg",
+                                Svg::new(svg_asset(match settings.repeat_mode {
+                                    config::RepeatMode::All | config::RepeatMode::Off => {
+                                        "assets/repeat.svg"
+                                    }
                                     config::RepeatMode::One => "assets/repeat_one.svg",
                                 }))
                                 .width(22)
                                 .height(22)
-                                .style(|_theme, _status| svg::Style {
-                                    color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                }),
+                                .style(
+                                    move |theme: &iced::Theme, status| {
+                                        let style = widgets::button_icon_style(theme, status);
+                                        // Dimmed while repeat is off
+                                        svg::Style {
+                                            color: style.color.map(|color| {
+                                                if repeat_off {
+                                                    color.scale_alpha(0.45)
+                                                } else {
+                                                    color
+                                                }
+                                            }),
+                                        }
+                                    }
+                                ),
                             )
                             .on_press(Message::ToggleRepeatMode),
                             match settings.repeat_mode {
                                 config::RepeatMode::All => "Repeat: all",
                                 config::RepeatMode::One => "Repeat: one",
+                                config::RepeatMode::Off => "Repeat: off",
                             },
                         ),
+                        tip(
+                            button(text("+").size(18).center().width(22).height(22))
+                                .padding([5, 10])
+                                .on_press(Message::AddCurrentToPlaylist),
+                            "Add to playlist",
+                        ),
                     ]
                     .spacing(5),
-                    queue_text,
+                    row![queue_text, save_queue]
+                        .spacing(10)
+                        .align_y(Vertical::Center),
                     row![
                         tip(
                             button(
-                                Svg::new(get_asset_path("assets/feed.svg"))
+                                Svg::new(svg_asset("assets/feed.svg"))
                                     .width(22)
                                     .height(22)
-                                    .style(|_theme, _status| svg::Style {
-                                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                    }),
+                                    .style(widgets::button_icon_style),
                             )
                             .on_press(Message::NavigateToFeed),
                             "Feed",
                         ),
                         tip(
                             button(
-                                Svg::new(get_asset_path("assets/heart.svg"))
+                                Svg::new(svg_asset("assets/heart.svg"))
                                     .width(22)
                                     .height(22)
-                                    .style(|_theme, _status| svg::Style {
-                                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                    }),
+                                    .style(widgets::button_icon_style),
                             )
                             .on_press(Message::NavigateToLikes),
                             "Likes",
                         ),
                         tip(
                             button(
-                                Svg::new(get_asset_path("assets/search.svg"))
+                                Svg::new(svg_asset("assets/search.svg"))
                                     .width(22)
                                     .height(22)
-                                    .style(|_theme, _status| svg::Style {
-                                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                                    }),
+                                    .style(widgets::button_icon_style),
                             )
                             .on_press(Message::NavigateToSearch),
                             "Search",
                         ),
+                        tip(
+                            button(
+                                Svg::new(svg_asset("assets/settings.svg"))
+                                    .width(22)
+                                    .height(22)
+                                    .style(widgets::button_icon_style),
+                            )
+                            .on_press(Message::NavigateToSettings),
+                            "Settings",
+                        ),
+                        tip(
+                            button(
+                                Svg::new(svg_asset(match settings.theme {
+                                    config::AppTheme::Dark => "assets/sun.svg",
+                                    config::AppTheme::Light => "assets/moon.svg",
+                                }))
+                                .width(22)
+                                .height(22)
+                                .style(widgets::button_icon_style),
+                            )
+                            .on_press(Message::ToggleTheme),
+                            match settings.theme {
+                                config::AppTheme::Dark => "Light theme",
+                                config::AppTheme::Light => "Dark theme",
+                            },
+                        ),
+                        tip(
+                            button(container(me_icon).center_x(22).center_y(22),)
+                                .on_press(Message::NavigateToMe),
+                            "Your profile",
+                        ),
                     ]
                     .spacing(5),
                 ]
@@ -180,18 +380,22 @@ pub fn get_playback_bar<'a>(
         ],)
         .align_y(Vertical::Center),
         rule::horizontal(5.0),
-        if matches!(settings.seekbar_type, config::SeekbarType::Slider) {
-            row![
-                slider(0.0..=100.0, progress_bar_value, Message::SeekToPosition)
+        match settings.seekbar_type {
+            config::SeekbarType::Slider => row![
+                slider(0.0..=100.0, progress_bar_value, Message::Scrub)
+                    .on_release(Message::ScrubRelease)
                     .width(Length::Fill)
                     .step(0.1),
             ]
-            .padding(5)
-        } else {
-            row![widgets::get_waveform_widget(
+            .padding(5),
+            config::SeekbarType::Waveform => row![widgets::get_waveform_widget(
                 waveform_peaks,
                 progress_bar_value / 100.0,
-            ),]
+            ),],
+            config::SeekbarType::Spectrum => row![widgets::get_visualizer_widget(
+                spectrum_bars,
+                progress_bar_value / 100.0,
+            ),],
         },
         rule::horizontal(5.0),
     ]
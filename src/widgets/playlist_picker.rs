@@ -0,0 +1,67 @@
+use crate::Message;
+use crate::models::SoundCloudPlaylist;
+use crate::widgets::spinner;
+use iced::widget::{Scrollable, button, column, container, row, text};
+use iced::{Alignment, Element, Length, Shadow, Theme, Vector, border};
+
+/// Popup listing the user's playlists to add a track to. `playlists` is None
+/// while they're still loading. MyApp floats it over the page.
+pub fn playlist_picker<'a>(
+    track_title: &'a str,
+    playlists: Option<&'a [SoundCloudPlaylist]>,
+) -> Element<'a, Message> {
+    let body: Element<'a, Message> = match playlists {
+        None => container(spinner(24.0))
+            .center_x(Length::Fill)
+            .padding(12)
+            .into(),
+        Some([]) => text("You don't have any playlists yet").size(14).into(),
+        Some(playlists) => Scrollable::new(playlists.iter().fold(
+            column![].spacing(2),
+            |col, playlist| {
+                col.push(
+                    button(text(playlist.title.as_str()).shaping(text::Shaping::Auto))
+                        .style(button::text)
+                        .width(Length::Fill)
+                        .on_press(Message::AddTrackToPlaylist(playlist.clone())),
+                )
+            },
+        ))
+        .style(crate::widgets::scrollbar_style)
+        .height(Length::Shrink)
+        .into(),
+    };
+
+    container(
+        column![
+            text(format!("Add \"{}\" to playlist", track_title))
+                .size(16)
+                .shaping(text::Shaping::Auto),
+            container(body).max_height(320),
+            row![
+                button(text("Cancel"))
+                    .style(button::secondary)
+                    .on_press(Message::ClosePlaylistPicker),
+            ]
+            .align_y(Alignment::Center),
+        ]
+        .spacing(10),
+    )
+    .padding(16)
+    .width(360)
+    .style(|theme: &Theme| {
+        let palette = theme.extended_palette();
+        container::Style {
+            background: Some(palette.background.strong.color.into()),
+            text_color: Some(palette.background.strong.text),
+            border: border::rounded(12),
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                offset: Vector::new(0.0, 2.0),
+                blur_radius: 12.0,
+            },
+            ..container::Style::default()
+        }
+    })
+    .into()
+}
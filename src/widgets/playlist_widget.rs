@@ -1,6 +1,7 @@
 use crate::Message;
 use crate::models::SoundCloudPlaylist;
-use crate::utilities::{NumberFormat, get_asset_path};
+use crate::utilities::{NumberFormat, placeholder_image};
+use crate::widgets::copy_link_button;
 use iced::widget::{MouseArea, Row, mouse_area, text};
 use iced::widget::{column, container, image, image::Handle};
 
@@ -30,11 +31,7 @@ where
     if let Some(handle) = image_handle {
         row = row.push(image(handle).width(100).height(100));
     } else {
-        row = row.push(
-            image(get_asset_path("assets/icon.png"))
-                .width(100)
-                .height(100),
-        );
+        row = row.push(image(placeholder_image()).width(100).height(100));
     }
 
     row = row.push(
@@ -47,6 +44,7 @@ where
                 playlist.track_count.unwrap_or(0).format_compact_number()
             ))
             .size(20),
+            copy_link_button(&playlist.permalink_url),
         ])
         // Never let the text grow taller than the artwork.
         .height(100)
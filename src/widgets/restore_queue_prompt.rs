@@ -0,0 +1,47 @@
+use crate::Message;
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Element, Shadow, Theme, Vector, border};
+
+/// Offer to bring back the queue from the last run, naming the track it was
+/// on. MyApp floats it over the top of the page until it's answered.
+pub fn restore_queue_prompt<'a>(track_title: &'a str, queue_length: usize) -> Element<'a, Message> {
+    container(
+        row![
+            column![
+                text("Pick up where you left off?").size(14),
+                text(format!(
+                    "\"{}\" and {} more in your last queue",
+                    track_title,
+                    queue_length.saturating_sub(1)
+                ))
+                .size(13)
+                .style(text::secondary)
+                .shaping(text::Shaping::Auto),
+            ]
+            .spacing(2),
+            button(text("Restore")).on_press(Message::RestoreQueue),
+            button(text("Dismiss"))
+                .style(button::secondary)
+                .on_press(Message::DismissSavedQueue),
+        ]
+        .spacing(12)
+        .align_y(Alignment::Center),
+    )
+    .padding([10, 14])
+    .max_width(520)
+    .style(|theme: &Theme| {
+        let palette = theme.extended_palette();
+        container::Style {
+            background: Some(palette.background.strong.color.into()),
+            text_color: Some(palette.background.strong.text),
+            border: border::rounded(8),
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                offset: Vector::new(0.0, 2.0),
+                blur_radius: 8.0,
+            },
+            ..container::Style::default()
+        }
+    })
+    .into()
+}
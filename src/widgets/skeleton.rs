@@ -0,0 +1,43 @@
+use crate::Message;
+use iced::widget::{Column, Space, column, container, row};
+use iced::{Element, Length, Theme, border};
+
+/// Placeholder rows shown while a track list's first page loads
+const SKELETON_ROWS: usize = 6;
+
+/// Gray stand-ins shaped like track rows (artwork, user, title, duration), so
+/// a list that's loading reads as a list rather than a blank page
+pub fn track_list_skeleton<'a>() -> Element<'a, Message> {
+    (0..SKELETON_ROWS)
+        .fold(Column::new(), |col, i| {
+            // Vary the title width a little so the rows don't look stamped out
+            let title_width = 180.0 + (i % 3) as f32 * 60.0;
+            col.push(
+                row![
+                    placeholder(100.0, 100.0),
+                    column![
+                        placeholder(120.0, 18.0),
+                        placeholder(title_width, 14.0),
+                        placeholder(48.0, 14.0),
+                    ]
+                    .spacing(10),
+                    Space::new().width(Length::Fill),
+                ]
+                .spacing(10)
+                .padding(5),
+            )
+        })
+        .into()
+}
+
+fn placeholder<'a>(width: f32, height: f32) -> Element<'a, Message> {
+    container(Space::new())
+        .width(width)
+        .height(height)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.extended_palette().background.strong.color.into()),
+            border: border::rounded(4),
+            ..container::Style::default()
+        })
+        .into()
+}
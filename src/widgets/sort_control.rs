@@ -0,0 +1,15 @@
+use crate::Message;
+use crate::config::TrackSort;
+use iced::widget::{pick_list, row, text};
+use iced::{Alignment, Element};
+
+/// "Sort by" picker shown above the likes and feed lists
+pub fn sort_control<'a>(current: TrackSort) -> Element<'a, Message> {
+    row![
+        text("Sort by").size(14),
+        pick_list(TrackSort::ALL, Some(current), Message::SetTrackSort).text_size(14),
+    ]
+    .spacing(8)
+    .align_y(Alignment::Center)
+    .into()
+}
@@ -0,0 +1,26 @@
+use crate::Message;
+use iced::widget::{container, text};
+use iced::{Element, Shadow, Theme, Vector, border};
+
+/// Small rounded notice floated over the bottom of the page for transient
+/// errors and status changes. MyApp decides when it appears and expires.
+pub fn toast<'a>(message: &'a str) -> Element<'a, Message> {
+    container(text(message).size(14))
+        .padding([8, 14])
+        .max_width(480)
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Style {
+                background: Some(palette.background.strong.color.into()),
+                text_color: Some(palette.background.strong.text),
+                border: border::rounded(8),
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.4),
+                    offset: Vector::new(0.0, 2.0),
+                    blur_radius: 8.0,
+                },
+                ..container::Style::default()
+            }
+        })
+        .into()
+}
@@ -1,15 +1,19 @@
 use crate::Message;
 use crate::models::SoundCloudTrack;
-use crate::utilities::{DurationFormat, NumberFormat, get_asset_path};
+use crate::utilities::{DurationFormat, NumberFormat, placeholder_image, svg_asset};
+use crate::widgets;
 use iced::widget::{MouseArea, Row, Space, Svg, button, mouse_area, stack, svg, text};
 use iced::widget::{column, container, image, image::Handle, row};
-use iced::{Alignment, Color, Element, Length};
+use iced::{Alignment, Color, Element, Length, Theme};
 use std::time::Duration;
 
+#[allow(clippy::too_many_arguments)]
 pub fn get_track_widget<F, U, L>(
     track: &'_ SoundCloudTrack,
     image_handle: Option<Handle>,
     image_opacity: f32,
+    posted: Option<String>, // How and when it was shared, e.g. "reposted 3h ago"
+    is_current: bool,       // The track loaded in the player
     on_play: F,
     on_user: U,
     on_like: L,
@@ -24,37 +28,56 @@ where
     if let Some(handle) = image_handle {
         // Cross-fade the real artwork in over the placeholder.
         let artwork: Element<'_, Message> = stack![
-            image(get_asset_path("assets/icon.png"))
-                .width(100)
-                .height(100),
+            image(placeholder_image()).width(100).height(100),
             image(handle).width(100).height(100).opacity(image_opacity),
         ]
         .into();
         row = row.push(artwork);
     } else {
-        row = row.push(
-            image(get_asset_path("assets/icon.png"))
-                .width(100)
-                .height(100),
-        );
+        row = row.push(image(placeholder_image()).width(100).height(100));
     }
 
     let duration = Duration::from_millis(track.duration);
 
-    let title_text = if track.stream_url.is_some() {
-        text(track.title.clone()).shaping(text::Shaping::Auto)
+    let title_text: Element<'_, Message> = if is_current {
+        // Accent-coloured title behind a small equalizer glyph
+        row![
+            Svg::new(svg_asset("assets/playing.svg"))
+                .width(16)
+                .height(16)
+                .style(|theme: &Theme, _status| svg::Style {
+                    color: Some(theme.palette().primary),
+                }),
+            text(track.title.clone())
+                .shaping(text::Shaping::Auto)
+                .style(text::primary),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center)
+        .into()
+    } else if track.is_blocked() {
+        text(format!("{} (Blocked in your region)", track.title.clone()))
+            .shaping(text::Shaping::Auto)
+            .color(Color::from_rgb(1.0, 0.0, 0.0))
+            .into()
+    } else if track.is_playable() {
+        text(track.title.clone())
+            .shaping(text::Shaping::Auto)
+            .into()
     } else {
         text(format!("{} (Unavailable)", track.title.clone()))
             .shaping(text::Shaping::Auto)
             .color(Color::from_rgb(1.0, 0.0, 0.0))
+            .into()
     };
 
-    let meta_data = column!(
-        text(track.genre.clone()),
-        text(track.created_at[0..4].to_string().clone()),
-    )
-    .align_x(Alignment::End)
-    .padding(10);
+    let mut meta_data = column![].align_x(Alignment::End).padding(10);
+    if let Some(posted) = posted {
+        meta_data = meta_data.push(text(posted).style(text::secondary));
+    }
+    let meta_data = meta_data.push(text(track.genre.clone())).push(text(
+        track.created_at.get(0..4).unwrap_or_default().to_string(),
+    ));
 
     row = row.push(column![
         mouse_area(
@@ -67,38 +90,32 @@ where
         text(duration.format_as_mmss()),
         row![
             button(row![
-                Svg::new(get_asset_path("assets/heart.svg"))
+                Svg::new(svg_asset("assets/heart.svg"))
                     .width(20)
                     .height(20)
-                    .style(|_theme, _status| svg::Style {
-                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                    }),
-                text(track.favoritings_count.unwrap_or(0).format_compact_number())
-                    .color(Color::from_rgb(1.0, 1.0, 1.0)),
+                    .style(widgets::button_icon_style),
+                text(track.favoritings_count.unwrap_or(0).format_compact_number()),
             ])
             .on_press(on_like(track.clone())),
             button(row![
-                Svg::new(get_asset_path("assets/repost.svg"))
+                Svg::new(svg_asset("assets/repost.svg"))
                     .width(20)
                     .height(20)
-                    .style(|_theme, _status| svg::Style {
-                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                    }),
-                text(track.reposts_count.unwrap_or(0).format_compact_number())
-                    .color(Color::from_rgb(1.0, 1.0, 1.0)),
+                    .style(widgets::button_icon_style),
+                text(track.reposts_count.unwrap_or(0).format_compact_number()),
             ])
             .on_press(on_play(track.clone())),
             button(row![
-                Svg::new(get_asset_path("assets/play.svg"))
+                Svg::new(svg_asset("assets/play.svg"))
                     .width(20)
                     .height(20)
-                    .style(|_theme, _status| svg::Style {
-                        color: Some(Color::from_rgb(1.0, 1.0, 1.0)),
-                    }),
-                text(track.playback_count.unwrap_or(0).format_compact_number())
-                    .color(Color::from_rgb(1.0, 1.0, 1.0)),
+                    .style(widgets::button_icon_style),
+                text(track.playback_count.unwrap_or(0).format_compact_number()),
             ])
             .on_press(on_play(track.clone())),
+            button(text("+")).on_press(Message::AddToPlaylist(track.clone())),
+            button(text("i")).on_press(Message::ShowTrackDetails(track.clone())),
+            copy_link_button(&track.permalink_url),
         ]
         .spacing(5),
     ]);
@@ -109,3 +126,26 @@ where
 
     mouse_area(container(row.spacing(10).padding(5))).on_press(on_play(track.clone()))
 }
+
+/// "Play all" and "Shuffle all" for a page's track list; `on_play` gets
+/// whether to shuffle. Disabled when nothing in the list can play.
+pub fn play_all_buttons<'a>(enabled: bool, on_play: impl Fn(bool) -> Message) -> Row<'a, Message> {
+    row![
+        button(text("Play all")).on_press_maybe(enabled.then(|| on_play(false))),
+        button(text("Shuffle all"))
+            .style(button::secondary)
+            .on_press_maybe(enabled.then(|| on_play(true))),
+    ]
+    .spacing(6)
+}
+
+/// Small link icon button that copies `url` to the clipboard
+pub fn copy_link_button<'a>(url: &str) -> iced::widget::Button<'a, Message> {
+    button(
+        Svg::new(svg_asset("assets/link.svg"))
+            .width(20)
+            .height(20)
+            .style(widgets::button_icon_style),
+    )
+    .on_press(Message::CopyLink(url.to_string()))
+}
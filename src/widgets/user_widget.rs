@@ -1,8 +1,44 @@
 use crate::Message;
 use crate::models::SoundCloudUser;
-use crate::utilities::{NumberFormat, get_asset_path, truncate_string};
-use iced::widget::{MouseArea, Row, mouse_area, text};
+use crate::utilities::{NumberFormat, placeholder_image, truncate_string};
+use crate::widgets::copy_link_button;
+use iced::widget::{MouseArea, Row, mouse_area, row, text};
 use iced::widget::{column, container, image, image::Handle};
+use iced::{Alignment, Theme, border};
+
+/// "Verified"/"Pro" pills and the location, whichever the user has
+pub fn user_badges<'a>(user: &SoundCloudUser) -> Row<'a, Message> {
+    let pill = |label: &'a str| {
+        container(text(label).size(12))
+            .padding([2, 8])
+            .style(|theme: &Theme| {
+                let palette = theme.extended_palette();
+                container::Style {
+                    background: Some(palette.primary.weak.color.into()),
+                    text_color: Some(palette.primary.weak.text),
+                    border: border::rounded(999),
+                    ..container::Style::default()
+                }
+            })
+    };
+
+    let mut badges = row![].spacing(6).align_y(Alignment::Center);
+    if user.is_verified() {
+        badges = badges.push(pill("Verified"));
+    }
+    if user.is_pro() {
+        badges = badges.push(pill("Pro"));
+    }
+    if let Some(location) = user.location() {
+        badges = badges.push(
+            text(location)
+                .size(14)
+                .shaping(text::Shaping::Auto)
+                .style(text::secondary),
+        );
+    }
+    badges
+}
 
 pub fn get_user_widget<F>(
     user: &'_ SoundCloudUser,
@@ -18,11 +54,7 @@ where
     if let Some(handle) = image_handle {
         row = row.push(image(handle).width(100).height(100));
     } else {
-        row = row.push(
-            image(get_asset_path("assets/icon.png"))
-                .width(100)
-                .height(100),
-        );
+        row = row.push(image(placeholder_image()).width(100).height(100));
     }
 
     row = row.push(column![
@@ -34,6 +66,8 @@ where
             user.followers_count.unwrap_or(0).format_compact_number()
         ))
         .size(20),
+        user_badges(user),
+        copy_link_button(&user.permalink_url),
     ]);
 
     mouse_area(container(row.spacing(10).padding(5))).on_press(load_user(user.urn.clone()))
@@ -0,0 +1,112 @@
+use crate::Message;
+use iced::widget::canvas;
+use iced::widget::canvas::{Action, Frame, Geometry, Path, Program};
+use iced::{Color, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, mouse};
+
+struct VisualizerCanvas {
+    bars: Vec<f32>,
+    progress: f32,
+}
+
+impl Program<Message> for VisualizerCanvas {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let width = bounds.width;
+        let height = bounds.height;
+        let progress_x = width * self.progress;
+
+        if !self.bars.is_empty() {
+            let slot_width = width / self.bars.len() as f32;
+            let bar_width = (slot_width * 0.75).max(1.0);
+
+            // Bars grow up from the bottom, leaving room for the progress line
+            for (i, &level) in self.bars.iter().enumerate() {
+                let x = i as f32 * slot_width;
+                let bar_height = level * (height - 6.0);
+                let color = if x < progress_x {
+                    Color::from_rgb(0.34, 0.59, 0.97) // Blue
+                } else {
+                    Color::from_rgb(0.4, 0.42, 0.49) // Grey
+                };
+
+                frame.fill(
+                    &Path::rectangle(
+                        Point::new(x, height - 6.0 - bar_height),
+                        Size::new(bar_width, bar_height),
+                    ),
+                    color,
+                );
+            }
+        }
+
+        // Progress line along the bottom edge
+        frame.fill(
+            &Path::rectangle(Point::new(0.0, height - 3.0), Size::new(width, 3.0)),
+            Color::from_rgb(0.4, 0.42, 0.49),
+        );
+        frame.fill(
+            &Path::rectangle(Point::new(0.0, height - 3.0), Size::new(progress_x, 3.0)),
+            Color::from_rgb(0.34, 0.59, 0.97),
+        );
+
+        vec![frame.into_geometry()]
+    }
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> Option<Action<Message>> {
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
+            && let Some(position) = cursor.position_in(bounds)
+        {
+            let percent = (position.x / bounds.width * 100.0).clamp(0.0, 100.0);
+            return Some(Action::publish(Message::SeekToPosition(percent)).and_capture());
+        }
+
+        None
+    }
+
+    fn mouse_interaction(
+        &self,
+        _state: &Self::State,
+        bounds: Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> iced::mouse::Interaction {
+        if cursor.is_over(bounds) {
+            iced::mouse::Interaction::Pointer
+        } else {
+            iced::mouse::Interaction::default()
+        }
+    }
+}
+
+/// Creates a spectrum visualizer that doubles as a seekbar
+///
+/// # Arguments
+/// * `bars` - Band levels (0.0 to 1.0), refreshed on the UI tick
+/// * `progress` - Current playback progress (0.0 to 1.0)
+///
+/// # Returns
+/// A canvas widget that emits SeekToPosition messages when clicked
+pub fn get_visualizer_widget(bars: &[f32], progress: f32) -> Element<'static, Message> {
+    canvas(VisualizerCanvas {
+        bars: bars.to_vec(),
+        progress,
+    })
+    .width(Length::Fill)
+    .height(100)
+    .into()
+}
@@ -1,21 +1,36 @@
+use std::sync::LazyLock;
+
 use crate::Message;
 use iced::widget::canvas;
 use iced::widget::canvas::{Action, Frame, Geometry, Path, Program};
 use iced::{Color, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, mouse};
 
-struct WaveformCanvas {
-    peaks: Vec<f32>,
+/// Sine-wave stand-in drawn while a track has no peak data
+static PLACEHOLDER_PEAKS: LazyLock<Vec<f32>> = LazyLock::new(|| {
+    (0..200)
+        .map(|i| ((i as f32 / 10.0).sin().abs() + 0.2).min(1.0))
+        .collect()
+});
+
+struct WaveformCanvas<'a> {
+    peaks: &'a [f32], // Borrowed from the app state, so a redraw doesn't copy them
     progress: f32,
 }
 
-impl WaveformCanvas {
-    fn new(peaks: Vec<f32>, progress: f32) -> Self {
+impl<'a> WaveformCanvas<'a> {
+    fn new(peaks: &'a [f32], progress: f32) -> Self {
         Self { peaks, progress }
     }
 }
 
-impl Program<Message> for WaveformCanvas {
-    type State = ();
+/// Whether a drag-to-scrub is in progress
+#[derive(Default)]
+struct ScrubState {
+    dragging: bool,
+}
+
+impl Program<Message> for WaveformCanvas<'_> {
+    type State = ScrubState;
 
     fn draw(
         &self,
@@ -61,17 +76,34 @@ impl Program<Message> for WaveformCanvas {
 
     fn update(
         &self,
-        _state: &mut Self::State,
+        state: &mut Self::State,
         event: &Event,
         bounds: Rectangle,
         cursor: iced::mouse::Cursor,
     ) -> Option<Action<Message>> {
-        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
-            && let Some(position) = cursor.position_in(bounds)
-        {
-            // Calculate seek position as percentage
-            let percent = (position.x / bounds.width * 100.0).clamp(0.0, 100.0);
-            return Some(Action::publish(Message::SeekToPosition(percent)).and_capture());
+        // Scrub position as a percentage; keeps tracking past the edges mid-drag
+        let percent_at = |x: f32| ((x - bounds.x) / bounds.width * 100.0).clamp(0.0, 100.0);
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if cursor.is_over(bounds)
+                    && let Some(position) = cursor.position()
+                {
+                    state.dragging = true;
+                    return Some(
+                        Action::publish(Message::Scrub(percent_at(position.x))).and_capture(),
+                    );
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if state.dragging => {
+                return Some(Action::publish(Message::Scrub(percent_at(position.x))).and_capture());
+            }
+            // The seek itself waits for the release, so a drag costs one seek
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if state.dragging => {
+                state.dragging = false;
+                return Some(Action::publish(Message::ScrubRelease).and_capture());
+            }
+            _ => {}
         }
 
         None
@@ -79,11 +111,13 @@ impl Program<Message> for WaveformCanvas {
 
     fn mouse_interaction(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         bounds: Rectangle,
         cursor: iced::mouse::Cursor,
     ) -> iced::mouse::Interaction {
-        if cursor.is_over(bounds) {
+        if state.dragging {
+            iced::mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
             iced::mouse::Interaction::Pointer
         } else {
             iced::mouse::Interaction::default()
@@ -98,18 +132,11 @@ impl Program<Message> for WaveformCanvas {
 /// * `progress` - Current playback progress (0.0 to 1.0)
 ///
 /// # Returns
-/// A canvas widget that emits SeekToPosition messages when clicked
-pub fn get_waveform_widget(
-    waveform_peaks: Option<Vec<f32>>,
-    progress: f32,
-) -> Element<'static, Message> {
-    // Use real peak data if available, otherwise use dummy data
-    let peaks = waveform_peaks.unwrap_or_else(|| {
-        // Fallback to dummy sine wave if no peaks available
-        (0..200)
-            .map(|i| ((i as f32 / 10.0).sin().abs() + 0.2).min(1.0))
-            .collect()
-    });
+/// A canvas widget that emits Scrub messages while clicked or dragged and
+/// ScrubRelease when let go
+pub fn get_waveform_widget(waveform_peaks: Option<&[f32]>, progress: f32) -> Element<'_, Message> {
+    // Use real peak data if available, otherwise a dummy sine wave
+    let peaks = waveform_peaks.unwrap_or(&PLACEHOLDER_PEAKS);
 
     let waveform_canvas = WaveformCanvas::new(peaks, progress);
     canvas(waveform_canvas)